@@ -1,11 +1,15 @@
-use std::collections::HashMap;
-
 use cairo_rs::{
 	bigint,
 	hint_processor::hint_processor_definition::HintProcessor,
-	types::program::Program,
+	types::{
+		program::Program,
+		relocatable::{MaybeRelocatable, Relocatable},
+	},
 	vm::{
-		errors::{cairo_run_errors::CairoRunError, vm_errors::VirtualMachineError},
+		errors::{
+			cairo_run_errors::CairoRunError, runner_errors::RunnerError,
+			vm_errors::VirtualMachineError,
+		},
 		hook::Hooks,
 		runners::cairo_runner::CairoRunner,
 		vm_core::VirtualMachine,
@@ -15,8 +19,14 @@ use num_bigint::BigInt;
 use uuid::Uuid;
 
 use crate::{
-	hints::{output_buffer::EXECUTION_UUID_VAR_NAME, EXPECT_REVERT_FLAG, MOCK_CALL_KEY},
-	hooks::{HOOKS_VAR_NAME, MAX_STEPS_VAR_NAME},
+	hints::{
+		output_buffer::EXECUTION_UUID_VAR_NAME, resume_after_region, revert_region_ends,
+		ExpectedRevert, MockCalls, EXPECT_REVERT_FLAG, MOCK_CALL_KEY, PANIC_DATA_VAR_NAME,
+	},
+	hooks::{
+		Deadline, Stagnation, DEADLINE_VAR_NAME, HOOKS_VAR_NAME, MAX_STEPS_ERROR_PREFIX,
+		MAX_STEPS_VAR_NAME, STAGNATION_ERROR_PREFIX, STAGNATION_VAR_NAME, TIMEOUT_ERROR_PREFIX,
+	},
 };
 
 /// Execute a cairo program
@@ -24,22 +34,41 @@ use crate::{
 /// A `CairoRunner` and a `VirtualMachine` will be created to execute the given `Program`.
 /// Hint and `Hooks` (if any) will be applied by the `VirtualMachine`
 ///
+/// The `max_steps` and `deadline` (if any) limits are enforced by the `Hooks`, as well as the
+/// detection of the loops which do not change the state of the execution, if a
+/// `stagnation_window` is given (see `Stagnation`).
+///
+/// `args` are given to the entrypoint, after the builtins (ie: its implicit arguments).
+///
+/// A revert inside a region opened by `start_expect_revert` resumes the execution at the end of
+/// the region (see `resume_after_region`).
+///
+/// `mock_calls` are the calls mocked before the execution (eg: by a `setUp` entrypoint),
+/// they are updated with the calls mocked during the execution.
+///
 /// When no error is encountered, returns the `CairoRunner` and `VirtualMachine`.
 /// Otherwise, returns a `CairoRunError`
 ///
 /// `cairo_run` is the last step after cairo files have been listed and compiled.
 /// Each *test* functions will be executed by `cairo_run` with hooks and hints applied.
+#[allow(clippy::too_many_arguments)]
 pub fn cairo_run(
 	program: Program,
 	hint_processor: &mut dyn HintProcessor,
 	execution_uuid: Uuid,
 	opt_hooks: Option<Hooks>,
 	max_steps: u64,
+	opt_deadline: Option<Deadline>,
+	opt_stagnation_window: Option<usize>,
+	args: &[BigInt],
+	mock_calls: &mut MockCalls,
 ) -> Result<(CairoRunner, VirtualMachine), CairoRunError> {
+	let entrypoint = program.main.ok_or(RunnerError::MissingMain)?;
+	let region_ends = revert_region_ends(&program);
 	// 2023-01-06: FIXME: avoid hardcoded default layout & proof mode ?
 	let mut cairo_runner = CairoRunner::new(&program, "small", false)?;
 	let mut vm = VirtualMachine::new(program.prime, false, program.error_message_attributes);
-	let end = cairo_runner.initialize(&mut vm)?;
+	let end = initialize_with_args(&mut cairo_runner, &mut vm, entrypoint, args)?;
 
 	cairo_runner
 		.exec_scopes
@@ -47,20 +76,54 @@ pub fn cairo_run(
 	if let Some(hooks) = opt_hooks {
 		cairo_runner.exec_scopes.insert_value(HOOKS_VAR_NAME, hooks);
 		cairo_runner.exec_scopes.insert_value(MAX_STEPS_VAR_NAME, max_steps);
+		if let Some(deadline) = opt_deadline {
+			cairo_runner.exec_scopes.insert_value(DEADLINE_VAR_NAME, deadline);
+		}
+		if let Some(window) = opt_stagnation_window {
+			cairo_runner
+				.exec_scopes
+				.insert_value(STAGNATION_VAR_NAME, Stagnation::new(window));
+		}
 	}
 
 	// Init exec context for mock_call
-	let hashmap: HashMap<usize, BigInt> = HashMap::new();
-	cairo_runner.exec_scopes.insert_value(MOCK_CALL_KEY, hashmap);
+	cairo_runner.exec_scopes.insert_value(MOCK_CALL_KEY, mock_calls.clone());
 
-	let execution_result = cairo_runner.run_until_pc(end, &mut vm, hint_processor);
-	let should_revert = cairo_runner.exec_scopes.get_any_boxed_ref(EXPECT_REVERT_FLAG).is_ok();
+	let execution_result = loop {
+		let result = cairo_runner.run_until_pc(end, &mut vm, hint_processor);
+		match result {
+			Err(error) if !is_interrupted(&error) => {
+				let exec_scopes = &mut cairo_runner.exec_scopes;
+				match resume_after_region(&mut vm, exec_scopes, &region_ends, &error) {
+					Ok(true) => continue,
+					Ok(false) => break Err(error),
+					Err(mismatch) => break Err(mismatch),
+				}
+			},
+			result => break result,
+		}
+	};
+	if let Ok(updated_mock_calls) = cairo_runner.exec_scopes.get::<MockCalls>(MOCK_CALL_KEY) {
+		*mock_calls = updated_mock_calls;
+	}
+	let opt_expected_revert =
+		cairo_runner.exec_scopes.get::<ExpectedRevert>(EXPECT_REVERT_FLAG).ok();
+	let should_revert = opt_expected_revert.is_some();
+	let interrupted = matches!(&execution_result, Err(error) if is_interrupted(error));
 
 	match execution_result {
+		_ if interrupted => execution_result,
 		Ok(_) if should_revert => Err(VirtualMachineError::CustomHint(
 			EXPECT_REVERT_FLAG.to_string(),
 		)),
-		Err(_) if should_revert => Ok(()),
+		Err(error) => match opt_expected_revert {
+			Some(expected_revert) => {
+				let opt_panic_data =
+					cairo_runner.exec_scopes.get::<Vec<BigInt>>(PANIC_DATA_VAR_NAME).ok();
+				expected_revert.check(&error, opt_panic_data.as_deref())
+			},
+			None => Err(error),
+		},
 		_ => execution_result,
 	}
 	.map_err(CairoRunError::VirtualMachine)?;
@@ -72,3 +135,38 @@ pub fn cairo_run(
 
 	Ok((cairo_runner, vm))
 }
+
+/// Whether the execution was interrupted: a test running out of time or steps (or stuck in a loop)
+/// did not revert, it did not complete
+fn is_interrupted(error: &VirtualMachineError) -> bool {
+	matches!(
+		error,
+		VirtualMachineError::CustomHint(message)
+			if message.starts_with(TIMEOUT_ERROR_PREFIX)
+				|| message.starts_with(MAX_STEPS_ERROR_PREFIX)
+				|| message.starts_with(STAGNATION_ERROR_PREFIX)
+	)
+}
+
+/// Same as `CairoRunner::initialize`, with `args` pushed on the stack of the entrypoint
+fn initialize_with_args(
+	cairo_runner: &mut CairoRunner,
+	vm: &mut VirtualMachine,
+	entrypoint: usize,
+	args: &[BigInt],
+) -> Result<Relocatable, RunnerError> {
+	cairo_runner.initialize_builtins(vm)?;
+	cairo_runner.initialize_segments(vm, None);
+
+	let mut stack = Vec::new();
+	for (_, builtin_runner) in vm.get_builtin_runners() {
+		stack.append(&mut builtin_runner.initial_stack());
+	}
+	stack.extend(args.iter().cloned().map(MaybeRelocatable::from));
+
+	let return_fp = vm.add_memory_segment();
+	let end =
+		cairo_runner.initialize_function_entrypoint(vm, entrypoint, stack, return_fp.into())?;
+	cairo_runner.initialize_vm(vm)?;
+	Ok(end)
+}
@@ -0,0 +1,299 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+	fmt::{self, Display},
+	fs, io,
+	path::PathBuf,
+	time::Duration,
+};
+
+use cairo_rs::serde::deserialize_program::{deserialize_program_json, ProgramJson};
+use clap::{Args, ValueHint};
+use log::info;
+use serde::Serialize;
+use thiserror::Error;
+
+use super::{
+	list::path_is_valid_directory,
+	test::{
+		setup_hooks, test_single_entrypoint, TestCommandError, TestOptions, TestStatus,
+		DEFAULT_MAX_STEPS,
+	},
+	CommandExecution, EXIT_CODE_TEST_FAILURE,
+};
+
+use crate::{
+	compile::{compile_with_cache, Compiler, CAIRO_COMPILE_BINARY},
+	hints::{processor::setup_hint_processor, MockCalls},
+	io::test_files::{list_test_files, ListTestsFilesError},
+};
+
+/// Prefix of the benchmarked entrypoints
+pub const BENCH_ENTRYPOINT_PREFIX: &str = "bench_";
+/// Default number of runs of each benchmark
+pub const DEFAULT_BENCH_ITERATIONS: usize = 10;
+
+/// Bench command
+#[derive(Args, Debug)]
+pub struct BenchArgs {
+	/// Root path
+	#[clap(short, long, value_hint=ValueHint::DirPath, value_parser=path_is_valid_directory, default_value="./")]
+	pub root: PathBuf,
+	/// Number of runs of each benchmark (`bench_` entrypoints)
+	#[clap(short = 'n', long, default_value_t = DEFAULT_BENCH_ITERATIONS)]
+	pub iterations: usize,
+	/// Maximum number of steps of a single run
+	#[clap(short, long, default_value_t = DEFAULT_MAX_STEPS)]
+	pub max_steps: u64,
+	/// Name or path of the cairo compiler binary
+	#[clap(long, env = "CAIRO_COMPILE_BIN", default_value = CAIRO_COMPILE_BINARY)]
+	pub compiler: String,
+	/// Directories searched for the imported modules, several directories can be given by
+	/// repeating the option or by separating them with colons (eg: `--cairo-path lib:vendor`)
+	#[clap(
+		long,
+		env = "CAIRO_PATH",
+		value_name = "DIRS",
+		value_hint = ValueHint::DirPath,
+		value_delimiter = ':'
+	)]
+	pub cairo_path: Vec<PathBuf>,
+	/// Extra arguments passed to the cairo compiler (eg: `--compiler-args "--proof_mode"`)
+	#[clap(
+		long,
+		value_name = "ARGS",
+		allow_hyphen_values = true,
+		value_delimiter = ' '
+	)]
+	pub compiler_args: Vec<String>,
+	/// Glob matching the test files (default: `test_*.cairo` and `*_test.cairo`)
+	#[clap(long, value_name = "GLOB")]
+	pub test_glob: Option<String>,
+	/// Skip the files and directories matching this glob (matched against their name, or against
+	/// their path relative to `root` if it contains a `/`)
+	#[clap(long = "exclude", value_name = "GLOB")]
+	pub excludes: Vec<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum BenchCommandError {
+	#[error(transparent)]
+	ListFilesError(#[from] ListTestsFilesError),
+	#[error(transparent)]
+	TestCommandError(#[from] TestCommandError),
+}
+
+/// Minimum, median, mean and maximum of the values measured over the runs of a benchmark
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct Stats<T> {
+	pub min: T,
+	pub median: T,
+	pub mean: T,
+	pub max: T,
+}
+
+impl<T: Ord + Copy> Stats<T> {
+	/// Statistics of `values`, using `mean` to average them. None if there is no value.
+	fn new(mut values: Vec<T>, mean: impl Fn(&[T]) -> T) -> Option<Self> {
+		values.sort();
+		Some(Self {
+			min: *values.first()?,
+			median: values[values.len() / 2],
+			mean: mean(&values),
+			max: *values.last()?,
+		})
+	}
+}
+
+/// Results of the runs of a single benchmark
+#[derive(Debug, Serialize)]
+pub struct BenchResult {
+	/// Path to the original cairo file
+	pub path: PathBuf,
+	/// Name of the benchmark, empty if the file could not be compiled
+	pub entrypoint: String,
+	/// Number of completed runs
+	pub iterations: usize,
+	/// Number of steps executed by the VM, if every run completed
+	pub steps: Option<Stats<usize>>,
+	/// Time spent executing the entrypoint (compilation excluded), if every run completed
+	pub duration: Option<Stats<Duration>>,
+	/// Reason why a run failed, if any
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+/// Bench command output, the benchmarks being sorted by mean step count (descending)
+#[derive(Debug, Serialize, Default)]
+pub struct BenchOutput {
+	pub benches: Vec<BenchResult>,
+}
+
+impl BenchOutput {
+	/// `EXIT_CODE_TEST_FAILURE` if a benchmark could not be run, 0 otherwise
+	pub fn exit_code(&self) -> u8 {
+		if self.benches.iter().any(|bench| bench.error.is_some()) {
+			EXIT_CODE_TEST_FAILURE
+		} else {
+			0
+		}
+	}
+}
+
+impl Display for BenchOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(
+			f,
+			"{:<60} | {:>6} | {:<38} | time (min / median / mean / max)",
+			"bench", "runs", "steps (min / median / mean / max)"
+		)?;
+		for bench in &self.benches {
+			let name = match bench.entrypoint.as_str() {
+				"" => bench.path.display().to_string(),
+				entrypoint => format!("{}::{entrypoint}", bench.path.display()),
+			};
+			match (&bench.steps, &bench.duration, &bench.error) {
+				(_, _, Some(error)) => writeln!(f, "{name:<60} | error: {error}")?,
+				(Some(steps), Some(duration), None) => writeln!(
+					f,
+					"{name:<60} | {:>6} | {:<38} | {:?} / {:?} / {:?} / {:?}",
+					bench.iterations,
+					format!(
+						"{} / {} / {} / {}",
+						steps.min, steps.median, steps.mean, steps.max
+					),
+					duration.min,
+					duration.median,
+					duration.mean,
+					duration.max
+				)?,
+				_ => writeln!(f, "{name:<60} | {:>6} |", bench.iterations)?,
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Names of the benchmark entrypoints of a program, sorted
+fn list_bench_entrypoints(program: &ProgramJson) -> Vec<String> {
+	let mut entrypoints: Vec<String> = program
+		.identifiers
+		.iter()
+		.filter(|(_, identifier)| identifier.type_.as_deref() == Some("function"))
+		.filter_map(|(name, _)| name.strip_prefix("__main__."))
+		.filter(|name| name.starts_with(BENCH_ENTRYPOINT_PREFIX))
+		.map(String::from)
+		.collect();
+	entrypoints.sort();
+	entrypoints
+}
+
+/// Compile a cairo file (unless it is cached) and deserialize its compiled program
+fn load_program(
+	path: &PathBuf,
+	root: &PathBuf,
+	compiler: &Compiler,
+) -> Result<ProgramJson, TestCommandError> {
+	let (path_to_compiled, _) = compile_with_cache(path, root, compiler)?;
+	let file = fs::File::open(&path_to_compiled)?;
+	Ok(deserialize_program_json(io::BufReader::new(file))?)
+}
+
+/// Run a benchmark `iterations` times, stopping at the first run which does not succeed
+fn run_bench(
+	program: &ProgramJson,
+	path: &PathBuf,
+	entrypoint: &str,
+	iterations: usize,
+	options: &TestOptions,
+) -> Result<BenchResult, TestCommandError> {
+	let mut steps = Vec::with_capacity(iterations);
+	let mut durations = Vec::with_capacity(iterations);
+	let mut error = None;
+	for _ in 0..iterations {
+		let result = test_single_entrypoint(
+			program.clone(),
+			entrypoint,
+			&mut setup_hint_processor(),
+			Some(setup_hooks()),
+			options,
+			&[],
+			&mut MockCalls::new(),
+		)?;
+		match (result.success, result.resources) {
+			(TestStatus::SUCCESS, Some(resources)) => {
+				steps.push(resources.n_steps);
+				durations.push(result.duration);
+			},
+			_ => {
+				error = Some(result.error.unwrap_or_else(|| String::from("run did not complete")));
+				break
+			},
+		}
+	}
+
+	let completed = steps.len();
+	let failed = error.is_some();
+	Ok(BenchResult {
+		path: path.clone(),
+		entrypoint: entrypoint.to_string(),
+		iterations: completed,
+		steps: Stats::new(steps, |steps| steps.iter().sum::<usize>() / steps.len())
+			.filter(|_| !failed),
+		duration: Stats::new(durations, |durations| {
+			durations.iter().sum::<Duration>() / durations.len() as u32
+		})
+		.filter(|_| !failed),
+		error,
+	})
+}
+
+impl CommandExecution<BenchOutput, BenchCommandError> for BenchArgs {
+	fn exec(&self) -> Result<BenchOutput, BenchCommandError> {
+		let compiler = Compiler {
+			binary: self.compiler.clone(),
+			cairo_path: self.cairo_path.clone(),
+			args: self.compiler_args.clone(),
+		};
+		let options = TestOptions {
+			max_steps: self.max_steps,
+			..Default::default()
+		};
+
+		let mut output = BenchOutput::default();
+		for path in list_test_files(&self.root, self.test_glob.as_deref(), &self.excludes)? {
+			// The file is compiled (or found in the cache) before any run, so that only the
+			// executions are timed. A file failing to compile does not prevent the others from
+			// being benchmarked.
+			let program = match load_program(&path, &self.root, &compiler) {
+				Ok(program) => program,
+				Err(error) => {
+					output.benches.push(BenchResult {
+						path,
+						entrypoint: String::new(),
+						iterations: 0,
+						steps: None,
+						duration: None,
+						error: Some(error.to_string()),
+					});
+					continue
+				},
+			};
+			for entrypoint in list_bench_entrypoints(&program) {
+				info!("Benchmarking {}::{entrypoint}", path.display());
+				output.benches.push(run_bench(
+					&program,
+					&path,
+					&entrypoint,
+					self.iterations,
+					&options,
+				)?);
+			}
+		}
+
+		let mean_steps = |bench: &BenchResult| bench.steps.map(|steps| steps.mean);
+		output.benches.sort_by(|a, b| mean_steps(b).cmp(&mean_steps(a)));
+		Ok(output)
+	}
+}
@@ -0,0 +1,26 @@
+func count_down(n: felt) {
+    if (n == 0) {
+        return ();
+    }
+    count_down(n - 1);
+    return ();
+}
+
+func bench_short_loop() {
+    count_down(10);
+    return ();
+}
+
+func bench_long_loop() {
+    count_down(100);
+    return ();
+}
+
+func bench_failing() {
+    assert 1 = 2;
+    return ();
+}
+
+func test_not_benchmarked() {
+    return ();
+}
@@ -0,0 +1,125 @@
+use std::{fs, path::PathBuf, time::Duration};
+
+use uuid::Uuid;
+
+use super::{BenchArgs, BenchResult, Stats, DEFAULT_BENCH_ITERATIONS};
+use crate::{
+	cli::commands::{test::DEFAULT_MAX_STEPS, CommandExecution, EXIT_CODE_TEST_FAILURE},
+	compile::CAIRO_COMPILE_BINARY,
+};
+
+#[test]
+fn stats_are_computed_over_sorted_values() {
+	let stats = Stats::new(vec![5, 1, 3, 9], |values| {
+		values.iter().sum::<usize>() / values.len()
+	});
+
+	assert_eq!(
+		Some(Stats {
+			min: 1,
+			median: 5,
+			mean: 4,
+			max: 9,
+		}),
+		stats
+	);
+	assert_eq!(None, Stats::new(Vec::<usize>::new(), |_| 0));
+}
+
+#[test]
+fn benches_are_sorted_by_mean_step_count() {
+	let args = BenchArgs {
+		root: PathBuf::from("src/cli/commands/bench/test_cairo_programs"),
+		iterations: 3,
+		max_steps: DEFAULT_MAX_STEPS,
+		compiler: CAIRO_COMPILE_BINARY.to_string(),
+		cairo_path: Vec::new(),
+		compiler_args: Vec::new(),
+		test_glob: None,
+		excludes: Vec::new(),
+	};
+	let output = args.exec().unwrap();
+
+	let names: Vec<&str> = output.benches.iter().map(|bench| bench.entrypoint.as_str()).collect();
+	assert_eq!(
+		vec!["bench_long_loop", "bench_short_loop", "bench_failing"],
+		names
+	);
+	let long_loop = &output.benches[0];
+	assert_eq!(3, long_loop.iterations);
+	let steps = long_loop.steps.expect("Should have completed");
+	assert!(steps.min <= steps.median && steps.median <= steps.max);
+	assert!(output.benches[2].error.is_some());
+	assert_eq!(EXIT_CODE_TEST_FAILURE, output.exit_code());
+}
+
+#[test]
+fn compilation_failure_only_fails_its_file() {
+	let root = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	fs::create_dir_all(&root).unwrap();
+	fs::copy(
+		"src/cli/commands/bench/test_cairo_programs/test_bench.cairo",
+		root.join("test_bench.cairo"),
+	)
+	.unwrap();
+	fs::write(
+		root.join("test_syntax_error.cairo"),
+		"func bench_syntax_error( {\n    return ();\n}\n",
+	)
+	.unwrap();
+	let args = BenchArgs {
+		root: root.clone(),
+		iterations: 1,
+		max_steps: DEFAULT_MAX_STEPS,
+		compiler: CAIRO_COMPILE_BINARY.to_string(),
+		cairo_path: Vec::new(),
+		compiler_args: Vec::new(),
+		test_glob: None,
+		excludes: Vec::new(),
+	};
+	let output = args.exec().unwrap();
+
+	let failing_file = output
+		.benches
+		.iter()
+		.find(|bench| bench.path.ends_with("test_syntax_error.cairo"))
+		.expect("Should contain the file failing to compile");
+	assert!(failing_file.entrypoint.is_empty());
+	assert!(failing_file.error.is_some());
+	assert!(output
+		.benches
+		.iter()
+		.any(|bench| bench.entrypoint == "bench_short_loop" && bench.steps.is_some()));
+	fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn output_can_display_as_table() {
+	let output = super::BenchOutput {
+		benches: vec![BenchResult {
+			path: PathBuf::from("test_foo.cairo"),
+			entrypoint: String::from("bench_foo"),
+			iterations: DEFAULT_BENCH_ITERATIONS,
+			steps: Some(Stats {
+				min: 10,
+				median: 10,
+				mean: 11,
+				max: 15,
+			}),
+			duration: Some(Stats {
+				min: Duration::from_millis(1),
+				median: Duration::from_millis(2),
+				mean: Duration::from_millis(2),
+				max: Duration::from_millis(3),
+			}),
+			error: None,
+		}],
+	};
+
+	let display = output.to_string();
+	let row = display.lines().nth(1).expect("Should contain a row");
+
+	assert!(row.starts_with("test_foo.cairo::bench_foo "));
+	assert!(row.contains("| 10 / 10 / 11 / 15 "));
+	assert!(row.ends_with("| 1ms / 2ms / 2ms / 3ms"));
+}
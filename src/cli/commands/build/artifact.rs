@@ -0,0 +1,91 @@
+use clap::ValueEnum;
+use serde_json::{json, Value};
+
+/// Shape of the compiled files written by the build command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ArtifactFormat {
+	/// The output of the compiler, as is
+	Program,
+	/// The program without its debug information, on a single line
+	Compact,
+	/// The program wrapped in a (Cairo 0) Starknet contract class, as read by tools such as
+	/// starknet.py, with an empty abi and no entry point
+	ContractClass,
+}
+
+impl ArtifactFormat {
+	/// Convert the output of the compiler to this format
+	pub fn convert(self, compiled: &str) -> Result<String, serde_json::Error> {
+		match self {
+			Self::Program => Ok(compiled.to_string()),
+			Self::Compact => {
+				let mut program: Value = serde_json::from_str(compiled)?;
+				if let Some(debug_info) = program.get_mut("debug_info") {
+					*debug_info = Value::Null;
+				}
+				serde_json::to_string(&program)
+			},
+			Self::ContractClass => {
+				let program: Value = serde_json::from_str(compiled)?;
+				serde_json::to_string_pretty(&json!({
+					"abi": [],
+					"entry_points_by_type": {
+						"CONSTRUCTOR": [],
+						"EXTERNAL": [],
+						"L1_HANDLER": [],
+					},
+					"program": program,
+				}))
+			},
+		}
+	}
+
+	/// The program contained in an artifact of this format
+	pub fn program(self, artifact: &Value) -> Option<&Value> {
+		match self {
+			Self::Program | Self::Compact => Some(artifact),
+			Self::ContractClass => artifact.get("program"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::compile::{compile, Compiler};
+	use cairo_rs::serde::deserialize_program::deserialize_program_json;
+	use std::{fs, path::PathBuf};
+
+	#[test]
+	fn every_format_contains_a_valid_program() {
+		let compiled = compile(
+			&PathBuf::from("test_cairo_contracts/test_valid_program.cairo"),
+			&PathBuf::from("test_cairo_contracts"),
+			&Compiler::default(),
+		)
+		.unwrap();
+		let compiled = fs::read_to_string(compiled).unwrap();
+
+		for format in ArtifactFormat::value_variants() {
+			let artifact: Value =
+				serde_json::from_str(&format.convert(&compiled).unwrap()).unwrap();
+			let program = format.program(&artifact).expect("Should contain the program");
+
+			let bytes = serde_json::to_vec(program).unwrap();
+			assert!(
+				deserialize_program_json(bytes.as_slice()).is_ok(),
+				"{format:?}"
+			);
+		}
+	}
+
+	#[test]
+	fn compact_format_drops_the_debug_info() {
+		let compiled = r#"{"data": [], "debug_info": {"instruction_locations": {}}}"#;
+
+		assert_eq!(
+			r#"{"data":[],"debug_info":null}"#,
+			ArtifactFormat::Compact.convert(compiled).unwrap()
+		);
+	}
+}
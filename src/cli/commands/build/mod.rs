@@ -0,0 +1,228 @@
+mod artifact;
+#[cfg(test)]
+mod tests;
+
+use std::{
+	fmt::{self, Display},
+	fs, io,
+	path::{Path, PathBuf},
+	time::{Duration, Instant},
+};
+
+use clap::{Args, ValueHint};
+use colored::Colorize;
+use log::info;
+use serde::Serialize;
+use thiserror::Error;
+
+pub use artifact::ArtifactFormat;
+
+use super::{list::path_is_valid_directory, CommandExecution, EXIT_CODE_TEST_FAILURE};
+
+use crate::{
+	compile::{
+		cache::{self, CacheStatus},
+		compile_with_cache, Compiler, CAIRO_COMPILE_BINARY,
+	},
+	io::test_files::{list_test_files, ListTestsFilesError},
+};
+
+/// Default directory the compiled files are written to
+pub const DEFAULT_BUILD_DIR: &str = "build";
+
+/// Build command
+#[derive(Args, Debug)]
+pub struct BuildArgs {
+	/// Root path
+	#[clap(short, long, value_hint=ValueHint::DirPath, value_parser=path_is_valid_directory, default_value="./")]
+	pub root: PathBuf,
+	/// Directory the compiled files are written to, at the path of their cairo file relatively to
+	/// `root`
+	#[clap(short, long, value_name = "DIR", value_hint = ValueHint::DirPath, default_value = DEFAULT_BUILD_DIR)]
+	pub out: PathBuf,
+	/// Recompile every file, even the ones whose compiled version in the cache is up to date
+	#[clap(long)]
+	pub no_cache: bool,
+	/// Name or path of the cairo compiler binary
+	#[clap(long, env = "CAIRO_COMPILE_BIN", default_value = CAIRO_COMPILE_BINARY)]
+	pub compiler: String,
+	/// Directories searched for the imported modules, several directories can be given by
+	/// repeating the option or by separating them with colons (eg: `--cairo-path lib:vendor`)
+	#[clap(
+		long,
+		env = "CAIRO_PATH",
+		value_name = "DIRS",
+		value_hint = ValueHint::DirPath,
+		value_delimiter = ':'
+	)]
+	pub cairo_path: Vec<PathBuf>,
+	/// Extra arguments passed to the cairo compiler (eg: `--compiler-args "--proof_mode"`)
+	#[clap(
+		long,
+		value_name = "ARGS",
+		allow_hyphen_values = true,
+		value_delimiter = ' '
+	)]
+	pub compiler_args: Vec<String>,
+	/// Skip the files and directories matching this glob (matched against their name, or against
+	/// their path relative to `root` if it contains a `/`)
+	#[clap(long = "exclude", value_name = "GLOB")]
+	pub excludes: Vec<String>,
+	/// Shape of the written files: the output of the compiler as is, without its debug
+	/// information, or wrapped in a Starknet contract class
+	#[clap(long, value_enum, value_name = "FORMAT", default_value_t = ArtifactFormat::Program)]
+	pub artifact_format: ArtifactFormat,
+}
+
+#[derive(Error, Debug)]
+pub enum BuildCommandError {
+	#[error(transparent)]
+	ListFilesError(#[from] ListTestsFilesError),
+	#[error("Failed to create the output directory {0}: {1}")]
+	OutputDir(String, io::Error),
+}
+
+/// Result of the compilation of a single cairo file
+#[derive(Debug, Serialize)]
+pub struct BuildResult {
+	/// Path to the original cairo file
+	pub path: PathBuf,
+	/// Path to the compiled file written to the output directory, if the compilation succeeded
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub artifact: Option<PathBuf>,
+	/// Whether the compiled file was taken from the cache, rather than recompiled
+	pub cached: bool,
+	/// Time spent compiling the file (or looking it up in the cache)
+	pub duration: Duration,
+	/// Reason why the file could not be compiled, if any
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+/// Build command output, the files being sorted by path
+#[derive(Debug, Serialize, Default)]
+pub struct BuildOutput {
+	pub files: Vec<BuildResult>,
+	/// Total time spent building the files
+	pub duration: Duration,
+}
+
+impl BuildOutput {
+	/// `EXIT_CODE_TEST_FAILURE` if a file could not be compiled, 0 otherwise
+	pub fn exit_code(&self) -> u8 {
+		if self.files.iter().any(|file| file.error.is_some()) {
+			EXIT_CODE_TEST_FAILURE
+		} else {
+			0
+		}
+	}
+}
+
+impl Display for BuildOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for file in &self.files {
+			match (&file.artifact, &file.error) {
+				(_, Some(error)) => writeln!(
+					f,
+					"[{}] {}\nError: {error}\n",
+					"FAILED".red(),
+					file.path.display()
+				)?,
+				(Some(artifact), None) => writeln!(
+					f,
+					"[{}] {} -> {} ({:?}{})",
+					"OK".green(),
+					file.path.display(),
+					artifact.display(),
+					file.duration,
+					if file.cached { ", cached" } else { "" }
+				)?,
+				(None, None) => (),
+			}
+		}
+		let failed = self.files.iter().filter(|file| file.error.is_some()).count();
+		let cached = self.files.iter().filter(|file| file.error.is_none() && file.cached).count();
+		let summary = format!(
+			"{} file(s) compiled ({cached} served from the cache), {failed} failed in {:.1}s",
+			self.files.len() - failed,
+			self.duration.as_secs_f64()
+		);
+		if failed == 0 {
+			writeln!(f, "{}", summary.green())
+		} else {
+			writeln!(f, "{}", summary.red())
+		}
+	}
+}
+
+impl BuildArgs {
+	/// Path of the compiled file of the cairo file at `path` in the output directory
+	fn artifact_path(&self, path: &Path) -> PathBuf {
+		self.out
+			.join(path.strip_prefix(&self.root).unwrap_or(path))
+			.with_extension("json")
+	}
+
+	/// Compile a cairo file (unless it is cached) and write its compiled file to the output
+	/// directory, in the `artifact_format`.
+	///
+	/// Returns the path of the copy, and whether the compiled file was taken from the cache
+	fn build_file(&self, path: &PathBuf, compiler: &Compiler) -> Result<(PathBuf, bool), String> {
+		if self.no_cache {
+			// The entry is recompiled, and written back to the cache for the next runs
+			cache::invalidate_cache(path, &self.root).map_err(|e| e.to_string())?;
+		}
+		let (path_to_compiled, cache_status) =
+			compile_with_cache(path, &self.root, compiler).map_err(|e| e.to_string())?;
+
+		let artifact = self.artifact_path(path);
+		if let Some(parent) = artifact.parent() {
+			fs::create_dir_all(parent)
+				.map_err(|e| format!("failed to create directory '{}': {e}", parent.display()))?;
+		}
+		let compiled = fs::read_to_string(&path_to_compiled)
+			.map_err(|e| format!("failed to read '{}': {e}", path_to_compiled.display()))?;
+		let content = self.artifact_format.convert(&compiled).map_err(|e| {
+			format!(
+				"invalid compiled file '{}': {e}",
+				path_to_compiled.display()
+			)
+		})?;
+		fs::write(&artifact, content)
+			.map_err(|e| format!("failed to write '{}': {e}", artifact.display()))?;
+		Ok((artifact, matches!(cache_status, CacheStatus::Cached(_))))
+	}
+}
+
+impl CommandExecution<BuildOutput, BuildCommandError> for BuildArgs {
+	fn exec(&self) -> Result<BuildOutput, BuildCommandError> {
+		let start = Instant::now();
+		let compiler = Compiler {
+			binary: self.compiler.clone(),
+			cairo_path: self.cairo_path.clone(),
+			args: self.compiler_args.clone(),
+		};
+		fs::create_dir_all(&self.out)
+			.map_err(|e| BuildCommandError::OutputDir(self.out.display().to_string(), e))?;
+
+		let mut output = BuildOutput::default();
+		for path in list_test_files(&self.root, Some("*.cairo"), &self.excludes)? {
+			info!("Building {}", path.display());
+			let file_start = Instant::now();
+			let (artifact, cached, error) = match self.build_file(&path, &compiler) {
+				Ok((artifact, cached)) => (Some(artifact), cached, None),
+				Err(error) => (None, false, Some(error)),
+			};
+			output.files.push(BuildResult {
+				path,
+				artifact,
+				cached,
+				duration: file_start.elapsed(),
+				error,
+			});
+		}
+
+		output.duration = start.elapsed();
+		Ok(output)
+	}
+}
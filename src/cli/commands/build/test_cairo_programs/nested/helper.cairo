@@ -0,0 +1,3 @@
+func double(x: felt) -> felt {
+    return x * 2;
+}
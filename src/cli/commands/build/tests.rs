@@ -0,0 +1,85 @@
+use std::{fs, path::PathBuf};
+
+use uuid::Uuid;
+
+use super::{ArtifactFormat, BuildArgs};
+use crate::{
+	cli::commands::{CommandExecution, EXIT_CODE_TEST_FAILURE},
+	compile::CAIRO_COMPILE_BINARY,
+};
+
+fn build_args(out: PathBuf) -> BuildArgs {
+	BuildArgs {
+		root: PathBuf::from("src/cli/commands/build/test_cairo_programs"),
+		out,
+		no_cache: false,
+		compiler: CAIRO_COMPILE_BINARY.to_string(),
+		cairo_path: Vec::new(),
+		compiler_args: Vec::new(),
+		excludes: Vec::new(),
+		artifact_format: ArtifactFormat::Program,
+	}
+}
+
+#[test]
+fn compiled_files_are_written_to_the_output_directory() {
+	let out = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	let output = build_args(out.clone()).exec().unwrap();
+
+	let paths: Vec<PathBuf> = output.files.iter().map(|file| file.path.clone()).collect();
+	assert_eq!(
+		vec![
+			PathBuf::from("src/cli/commands/build/test_cairo_programs/invalid.cairo"),
+			PathBuf::from("src/cli/commands/build/test_cairo_programs/nested/helper.cairo"),
+			PathBuf::from("src/cli/commands/build/test_cairo_programs/valid.cairo"),
+		],
+		paths
+	);
+	assert!(output.files[0].error.is_some());
+	assert_eq!(
+		Some(out.join("nested/helper.json")),
+		output.files[1].artifact
+	);
+	assert_eq!(Some(out.join("valid.json")), output.files[2].artifact);
+	assert!(out.join("nested/helper.json").is_file());
+	assert!(out.join("valid.json").is_file());
+	assert_eq!(EXIT_CODE_TEST_FAILURE, output.exit_code());
+	assert!(
+		output.to_string().contains("2 file(s) compiled"),
+		"{output}"
+	);
+	fs::remove_dir_all(out).unwrap();
+}
+
+#[test]
+fn no_cache_recompiles_the_cached_files() {
+	let out = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	let mut args = build_args(out.clone());
+	args.excludes = vec![String::from("invalid.cairo")];
+	args.exec().unwrap();
+
+	let output = args.exec().unwrap();
+	assert!(output.files.iter().all(|file| file.cached));
+	assert_eq!(0, output.exit_code());
+
+	args.no_cache = true;
+	let output = args.exec().unwrap();
+	assert!(output.files.iter().all(|file| !file.cached && file.artifact.is_some()));
+	fs::remove_dir_all(out).unwrap();
+}
+
+#[test]
+fn artifacts_are_written_in_the_requested_format() {
+	let out = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	let mut args = build_args(out.clone());
+	args.excludes = vec![String::from("invalid.cairo")];
+	args.artifact_format = ArtifactFormat::ContractClass;
+	let output = args.exec().unwrap();
+	assert_eq!(0, output.exit_code(), "{output}");
+
+	let artifact: serde_json::Value =
+		serde_json::from_str(&fs::read_to_string(out.join("valid.json")).unwrap()).unwrap();
+	assert_eq!(Some(&serde_json::json!([])), artifact.get("abi"));
+	assert!(artifact["program"].get("data").is_some());
+	fs::remove_dir_all(out).unwrap();
+}
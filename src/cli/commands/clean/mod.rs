@@ -1,22 +1,36 @@
 #[cfg(test)]
 mod tests;
 
-use std::{fmt::Display, fs, io, path::PathBuf};
+use std::{
+	fmt::Display,
+	fs, io,
+	path::{Path, PathBuf},
+	time::{Duration, SystemTime},
+};
 
 use clap::Args;
 use serde::Serialize;
 
 use thiserror::Error;
+use walkdir::WalkDir;
 
 use super::CommandExecution;
 
 use crate::compile::cache;
 #[derive(Args, Debug)]
-pub struct CleanArgs {}
+pub struct CleanArgs {
+	/// Only remove the cached files older than this duration (ie: 30s, 15m, 12h, 7d)
+	#[clap(long, value_name = "DURATION", value_parser = parse_duration)]
+	pub max_age: Option<Duration>,
+}
 
 #[derive(Debug, Serialize, PartialEq)]
 pub struct CleanOutput {
 	pub dirs: Vec<(PathBuf, bool)>,
+	/// Number of files removed
+	pub removed_files: usize,
+	/// Total size of the removed files, in bytes
+	pub reclaimed_bytes: u64,
 }
 
 #[derive(Error, Debug)]
@@ -25,6 +39,10 @@ pub enum CleanCommandError {
 	CacheDirNotSupported(#[from] cache::CacheDirNotSupported),
 	#[error("Cannot remove directory {dir}: {err}")]
 	DirDeletion { dir: String, err: io::Error },
+	#[error("Cannot remove file {file}: {err}")]
+	FileDeletion { file: String, err: io::Error },
+	#[error("Refusing to clean {0}: it is not a cache directory of cairo-foundry")]
+	UnexpectedDirectory(String),
 }
 
 impl Display for CleanOutput {
@@ -36,26 +54,131 @@ impl Display for CleanOutput {
 				writeln!(f, "not found: {}", dir.display())?;
 			}
 		}
+		writeln!(
+			f,
+			"Removed {} files, reclaimed {}",
+			self.removed_files,
+			format_size(self.reclaimed_bytes)
+		)?;
 		writeln!(f, "Cache cleaned successfully.")
 	}
 }
 
-fn remove_dir_all_if_exists(dir: &PathBuf) -> Result<bool, CleanCommandError> {
-	if dir.exists() {
+/// Format a size in bytes with a binary unit (ie: 1.5 KiB)
+fn format_size(bytes: u64) -> String {
+	const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+	if bytes < 1024 {
+		return format!("{bytes} B")
+	}
+	let mut size = bytes as f64;
+	let mut unit = "B";
+	for next_unit in UNITS {
+		if size < 1024.0 {
+			break
+		}
+		size /= 1024.0;
+		unit = next_unit;
+	}
+	format!("{size:.1} {unit}")
+}
+
+/// Parse a duration given as a number followed by an optional unit: `s` (default), `m`, `h` or
+/// `d`
+pub fn parse_duration(duration: &str) -> Result<Duration, String> {
+	let duration = duration.trim();
+	let (value, seconds_per_unit) = match duration.chars().last() {
+		Some('s') => (&duration[..duration.len() - 1], 1),
+		Some('m') => (&duration[..duration.len() - 1], 60),
+		Some('h') => (&duration[..duration.len() - 1], 60 * 60),
+		Some('d') => (&duration[..duration.len() - 1], 24 * 60 * 60),
+		_ => (duration, 1),
+	};
+	value
+		.parse::<u64>()
+		.ok()
+		.and_then(|value| value.checked_mul(seconds_per_unit))
+		.map(Duration::from_secs)
+		.ok_or_else(|| format!("\"{duration}\" is not a valid duration"))
+}
+
+/// Make sure `dir` is one of the directories cairo-foundry creates in `cache_dir`,
+/// and not a symbolic link pointing elsewhere
+fn ensure_is_cache_subdir(cache_dir: &Path, dir: &Path) -> Result<(), CleanCommandError> {
+	let is_expected_name = dir.file_name().map_or(false, |name| {
+		name == cache::CAIRO_FOUNDRY_CACHE_DIR || name == cache::CAIRO_FOUNDRY_COMPILED_CONTRACT_DIR
+	});
+	let is_symlink = fs::symlink_metadata(dir).map_or(false, |metadata| metadata.is_symlink());
+	if dir.parent() != Some(cache_dir) || !is_expected_name || is_symlink {
+		return Err(CleanCommandError::UnexpectedDirectory(
+			dir.display().to_string(),
+		))
+	}
+	Ok(())
+}
+
+/// Files removed from a directory
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Removed {
+	files: usize,
+	bytes: u64,
+}
+
+/// Remove the files of `dir` which have not been modified for `max_age` (all of them if None).
+/// The directory itself is only removed without `max_age`, it is kept otherwise (even if none of
+/// its files is left).
+///
+/// Returns None if the directory does not exist
+fn clean_dir(
+	dir: &PathBuf,
+	max_age: Option<Duration>,
+) -> Result<Option<Removed>, CleanCommandError> {
+	if !dir.exists() {
+		return Ok(None)
+	}
+
+	let now = SystemTime::now();
+	let mut removed = Removed::default();
+	// Symbolic links are not followed, only the content of `dir` is walked
+	for entry in WalkDir::new(dir).into_iter().filter_map(Result::ok) {
+		let metadata = match entry.metadata() {
+			Ok(metadata) if metadata.is_file() => metadata,
+			_ => continue,
+		};
+		let age = metadata
+			.modified()
+			.ok()
+			.and_then(|modified| now.duration_since(modified).ok())
+			.unwrap_or_default();
+		if max_age.map_or(false, |max_age| age < max_age) {
+			continue
+		}
+
+		fs::remove_file(entry.path()).map_err(|err| CleanCommandError::FileDeletion {
+			file: entry.path().display().to_string(),
+			err,
+		})?;
+		removed.files += 1;
+		removed.bytes += metadata.len();
+	}
+
+	if max_age.is_none() {
 		fs::remove_dir_all(dir).map_err(|err| CleanCommandError::DirDeletion {
 			dir: dir.as_path().display().to_string(),
 			err,
 		})?;
-		return Ok(true)
 	}
-	Ok(false)
+	Ok(Some(removed))
 }
 
 impl CommandExecution<CleanOutput, CleanCommandError> for CleanArgs {
 	fn exec(&self) -> Result<CleanOutput, CleanCommandError> {
 		let cache_dir = cache::cache_dir()?;
 
-		let mut dirs: Vec<(PathBuf, bool)> = Vec::new();
+		let mut output = CleanOutput {
+			dirs: Vec::new(),
+			removed_files: 0,
+			reclaimed_bytes: 0,
+		};
 
 		let paths_to_clean = [
 			cache::CAIRO_FOUNDRY_CACHE_DIR,
@@ -64,10 +187,15 @@ impl CommandExecution<CleanOutput, CleanCommandError> for CleanArgs {
 
 		for path in paths_to_clean.iter() {
 			let dir = cache_dir.join(path);
-			let deleted = remove_dir_all_if_exists(&dir)?;
-			dirs.push((dir, deleted));
+			ensure_is_cache_subdir(&cache_dir, &dir)?;
+			let removed = clean_dir(&dir, self.max_age)?;
+			if let Some(removed) = &removed {
+				output.removed_files += removed.files;
+				output.reclaimed_bytes += removed.bytes;
+			}
+			output.dirs.push((dir, removed.is_some()));
 		}
 
-		Ok(CleanOutput { dirs })
+		Ok(output)
 	}
 }
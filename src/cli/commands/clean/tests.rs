@@ -1,9 +1,12 @@
 #[cfg(test)]
-use std::{fs, path::PathBuf};
+use std::{fs, path::PathBuf, time::Duration};
 
 use crate::{
 	cli::commands::{
-		clean::{CleanArgs, CleanCommandError, CleanOutput},
+		clean::{
+			ensure_is_cache_subdir, format_size, parse_duration, CleanArgs, CleanCommandError,
+			CleanOutput,
+		},
 		CommandExecution,
 	},
 	compile::cache,
@@ -16,9 +19,16 @@ fn output_can_display_as_string() {
 			(PathBuf::from("/dir1"), true),
 			(PathBuf::from("/dir2"), false),
 		],
+		removed_files: 3,
+		reclaimed_bytes: 2048,
 	};
 
-	let expected_output = "cleaned  : /dir1\nnot found: /dir2\nCache cleaned successfully.\n";
+	let expected_output = concat!(
+		"cleaned  : /dir1\n",
+		"not found: /dir2\n",
+		"Removed 3 files, reclaimed 2.0 KiB\n",
+		"Cache cleaned successfully.\n"
+	);
 
 	assert_eq!(expected_output, format!("{}", output));
 }
@@ -27,8 +37,13 @@ fn output_can_display_as_string() {
 fn clean_cache_dirs() -> Result<(), CleanCommandError> {
 	let test_cache_dir = cache::cache_dir()?;
 	fs::create_dir_all(test_cache_dir.join(cache::CAIRO_FOUNDRY_CACHE_DIR));
+	fs::write(
+		test_cache_dir.join(cache::CAIRO_FOUNDRY_CACHE_DIR).join("test_clean.json"),
+		"{}",
+	);
+	let _ = fs::remove_dir_all(test_cache_dir.join(cache::CAIRO_FOUNDRY_COMPILED_CONTRACT_DIR));
 
-	let output = CleanArgs {}.exec()?;
+	let output = CleanArgs { max_age: None }.exec()?;
 
 	let expected_dirs = vec![
 		(test_cache_dir.join(cache::CAIRO_FOUNDRY_CACHE_DIR), true),
@@ -40,6 +55,8 @@ fn clean_cache_dirs() -> Result<(), CleanCommandError> {
 
 	let expected_output = CleanOutput {
 		dirs: expected_dirs,
+		removed_files: 1,
+		reclaimed_bytes: 2,
 	};
 
 	assert_eq!(expected_output, output);
@@ -48,3 +65,64 @@ fn clean_cache_dirs() -> Result<(), CleanCommandError> {
 
 	Ok(())
 }
+
+#[test]
+fn clean_only_removes_old_files() -> Result<(), CleanCommandError> {
+	let test_cache_dir = cache::cache_dir()?;
+	let compiled_dir = test_cache_dir.join(cache::CAIRO_FOUNDRY_COMPILED_CONTRACT_DIR);
+	fs::create_dir_all(&compiled_dir).unwrap();
+	let compiled_file = compiled_dir.join("test_clean_max_age.json");
+	fs::write(&compiled_file, "{}").unwrap();
+
+	CleanArgs {
+		max_age: Some(Duration::from_secs(3600)),
+	}
+	.exec()?;
+	assert!(compiled_file.exists());
+
+	CleanArgs {
+		max_age: Some(Duration::ZERO),
+	}
+	.exec()?;
+	assert!(!compiled_file.exists());
+
+	Ok(())
+}
+
+#[test]
+fn clean_refuses_unexpected_directories() {
+	let cache_dir = PathBuf::from("/cache");
+	assert!(
+		ensure_is_cache_subdir(&cache_dir, &cache_dir.join(cache::CAIRO_FOUNDRY_CACHE_DIR)).is_ok()
+	);
+	assert!(matches!(
+		ensure_is_cache_subdir(&cache_dir, &cache_dir.join("other")),
+		Err(CleanCommandError::UnexpectedDirectory(_))
+	));
+	assert!(matches!(
+		ensure_is_cache_subdir(
+			&cache_dir,
+			&PathBuf::from("/").join(cache::CAIRO_FOUNDRY_CACHE_DIR)
+		),
+		Err(CleanCommandError::UnexpectedDirectory(_))
+	));
+}
+
+#[test]
+fn parse_durations() {
+	assert_eq!(Ok(Duration::from_secs(30)), parse_duration("30"));
+	assert_eq!(Ok(Duration::from_secs(30)), parse_duration("30s"));
+	assert_eq!(Ok(Duration::from_secs(15 * 60)), parse_duration("15m"));
+	assert_eq!(Ok(Duration::from_secs(12 * 3600)), parse_duration("12h"));
+	assert_eq!(Ok(Duration::from_secs(7 * 86400)), parse_duration("7d"));
+	assert!(parse_duration("7w").is_err());
+	assert!(parse_duration("").is_err());
+	assert!(parse_duration(&format!("{}d", u64::MAX / 60)).is_err());
+}
+
+#[test]
+fn sizes_are_human_readable() {
+	assert_eq!("512 B", format_size(512));
+	assert_eq!("1.5 KiB", format_size(1536));
+	assert_eq!("3.0 MiB", format_size(3 * 1024 * 1024));
+}
@@ -0,0 +1,171 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+	fmt::{self, Display},
+	fs, io,
+	path::PathBuf,
+};
+
+use clap::Args;
+use colored::Colorize;
+use serde::Serialize;
+use thiserror::Error;
+use uuid::Uuid;
+use which::which;
+
+use super::{test::run_file, CommandExecution, EXIT_CODE_TEST_FAILURE};
+
+use crate::compile::{
+	cache::{self, CAIRO_FOUNDRY_CACHE_DIR},
+	Compiler, CAIRO_COMPILE_BINARY,
+};
+
+/// Program compiled and run to check the whole toolchain
+const SAMPLE_PROGRAM: &str = "func test_doctor() {\n    assert 1 + 1 = 2;\n    return ();\n}\n";
+
+/// Doctor command
+#[derive(Args, Debug)]
+pub struct DoctorArgs {
+	/// Name or path of the cairo compiler binary
+	#[clap(long, env = "CAIRO_COMPILE_BIN", default_value = CAIRO_COMPILE_BINARY)]
+	pub compiler: String,
+}
+
+#[derive(Error, Debug)]
+pub enum DoctorCommandError {
+	#[error("Failed to write the sample program to {0}: {1}")]
+	SampleProgram(String, io::Error),
+}
+
+/// Outcome of a check of the environment
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct Check {
+	pub name: String,
+	pub passed: bool,
+	/// What has been found (eg: the version of the compiler), or why the check failed
+	pub details: String,
+	/// How to fix the environment, for the failed checks
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub hint: Option<String>,
+}
+
+impl Check {
+	fn new(name: &str, result: Result<String, String>, hint: &str) -> Self {
+		let passed = result.is_ok();
+		Self {
+			name: name.to_string(),
+			passed,
+			details: result.unwrap_or_else(|error| error),
+			hint: Some(hint.to_string()).filter(|_| !passed),
+		}
+	}
+}
+
+/// Doctor command output, one check per requirement of cairo-foundry
+#[derive(Debug, Serialize, Default, PartialEq, Eq)]
+pub struct DoctorOutput {
+	pub checks: Vec<Check>,
+}
+
+impl DoctorOutput {
+	/// `EXIT_CODE_TEST_FAILURE` if a check failed, 0 otherwise
+	pub fn exit_code(&self) -> u8 {
+		if self.checks.iter().all(|check| check.passed) {
+			0
+		} else {
+			EXIT_CODE_TEST_FAILURE
+		}
+	}
+}
+
+impl Display for DoctorOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for check in &self.checks {
+			let status = if check.passed {
+				"OK".green()
+			} else {
+				"FAILED".red()
+			};
+			writeln!(f, "[{status}] {}: {}", check.name, check.details)?;
+			if let Some(hint) = &check.hint {
+				writeln!(f, "  hint: {hint}")?;
+			}
+		}
+		if self.exit_code() == 0 {
+			writeln!(f, "{}", "Everything looks fine.".green())
+		} else {
+			writeln!(f, "{}", "Some checks failed, see the hints above.".red())
+		}
+	}
+}
+
+impl DoctorArgs {
+	/// The compiler binary is found, and reports its version
+	fn check_compiler(&self, compiler: &Compiler) -> Result<String, String> {
+		let path = which(&compiler.binary)
+			.map_err(|e| format!("binary '{}' not found: {e}", compiler.binary))?;
+		let version = compiler.version().map_err(|e| e.to_string())?;
+		Ok(format!("{version} ({})", path.display()))
+	}
+
+	/// Files can be written to the cache directory
+	fn check_cache_dir(&self) -> Result<String, String> {
+		let dir = cache::cache_dir().map_err(|e| e.to_string())?.join(CAIRO_FOUNDRY_CACHE_DIR);
+		let probe = dir.join(format!(".doctor-{}", Uuid::new_v4()));
+		fs::create_dir_all(&dir)
+			.and_then(|_| fs::write(&probe, ""))
+			.and_then(|_| fs::remove_file(&probe))
+			.map_err(|e| format!("{} is not writable: {e}", dir.display()))?;
+		Ok(format!("{} is writable", dir.display()))
+	}
+
+	/// A sample test file (in `dir`) compiles, and its test passes
+	fn check_sample_program(&self, dir: &PathBuf, compiler: &Compiler) -> Result<String, String> {
+		let path = dir.join("test_doctor.cairo");
+		let file_result = run_file(&path, dir, compiler, &Default::default())
+			.map_err(|e| e.to_string())?
+			.ok_or_else(|| String::from("the test of the sample program has not been found"))?;
+		if let Some(error) = &file_result.error {
+			return Err(error.clone())
+		}
+		match file_result.tests.iter().find_map(|test| test.error.as_ref()) {
+			Some(error) => Err(format!("the test of the sample program failed: {error}")),
+			None => Ok(String::from("a sample test compiled and passed")),
+		}
+	}
+}
+
+impl CommandExecution<DoctorOutput, DoctorCommandError> for DoctorArgs {
+	fn exec(&self) -> Result<DoctorOutput, DoctorCommandError> {
+		let compiler = Compiler {
+			binary: self.compiler.clone(),
+			..Default::default()
+		};
+		let dir = std::env::temp_dir().join(format!("cairo-foundry-doctor-{}", Uuid::new_v4()));
+		fs::create_dir_all(&dir)
+			.and_then(|_| fs::write(dir.join("test_doctor.cairo"), SAMPLE_PROGRAM))
+			.map_err(|e| DoctorCommandError::SampleProgram(dir.display().to_string(), e))?;
+
+		let checks = vec![
+			Check::new(
+				"compiler",
+				self.check_compiler(&compiler),
+				"install cairo-lang (`pip install cairo-lang`), or give the path of the compiler \
+				 with --compiler (or CAIRO_COMPILE_BIN)",
+			),
+			Check::new(
+				"cache directory",
+				self.check_cache_dir(),
+				"give a writable directory with --cache-dir (or CAIRO_FOUNDRY_CACHE_DIR)",
+			),
+			Check::new(
+				"sample program",
+				self.check_sample_program(&dir, &compiler),
+				"fix the checks above, or run the command with --log-level debug for more details",
+			),
+		];
+		let _ = fs::remove_dir_all(&dir);
+		Ok(DoctorOutput { checks })
+	}
+}
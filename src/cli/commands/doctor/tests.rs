@@ -0,0 +1,68 @@
+use super::{Check, DoctorArgs, DoctorOutput};
+use crate::{
+	cli::commands::{CommandExecution, EXIT_CODE_TEST_FAILURE},
+	compile::CAIRO_COMPILE_BINARY,
+};
+
+#[test]
+fn every_check_passes_with_the_default_compiler() {
+	let output = DoctorArgs {
+		compiler: CAIRO_COMPILE_BINARY.to_string(),
+	}
+	.exec()
+	.unwrap();
+
+	assert_eq!(3, output.checks.len());
+	assert_eq!(0, output.exit_code(), "{output}");
+}
+
+#[test]
+fn missing_compiler_is_reported_with_a_hint() {
+	let output = DoctorArgs {
+		compiler: String::from("not-a-cairo-compiler"),
+	}
+	.exec()
+	.unwrap();
+
+	let passed: Vec<(&str, bool)> =
+		output.checks.iter().map(|check| (check.name.as_str(), check.passed)).collect();
+	assert_eq!(
+		vec![
+			("compiler", false),
+			("cache directory", true),
+			("sample program", false)
+		],
+		passed
+	);
+	assert!(output.checks[0].details.contains("not-a-cairo-compiler"));
+	assert_eq!(EXIT_CODE_TEST_FAILURE, output.exit_code());
+}
+
+#[test]
+fn failed_checks_are_displayed_with_their_hint() {
+	let output = DoctorOutput {
+		checks: vec![
+			Check::new(
+				"compiler",
+				Ok(String::from("cairo-compile 0.10.3")),
+				"install it",
+			),
+			Check::new(
+				"cache directory",
+				Err(String::from("/cache is not writable")),
+				"use --cache-dir",
+			),
+		],
+	};
+
+	let text = output.to_string();
+	assert!(
+		text.contains("] compiler: cairo-compile 0.10.3\n"),
+		"{text}"
+	);
+	assert!(!text.contains("install it"), "{text}");
+	assert!(
+		text.contains("] cache directory: /cache is not writable\n  hint: use --cache-dir\n"),
+		"{text}"
+	);
+}
@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+	fmt::Display,
+	fs, io,
+	path::{Path, PathBuf},
+};
+
+use clap::{Args, ValueHint};
+use serde::Serialize;
+use thiserror::Error;
+
+use super::CommandExecution;
+use crate::cli::config::CONFIG_FILE_NAME;
+/// Directory of the tests created by `init`
+const TESTS_DIR: &str = "tests";
+/// Sample test file created by `init`, named so that it is found by `list_test_files`
+const SAMPLE_TEST_FILE_NAME: &str = "test_example.cairo";
+
+const CONFIG_FILE_CONTENT: &str = r#"# Default options of cairo-foundry, the command line flags take precedence over them
+root = "tests"
+# max-steps = 1000000
+# compiler = "cairo-compile"
+# cairo-path = ["lib"]
+# compiler-args = ["--proof_mode"]
+# fuzz-runs = 100
+
+# Profiles override the options above, when selected with `--config-profile <name>`
+# [profile.ci]
+# max-steps = 10000000
+# jobs = 2
+"#;
+
+const SAMPLE_TEST_FILE_CONTENT: &str = r#"func test_passing() {
+    assert 1 + 1 = 2;
+    return ();
+}
+
+// Fails on purpose, to show how a failing test is reported
+func test_failing() {
+    assert 1 + 1 = 3;
+    return ();
+}
+"#;
+
+#[derive(Args, Debug)]
+pub struct InitArgs {
+	/// Directory of the project to create
+	#[clap(value_hint = ValueHint::DirPath, default_value = "./")]
+	pub dir: PathBuf,
+	/// Overwrite the existing files
+	#[clap(long)]
+	pub force: bool,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct InitOutput {
+	/// Files written by the command
+	pub created: Vec<PathBuf>,
+}
+
+#[derive(Error, Debug)]
+pub enum InitCommandError {
+	#[error("Refusing to overwrite {0}, use --force to overwrite it")]
+	ExistingFile(String),
+	#[error("Cannot create directory {dir}: {err}")]
+	DirCreation { dir: String, err: io::Error },
+	#[error("Cannot write file {file}: {err}")]
+	FileCreation { file: String, err: io::Error },
+}
+
+impl Display for InitOutput {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		for file in &self.created {
+			writeln!(f, "created: {}", file.display())?;
+		}
+		writeln!(
+			f,
+			"Project initialized, run the tests with `cairo-foundry test`."
+		)
+	}
+}
+
+fn create_dir(dir: &Path) -> Result<(), InitCommandError> {
+	fs::create_dir_all(dir).map_err(|err| InitCommandError::DirCreation {
+		dir: dir.display().to_string(),
+		err,
+	})
+}
+
+impl CommandExecution<InitOutput, InitCommandError> for InitArgs {
+	fn exec(&self) -> Result<InitOutput, InitCommandError> {
+		let files = [
+			(self.dir.join(CONFIG_FILE_NAME), CONFIG_FILE_CONTENT),
+			(
+				self.dir.join(TESTS_DIR).join(SAMPLE_TEST_FILE_NAME),
+				SAMPLE_TEST_FILE_CONTENT,
+			),
+		];
+
+		// Check every file before writing any, not to leave a half initialized project
+		if !self.force {
+			if let Some((path, _)) = files.iter().find(|(path, _)| path.exists()) {
+				return Err(InitCommandError::ExistingFile(path.display().to_string()))
+			}
+		}
+
+		create_dir(&self.dir.join(TESTS_DIR))?;
+		let mut output = InitOutput {
+			created: Vec::new(),
+		};
+		for (path, content) in files {
+			fs::write(&path, content).map_err(|err| InitCommandError::FileCreation {
+				file: path.display().to_string(),
+				err,
+			})?;
+			output.created.push(path);
+		}
+
+		Ok(output)
+	}
+}
@@ -0,0 +1,67 @@
+use std::fs;
+
+use assert_matches::assert_matches;
+use uuid::Uuid;
+
+use crate::{
+	cli::{
+		commands::{
+			init::{InitArgs, InitCommandError},
+			CommandExecution,
+		},
+		config::CONFIG_FILE_NAME,
+	},
+	io::test_files::list_test_files,
+};
+
+fn init_args(force: bool) -> InitArgs {
+	InitArgs {
+		dir: std::env::temp_dir().join(Uuid::new_v4().to_string()),
+		force,
+	}
+}
+
+#[test]
+fn init_creates_a_discoverable_sample_test() {
+	let args = init_args(false);
+
+	let output = args.exec().unwrap();
+
+	assert_eq!(
+		vec![
+			args.dir.join(CONFIG_FILE_NAME),
+			args.dir.join("tests").join("test_example.cairo"),
+		],
+		output.created
+	);
+	assert_eq!(
+		vec![args.dir.join("tests").join("test_example.cairo")],
+		list_test_files(&args.dir, None, &[]).unwrap()
+	);
+	fs::remove_dir_all(&args.dir).unwrap();
+}
+
+#[test]
+fn init_refuses_to_overwrite_existing_files() {
+	let args = init_args(false);
+	fs::create_dir_all(&args.dir).unwrap();
+	fs::write(args.dir.join(CONFIG_FILE_NAME), "root = \"src\"\n").unwrap();
+
+	assert_matches!(args.exec(), Err(InitCommandError::ExistingFile(_)));
+	assert_eq!(
+		"root = \"src\"\n",
+		fs::read_to_string(args.dir.join(CONFIG_FILE_NAME)).unwrap()
+	);
+	assert!(!args.dir.join("tests").exists());
+
+	let args = InitArgs {
+		force: true,
+		..args
+	};
+	args.exec().unwrap();
+	assert_ne!(
+		"root = \"src\"\n",
+		fs::read_to_string(args.dir.join(CONFIG_FILE_NAME)).unwrap()
+	);
+	fs::remove_dir_all(&args.dir).unwrap();
+}
@@ -8,9 +8,12 @@ use log::info;
 use serde::Serialize;
 use thiserror::Error;
 
-use crate::io::test_files::{list_test_files, ListTestsFilesError};
+use crate::{
+	compile::{Compiler, CAIRO_COMPILE_BINARY},
+	io::test_files::{list_test_files, ListTestsFilesError},
+};
 
-use super::CommandExecution;
+use super::{test::compile_and_list_entrypoints, CommandExecution};
 
 /// List command
 #[derive(Args, Debug)]
@@ -18,6 +21,34 @@ pub struct ListArgs {
 	/// Root path
 	#[clap(short, long, value_hint=ValueHint::DirPath, value_parser=path_is_valid_directory)]
 	pub root: PathBuf,
+	/// Name or path of the cairo compiler binary
+	#[clap(long, env = "CAIRO_COMPILE_BIN", default_value = CAIRO_COMPILE_BINARY)]
+	pub compiler: String,
+	/// Directories searched for the imported modules, several directories can be given by
+	/// repeating the option or by separating them with colons (eg: `--cairo-path lib:vendor`)
+	#[clap(
+		long,
+		env = "CAIRO_PATH",
+		value_name = "DIRS",
+		value_hint = ValueHint::DirPath,
+		value_delimiter = ':'
+	)]
+	pub cairo_path: Vec<PathBuf>,
+	/// Extra arguments passed to the cairo compiler (eg: `--compiler-args "--proof_mode"`)
+	#[clap(
+		long,
+		value_name = "ARGS",
+		allow_hyphen_values = true,
+		value_delimiter = ' '
+	)]
+	pub compiler_args: Vec<String>,
+	/// Glob matching the test files (default: `test_*.cairo` and `*_test.cairo`)
+	#[clap(long, value_name = "GLOB")]
+	pub test_glob: Option<String>,
+	/// Skip the files and directories matching this glob (matched against their name, or against
+	/// their path relative to `root` if it contains a `/`)
+	#[clap(long = "exclude", value_name = "GLOB")]
+	pub excludes: Vec<String>,
 }
 
 #[derive(Error, Debug)]
@@ -39,11 +70,50 @@ pub fn path_is_valid_directory(path: &str) -> Result<PathBuf, String> {
 	}
 }
 
+/// Function used to validate the root of the test command, which is either a directory or a single
+/// test file
+/// `path: &str` the Path to test
+/// Returns the `PathBuf` for the given path
+/// or an Err with the Path if it does not exist.
+pub fn path_is_valid_directory_or_file(path: &str) -> Result<PathBuf, String> {
+	let path = PathBuf::from(path);
+	if path.exists() {
+		Ok(path)
+	} else {
+		Err(format!(
+			"\"{}\" is not a valid directory or file",
+			path.display()
+		))
+	}
+}
+
+/// A test file and the test entrypoints it contains
+#[derive(Debug, Serialize)]
+pub struct ListedFile {
+	pub path: PathBuf,
+	pub entrypoints: Vec<String>,
+	/// Reason why the entrypoints of the file could not be listed, if any
+	pub error: Option<String>,
+}
+
+impl fmt::Display for ListedFile {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.path.display())?;
+		if let Some(error) = &self.error {
+			write!(f, " (error: {error})")?;
+		}
+		for entrypoint in &self.entrypoints {
+			write!(f, "\n\t{entrypoint}")?;
+		}
+		Ok(())
+	}
+}
+
 /// List command output
 #[derive(Debug, Serialize)]
 pub struct ListOutput {
 	/// The list of test files found
-	pub files: Vec<PathBuf>,
+	pub files: Vec<ListedFile>,
 }
 
 impl fmt::Display for ListOutput {
@@ -51,11 +121,7 @@ impl fmt::Display for ListOutput {
 		write!(
 			f,
 			"{}",
-			self.files
-				.iter()
-				.map(|path| path.display().to_string())
-				.collect::<Vec<_>>()
-				.join("\n")
+			self.files.iter().map(|file| file.to_string()).collect::<Vec<_>>().join("\n")
 		)
 	}
 }
@@ -64,26 +130,57 @@ impl CommandExecution<ListOutput, ListCommandError> for ListArgs {
 	/// Implementation of CommandExecution Trait for the List Command
 	///
 	/// The List Command lists and returns the 'ListOutput' of all the valid
-	/// Cairo tests files within the ListArgs root directory(PathBuf).
-	/// To be valid, the filename must follow the following regex:
-	///    "^test_.*\.cairo$"
+	/// Cairo tests files within the ListArgs root directory(PathBuf), along with
+	/// the test entrypoints they contain.
+	/// To be valid, the filename must follow the following regex (unless a `--test-glob` is
+	/// given):
+	///    "^(test_.*|.*_test)\.cairo$"
 	///
 	/// Filename examples:
 	///    test_invalid_program.cairo > Valid
-	///    failing.cairo > Invalid, filename does not start with "test_"
+	///    array_sum_test.cairo > Valid
+	///    failing.cairo > Invalid, filename neither starts with "test_" nor ends with "_test.cairo"
 	///    test_mock_call.cairo.test > Invalid, ends with "test" not ".cairo"
 	///
 	/// When using the cairo-compile command, the root directory is the one specified
 	/// by the option "--root"
 	///
-	/// Returns a `ListOutput` struct with all valid tests files in the `.files: vector<PathBuf>`
+	/// Each file is compiled (or taken from the cache) to list its entrypoints, but none of them
+	/// is executed.
+	///
+	/// Returns a `ListOutput` struct with all valid tests files in the `.files: vector<ListedFile>`
 	/// or an error `ListCommandError`, the first Error encoutered during the
 	/// processing of the root directory.
 	fn exec(&self) -> Result<ListOutput, ListCommandError> {
 		info!("Listing files within directory {:?}", self.root);
 
-		let tests_list = list_test_files(&self.root)?;
+		let compiler = Compiler {
+			binary: self.compiler.clone(),
+			cairo_path: self.cairo_path.clone(),
+			args: self.compiler_args.clone(),
+		};
+
+		let files = list_test_files(&self.root, self.test_glob.as_deref(), &self.excludes)?
+			.into_iter()
+			.map(|path| {
+				match compile_and_list_entrypoints(path.clone(), &self.root, &compiler, true) {
+					Ok((path, _, entrypoints, _)) => ListedFile {
+						path,
+						entrypoints: entrypoints
+							.into_iter()
+							.map(|entrypoint| entrypoint.name)
+							.collect(),
+						error: None,
+					},
+					Err(err) => ListedFile {
+						path,
+						entrypoints: Vec::new(),
+						error: Some(err.to_string()),
+					},
+				}
+			})
+			.collect();
 
-		Ok(ListOutput { files: tests_list })
+		Ok(ListOutput { files })
 	}
 }
@@ -1,12 +1,26 @@
 use std::path::PathBuf;
 
-use super::ListOutput;
+use super::{ListOutput, ListedFile};
 
 #[test]
 fn output_can_display_as_string() {
 	let output = ListOutput {
-		files: vec![PathBuf::from("item 1"), PathBuf::from("item 2")],
+		files: vec![
+			ListedFile {
+				path: PathBuf::from("item 1"),
+				entrypoints: vec![String::from("test_1"), String::from("test_2")],
+				error: None,
+			},
+			ListedFile {
+				path: PathBuf::from("item 2"),
+				entrypoints: vec![],
+				error: Some(String::from("compilation failed")),
+			},
+		],
 	};
 
-	assert_eq!("item 1\nitem 2", format!("{output}"));
+	assert_eq!(
+		"item 1\n\ttest_1\n\ttest_2\nitem 2 (error: compilation failed)",
+		format!("{output}")
+	);
 }
@@ -0,0 +1,173 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+	fmt::Display,
+	path::{Path, PathBuf},
+};
+
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use thiserror::Error;
+use walkdir::WalkDir;
+
+use super::CommandExecution;
+
+use crate::compile::cache::{self, changed_dependency, compute_hash, read_cache_file, Cache};
+
+#[derive(Args, Debug)]
+pub struct ListCachedArgs {
+	/// Order of the listed entries
+	#[clap(long, value_enum, default_value_t = SortKey::Status)]
+	pub sort: SortKey,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortKey {
+	/// Problematic entries first, then by source path
+	Status,
+	/// By source path
+	Path,
+}
+
+/// State of a cache entry, from the most to the least problematic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CacheEntryStatus {
+	/// The cache file cannot be read
+	Invalid,
+	/// The source file does not exist anymore
+	Orphaned,
+	/// The source file changed since it was compiled
+	Stale,
+	/// The source file did not change since it was compiled
+	Valid,
+}
+
+impl Display for CacheEntryStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let status = match self {
+			Self::Invalid => "invalid",
+			Self::Orphaned => "orphaned",
+			Self::Stale => "stale",
+			Self::Valid => "valid",
+		};
+		// Pad the status, to align the table columns
+		f.pad(status)
+	}
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct CacheEntry {
+	pub status: CacheEntryStatus,
+	/// Path of the cache file
+	pub cache_file: PathBuf,
+	/// Path of the source file, unless the cache file is invalid
+	pub contract_path: Option<PathBuf>,
+	/// Sha256 hash of the source file when it was compiled, unless the cache file is invalid
+	pub hash: Option<String>,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct ListCachedOutput {
+	pub entries: Vec<CacheEntry>,
+}
+
+#[derive(Error, Debug)]
+pub enum ListCachedCommandError {
+	#[error(transparent)]
+	CacheDirNotSupported(#[from] cache::CacheDirNotSupported),
+}
+
+impl Display for ListCachedOutput {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "{:<8}  {:<66}  SOURCE", "STATUS", "HASH")?;
+		for entry in &self.entries {
+			let source = entry.contract_path.as_ref().unwrap_or(&entry.cache_file);
+			writeln!(
+				f,
+				"{:<8}  {:<66}  {}",
+				entry.status,
+				entry.hash.as_deref().unwrap_or("-"),
+				source.display()
+			)?;
+		}
+		Ok(())
+	}
+}
+
+/// Root directory of the contract of a cache entry, which the paths of its dependencies are
+/// relative to: the cache file is at the path of the contract relatively to it (`relative_path`).
+/// Empty if it cannot be found, so that the dependencies are resolved as they are recorded.
+fn contract_root_dir(cache: &Cache, relative_path: &Path) -> PathBuf {
+	let relative_path = relative_path.with_extension("cairo");
+	cache
+		.contract_path
+		.ancestors()
+		.find(|dir| dir.join(&relative_path) == cache.contract_path)
+		.map(PathBuf::from)
+		.unwrap_or_default()
+}
+
+/// Read a cache file, and compare the hashes it stores with the current content of its source and
+/// of the modules it imports.
+/// `cache_files_dir` is the directory of the cache files, which `cache_file` is in.
+fn read_cache_entry(cache_file: &Path, cache_files_dir: &Path) -> CacheEntry {
+	let cache = match read_cache_file(&cache_file.to_path_buf()) {
+		Ok(cache) => cache,
+		Err(_) =>
+			return CacheEntry {
+				status: CacheEntryStatus::Invalid,
+				cache_file: cache_file.to_path_buf(),
+				contract_path: None,
+				hash: None,
+			},
+	};
+	let root_dir = contract_root_dir(
+		&cache,
+		cache_file.strip_prefix(cache_files_dir).unwrap_or(cache_file),
+	);
+	let status = if !cache.contract_path.exists() {
+		CacheEntryStatus::Orphaned
+	} else if compute_hash(&cache.contract_path).ok().as_ref() == Some(&cache.hash)
+		&& changed_dependency(&cache, &root_dir).is_none()
+	{
+		CacheEntryStatus::Valid
+	} else {
+		CacheEntryStatus::Stale
+	};
+	CacheEntry {
+		status,
+		cache_file: cache_file.to_path_buf(),
+		contract_path: Some(cache.contract_path),
+		hash: Some(cache.hash),
+	}
+}
+
+/// List the entries of a directory of cache files, sorted by `sort`
+fn list_cache_entries(cache_files_dir: &Path, sort: SortKey) -> Vec<CacheEntry> {
+	let mut entries: Vec<CacheEntry> = WalkDir::new(cache_files_dir)
+		.into_iter()
+		.filter_map(Result::ok)
+		.filter(|entry| entry.file_type().is_file())
+		.map(|entry| read_cache_entry(entry.path(), cache_files_dir))
+		.collect();
+
+	let source = |entry: &CacheEntry| {
+		entry.contract_path.clone().unwrap_or_else(|| entry.cache_file.clone())
+	};
+	match sort {
+		SortKey::Status => entries.sort_by_key(|entry| (entry.status, source(entry))),
+		SortKey::Path => entries.sort_by_key(source),
+	}
+	entries
+}
+
+impl CommandExecution<ListCachedOutput, ListCachedCommandError> for ListCachedArgs {
+	fn exec(&self) -> Result<ListCachedOutput, ListCachedCommandError> {
+		let cache_files_dir = cache::cache_dir()?.join(cache::CAIRO_FOUNDRY_CACHE_DIR);
+		Ok(ListCachedOutput {
+			entries: list_cache_entries(&cache_files_dir, self.sort),
+		})
+	}
+}
@@ -0,0 +1,84 @@
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use uuid::Uuid;
+
+use super::{list_cache_entries, CacheEntry, CacheEntryStatus, ListCachedOutput, SortKey};
+use crate::compile::cache::{compute_hash, Cache};
+
+fn write_cache_file(dir: &PathBuf, name: &str, contract_path: &PathBuf, hash: &str) {
+	let cache = Cache {
+		contract_path: contract_path.clone(),
+		compiled_contract_path: dir.join(format!("{name}.compiled.json")),
+		hash: hash.to_string(),
+		compiler_version: String::from("cairo-compile 0.10.3"),
+		compiler_args: Vec::new(),
+		dependencies: BTreeMap::new(),
+	};
+	fs::write(
+		dir.join(format!("{name}.json")),
+		serde_json::to_string(&cache).unwrap(),
+	)
+	.unwrap();
+}
+
+#[test]
+fn entries_are_flagged_and_sorted_by_status() {
+	let dir = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	let cache_files_dir = dir.join("cache");
+	fs::create_dir_all(&cache_files_dir).unwrap();
+	let valid = dir.join("test_valid.cairo");
+	let stale = dir.join("test_stale.cairo");
+	fs::write(&valid, "func test_valid() {\n    return ();\n}\n").unwrap();
+	fs::write(&stale, "func test_stale() {\n    return ();\n}\n").unwrap();
+
+	write_cache_file(
+		&cache_files_dir,
+		"valid",
+		&valid,
+		&compute_hash(&valid).unwrap(),
+	);
+	write_cache_file(&cache_files_dir, "stale", &stale, "0x0");
+	write_cache_file(
+		&cache_files_dir,
+		"orphaned",
+		&dir.join("test_gone.cairo"),
+		"0x0",
+	);
+	fs::write(cache_files_dir.join("invalid.json"), "{").unwrap();
+
+	let statuses: Vec<CacheEntryStatus> = list_cache_entries(&cache_files_dir, SortKey::Status)
+		.into_iter()
+		.map(|entry| entry.status)
+		.collect();
+
+	assert_eq!(
+		vec![
+			CacheEntryStatus::Invalid,
+			CacheEntryStatus::Orphaned,
+			CacheEntryStatus::Stale,
+			CacheEntryStatus::Valid,
+		],
+		statuses
+	);
+	fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn output_can_display_as_table() {
+	let output = ListCachedOutput {
+		entries: vec![CacheEntry {
+			status: CacheEntryStatus::Stale,
+			cache_file: PathBuf::from("cache/test_foo.json"),
+			contract_path: Some(PathBuf::from("tests/test_foo.cairo")),
+			hash: Some(String::from("0x1234")),
+		}],
+	};
+
+	let display = format!("{output}");
+	let lines: Vec<&str> = display.lines().collect();
+
+	assert_eq!(2, lines.len());
+	assert!(lines[0].starts_with("STATUS    HASH"));
+	assert!(lines[1].starts_with("stale     0x1234 "));
+	assert!(lines[1].ends_with("  tests/test_foo.cairo"));
+}
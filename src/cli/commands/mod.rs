@@ -1,7 +1,7 @@
 use crate::cli::formatter::Formattable;
 use clap::Subcommand;
 use serde::Serialize;
-use std::{error, fmt};
+use std::{error, fmt, process::ExitCode};
 use thiserror::Error;
 
 /// list module: contains everything related to the `List` command
@@ -10,6 +10,16 @@ mod list;
 pub mod test;
 // clean module: contains everything related to the `Clean` command
 mod clean;
+// init module: contains everything related to the `Init` command
+mod init;
+// list_cached module: contains everything related to the `ListCached` command
+mod list_cached;
+// bench module: contains everything related to the `Bench` command
+mod bench;
+// build module: contains everything related to the `Build` command
+mod build;
+// doctor module: contains everything related to the `Doctor` command
+mod doctor;
 
 #[derive(Error, Debug)]
 // Todo: Think about a better way to bubble up the errors
@@ -21,6 +31,16 @@ pub enum CommandError {
 	TestCommandError(#[from] test::TestCommandError),
 	#[error(transparent)]
 	CleanCommandError(#[from] clean::CleanCommandError),
+	#[error(transparent)]
+	InitCommandError(#[from] init::InitCommandError),
+	#[error(transparent)]
+	ListCachedCommandError(#[from] list_cached::ListCachedCommandError),
+	#[error(transparent)]
+	BenchCommandError(#[from] bench::BenchCommandError),
+	#[error(transparent)]
+	BuildCommandError(#[from] build::BuildCommandError),
+	#[error(transparent)]
+	DoctorCommandError(#[from] doctor::DoctorCommandError),
 }
 
 /// Enum of all supported commands
@@ -32,8 +52,28 @@ pub enum Commands {
 	Test(test::TestArgs),
 	// Cleans the cache files
 	Clean(clean::CleanArgs),
+	// Creates a sample project
+	Init(init::InitArgs),
+	// Lists the cache entries, flagging the orphaned and stale ones
+	ListCached(list_cached::ListCachedArgs),
+	// Runs the benchmarks (`bench_` entrypoints) and reports their resources
+	Bench(bench::BenchArgs),
+	// Compiles the cairo files and writes the compiled files to an output directory
+	Build(build::BuildArgs),
+	// Checks that the compiler and the cache directory are usable, and that a sample test runs
+	Doctor(doctor::DoctorArgs),
 }
 
+/// Exit code returned when at least one test failed
+pub const EXIT_CODE_TEST_FAILURE: u8 = 1;
+/// Exit code returned when the command could not be executed properly
+/// (ie: the test files could not be listed, or a test file could not be compiled)
+pub const EXIT_CODE_ERROR: u8 = 2;
+/// Exit code returned when no test file has been found, with `--fail-if-no-tests`
+pub const EXIT_CODE_NO_TEST_FILES: u8 = 3;
+/// Exit code returned when the run has been interrupted with Ctrl-C (128 + SIGINT)
+pub const EXIT_CODE_INTERRUPTED: u8 = 130;
+
 /// Behaviour of a command
 pub trait CommandExecution<F: Formattable, E: error::Error + Into<CommandError>> {
 	fn exec(&self) -> Result<F, E>;
@@ -43,11 +83,32 @@ enum CommandOutputs {
 	List(list::ListOutput),
 	Test(test::TestOutput),
 	Clean(clean::CleanOutput),
+	Init(init::InitOutput),
+	ListCached(list_cached::ListCachedOutput),
+	Bench(bench::BenchOutput),
+	Build(build::BuildOutput),
+	Doctor(doctor::DoctorOutput),
 }
 
 /// The executed command output
 pub struct Output(CommandOutputs);
 
+impl Output {
+	/// The exit code the process should return once the output has been displayed
+	pub fn exit_code(&self) -> ExitCode {
+		match &self.0 {
+			CommandOutputs::Test(output) => ExitCode::from(output.exit_code()),
+			CommandOutputs::Bench(output) => ExitCode::from(output.exit_code()),
+			CommandOutputs::Build(output) => ExitCode::from(output.exit_code()),
+			CommandOutputs::Doctor(output) => ExitCode::from(output.exit_code()),
+			CommandOutputs::List(_)
+			| CommandOutputs::Clean(_)
+			| CommandOutputs::Init(_)
+			| CommandOutputs::ListCached(_) => ExitCode::SUCCESS,
+		}
+	}
+}
+
 impl Serialize for Output {
 	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
 	where
@@ -57,6 +118,11 @@ impl Serialize for Output {
 			CommandOutputs::List(output) => output.serialize(serializer),
 			CommandOutputs::Test(output) => output.serialize(serializer),
 			CommandOutputs::Clean(output) => output.serialize(serializer),
+			CommandOutputs::Init(output) => output.serialize(serializer),
+			CommandOutputs::ListCached(output) => output.serialize(serializer),
+			CommandOutputs::Bench(output) => output.serialize(serializer),
+			CommandOutputs::Build(output) => output.serialize(serializer),
+			CommandOutputs::Doctor(output) => output.serialize(serializer),
 		}
 	}
 }
@@ -67,6 +133,11 @@ impl fmt::Display for Output {
 			CommandOutputs::List(output) => output.fmt(f),
 			CommandOutputs::Test(output) => output.fmt(f),
 			CommandOutputs::Clean(output) => output.fmt(f),
+			CommandOutputs::Init(output) => output.fmt(f),
+			CommandOutputs::ListCached(output) => output.fmt(f),
+			CommandOutputs::Bench(output) => output.fmt(f),
+			CommandOutputs::Build(output) => output.fmt(f),
+			CommandOutputs::Doctor(output) => output.fmt(f),
 		}
 	}
 }
@@ -80,6 +151,16 @@ impl CommandExecution<Output, CommandError> for Commands {
 				args.exec().map_err(|e| e.into()).map(|o| Output(CommandOutputs::Test(o))),
 			Commands::Clean(args) =>
 				args.exec().map_err(|e| e.into()).map(|o| Output(CommandOutputs::Clean(o))),
+			Commands::Init(args) =>
+				args.exec().map_err(|e| e.into()).map(|o| Output(CommandOutputs::Init(o))),
+			Commands::ListCached(args) =>
+				args.exec().map_err(|e| e.into()).map(|o| Output(CommandOutputs::ListCached(o))),
+			Commands::Bench(args) =>
+				args.exec().map_err(|e| e.into()).map(|o| Output(CommandOutputs::Bench(o))),
+			Commands::Build(args) =>
+				args.exec().map_err(|e| e.into()).map(|o| Output(CommandOutputs::Build(o))),
+			Commands::Doctor(args) =>
+				args.exec().map_err(|e| e.into()).map(|o| Output(CommandOutputs::Doctor(o))),
 		}
 	}
 }
@@ -0,0 +1,69 @@
+use cairo_rs::{
+	types::relocatable::MaybeRelocatable,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use std::fmt::Write;
+
+use super::state_dump::{format_relocatable, format_value};
+
+/// Start of the error message of a test stopped by a breakpoint
+pub const BREAKPOINT_ERROR_PREFIX: &str = "breakpoint reached";
+
+/// Number of memory cells displayed before `ap` when a breakpoint is reached
+const DISPLAYED_CELLS: usize = 8;
+
+/// Instruction at which the tests are stopped, with `--break-at`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Breakpoint {
+	/// Offset of the instruction in the program segment
+	pub pc: usize,
+	/// Keep on executing the test once the state has been printed, rather than failing it
+	pub resume: bool,
+}
+
+impl Breakpoint {
+	/// Print the state of the `vm` to stderr if its next instruction is the breakpoint one.
+	///
+	/// Fails with `BREAKPOINT_ERROR_PREFIX` unless the breakpoint resumes the execution
+	pub fn check(&self, vm: &VirtualMachine, entrypoint: &str) -> Result<(), VirtualMachineError> {
+		if vm.get_pc().offset != self.pc {
+			return Ok(())
+		}
+		eprint!("{}", describe_state(vm, entrypoint));
+		if self.resume {
+			Ok(())
+		} else {
+			Err(VirtualMachineError::CustomHint(format!(
+				"{BREAKPOINT_ERROR_PREFIX} at pc {}",
+				self.pc
+			)))
+		}
+	}
+}
+
+/// Registers of the `vm`, and the last `DISPLAYED_CELLS` cells before `ap`
+fn describe_state(vm: &VirtualMachine, entrypoint: &str) -> String {
+	let ap = vm.get_ap();
+	let mut description = format!(
+		"Breakpoint at pc {} of {entrypoint} (step {}): pc={} ap={} fp={}\n",
+		vm.get_pc().offset,
+		vm.get_current_step(),
+		format_relocatable(vm.get_pc()),
+		format_relocatable(&ap),
+		format_relocatable(&vm.get_fp())
+	);
+	let first = ap.offset.saturating_sub(DISPLAYED_CELLS);
+	let start = MaybeRelocatable::from((ap.segment_index, first));
+	let cells = vm.get_range(&start, ap.offset - first).unwrap_or_default();
+	// Writing to a String cannot fail
+	for (offset, cell) in (first..).zip(cells) {
+		let value = cell.as_ref().map(|value| format_value(value));
+		let _ = writeln!(
+			description,
+			"  [{}:{offset}] {}",
+			ap.segment_index,
+			value.as_deref().unwrap_or("<unset>")
+		);
+	}
+	description
+}
@@ -0,0 +1,141 @@
+use std::{
+	collections::HashSet,
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+use crate::compile::cache;
+
+/// Output of a git command run in `dir`, or its error message
+fn git(dir: &Path, args: &[&str]) -> Result<String, String> {
+	let output = Command::new("git")
+		.args(args)
+		.current_dir(dir)
+		.output()
+		.map_err(|e| format!("failed to execute git: {e}"))?;
+	if !output.status.success() {
+		return Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+	}
+	Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn canonicalize(path: PathBuf) -> PathBuf {
+	path.canonicalize().unwrap_or(path)
+}
+
+/// Files of the git repository containing `dir` which changed compared to `base`: the modified
+/// ones (committed or not) and the untracked ones.
+///
+/// return the canonical paths of the changed files, or the error of git (eg: if `dir` is not in
+/// a git repository, or `base` is not a valid ref)
+pub fn git_changed_files(dir: &Path, base: &str) -> Result<HashSet<PathBuf>, String> {
+	let toplevel = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?.trim());
+	let modified = git(dir, &["diff", "--name-only", base])?;
+	let untracked = git(
+		dir,
+		&["ls-files", "--others", "--exclude-standard", "--full-name"],
+	)?;
+	Ok(modified
+		.lines()
+		.chain(untracked.lines())
+		.filter(|line| !line.is_empty())
+		.map(|file| canonicalize(toplevel.join(file)))
+		.collect())
+}
+
+/// Whether a test file has to be run, given the `changed` files: if it changed, if one of the
+/// modules it imported when it was last compiled changed (or has been removed), or if it has
+/// never been compiled, as its imports are unknown then
+fn is_affected(test_file: &Path, root: &Path, changed: &HashSet<PathBuf>) -> bool {
+	let test_file = test_file.to_path_buf();
+	if changed.contains(&canonicalize(test_file.clone())) {
+		return true
+	}
+	match cache::read_cache(&test_file, &root.to_path_buf()) {
+		Ok(cache) => cache::dependency_paths(&cache, root).any(|(dependency, _)| {
+			!dependency.exists() || changed.contains(&canonicalize(dependency))
+		}),
+		Err(_) => true,
+	}
+}
+
+/// Keep the test files of `root` affected by the `changed` files (see `is_affected`)
+pub fn select_changed_test_files(
+	test_files: Vec<PathBuf>,
+	root: &Path,
+	changed: &HashSet<PathBuf>,
+) -> Vec<PathBuf> {
+	test_files
+		.into_iter()
+		.filter(|test_file| is_affected(test_file, root, changed))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::compile::cache::{compute_hash, write_cache, Cache};
+	use std::{collections::BTreeMap, fs};
+	use uuid::Uuid;
+
+	#[test]
+	fn test_files_affected_by_the_changes_are_selected() {
+		let root = std::env::temp_dir().join(Uuid::new_v4().to_string());
+		fs::create_dir_all(&root).unwrap();
+		let helper = root.join("helper.cairo");
+		fs::write(&helper, "").unwrap();
+		let cached_test_file = |name: &str, dependencies: &[&PathBuf]| {
+			let path = root.join(name);
+			fs::write(&path, "").unwrap();
+			let cache = Cache {
+				contract_path: path.clone(),
+				compiled_contract_path: root.join(name).with_extension("json"),
+				hash: compute_hash(&path).unwrap(),
+				compiler_version: String::new(),
+				compiler_args: Vec::new(),
+				dependencies: dependencies
+					.iter()
+					.map(|dependency| ((*dependency).clone(), compute_hash(dependency).unwrap()))
+					.collect::<BTreeMap<_, _>>(),
+			};
+			write_cache(&cache, &root).unwrap();
+			path
+		};
+		let test_modified = cached_test_file("test_modified.cairo", &[]);
+		let test_importing = cached_test_file("test_importing.cairo", &[&helper]);
+		let test_unchanged = cached_test_file("test_unchanged.cairo", &[]);
+		let test_uncached = root.join("test_uncached.cairo");
+		fs::write(&test_uncached, "").unwrap();
+
+		let changed = HashSet::from([canonicalize(test_modified.clone())]);
+		assert_eq!(
+			vec![test_modified.clone(), test_uncached.clone()],
+			select_changed_test_files(
+				vec![
+					test_modified.clone(),
+					test_importing.clone(),
+					test_unchanged.clone(),
+					test_uncached.clone()
+				],
+				&root,
+				&changed
+			)
+		);
+
+		let changed = HashSet::from([canonicalize(helper)]);
+		assert_eq!(
+			vec![test_importing.clone()],
+			select_changed_test_files(vec![test_importing, test_unchanged], &root, &changed)
+		);
+		fs::remove_dir_all(root).unwrap();
+	}
+
+	#[test]
+	fn changed_files_require_a_git_repository() {
+		let dir = std::env::temp_dir().join(Uuid::new_v4().to_string());
+		fs::create_dir_all(&dir).unwrap();
+
+		assert!(git_changed_files(&dir, "HEAD").is_err());
+		fs::remove_dir_all(dir).unwrap();
+	}
+}
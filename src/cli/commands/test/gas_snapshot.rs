@@ -0,0 +1,175 @@
+use colored::Colorize;
+use serde::Serialize;
+use std::{
+	collections::BTreeMap,
+	fmt::{Display, Write},
+	fs, io,
+	path::Path,
+};
+
+use super::{GasReport, TestFileResult};
+
+/// Name of the file storing the step count of each test, in the root of the project
+pub const GAS_SNAPSHOT_FILE_NAME: &str = ".gas-snapshot";
+
+/// Step count of each test, by `<path relative to the root>::<entrypoint>`
+pub type GasSnapshot = BTreeMap<String, usize>;
+
+/// Change of the step count of a test, compared to the gas snapshot
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct GasDelta {
+	pub test: String,
+	pub baseline: usize,
+	pub current: usize,
+	/// Whether the step count increased beyond the threshold
+	pub regression: bool,
+}
+
+impl GasDelta {
+	/// Change of the step count, as a percentage of the baseline
+	pub fn percent(&self) -> f64 {
+		if self.baseline == 0 {
+			return 100.0
+		}
+		(self.current as f64 - self.baseline as f64) * 100.0 / self.baseline as f64
+	}
+}
+
+impl Display for GasDelta {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let delta = format!(
+			"{}: {} -> {} steps ({:+.2}%)",
+			self.test,
+			self.baseline,
+			self.current,
+			self.percent()
+		);
+		if self.regression {
+			write!(f, "{}", delta.red())
+		} else if self.current > self.baseline {
+			write!(f, "{}", delta.yellow())
+		} else {
+			write!(f, "{}", delta.green())
+		}
+	}
+}
+
+/// Step count of each completed test of a run
+pub fn gas_snapshot(files: &[TestFileResult], root: &Path) -> GasSnapshot {
+	GasReport::new(files)
+		.0
+		.into_iter()
+		.map(|entry| {
+			let path = entry.path.strip_prefix(root).unwrap_or(&entry.path);
+			(
+				format!("{}::{}", path.display(), entry.entrypoint),
+				entry.resources.n_steps,
+			)
+		})
+		.collect()
+}
+
+/// Read a gas snapshot file, made of `<test> <steps>` lines
+pub fn read_gas_snapshot(path: &Path) -> io::Result<GasSnapshot> {
+	fs::read_to_string(path)?
+		.lines()
+		.filter(|line| !line.trim().is_empty())
+		.map(|line| {
+			line.rsplit_once(' ')
+				.and_then(|(test, steps)| Some((test.to_string(), steps.parse().ok()?)))
+				.ok_or_else(|| {
+					io::Error::new(
+						io::ErrorKind::InvalidData,
+						format!("invalid gas snapshot line '{line}'"),
+					)
+				})
+		})
+		.collect()
+}
+
+/// Write a gas snapshot file, one `<test> <steps>` line per test, sorted by test
+pub fn write_gas_snapshot(path: &Path, snapshot: &GasSnapshot) -> io::Result<()> {
+	let mut content = String::new();
+	for (test, steps) in snapshot {
+		// Writing to a String cannot fail
+		let _ = writeln!(content, "{test} {steps}");
+	}
+	fs::write(path, content)
+}
+
+/// Changes of the step counts of the tests present in both snapshots.
+/// An increase of more than `threshold` percent is a regression.
+pub fn compare_gas_snapshots(
+	baseline: &GasSnapshot,
+	current: &GasSnapshot,
+	threshold: f64,
+) -> Vec<GasDelta> {
+	current
+		.iter()
+		.filter_map(|(test, current)| {
+			let baseline = *baseline.get(test)?;
+			if baseline == *current {
+				return None
+			}
+			let mut delta = GasDelta {
+				test: test.clone(),
+				baseline,
+				current: *current,
+				regression: false,
+			};
+			delta.regression = delta.percent() > threshold;
+			Some(delta)
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use uuid::Uuid;
+
+	#[test]
+	fn gas_snapshot_file_is_written_then_read_back() {
+		let path = std::env::temp_dir().join(format!("{}.gas-snapshot", Uuid::new_v4()));
+		let snapshot = GasSnapshot::from([
+			(String::from("test_foo.cairo::test_a"), 120),
+			(String::from("test_foo.cairo::test_b"), 7),
+		]);
+
+		write_gas_snapshot(&path, &snapshot).unwrap();
+
+		assert_eq!(
+			"test_foo.cairo::test_a 120\ntest_foo.cairo::test_b 7\n",
+			fs::read_to_string(&path).unwrap()
+		);
+		assert_eq!(snapshot, read_gas_snapshot(&path).unwrap());
+		fs::remove_file(path).unwrap();
+	}
+
+	#[test]
+	fn increases_beyond_the_threshold_are_regressions() {
+		let baseline = GasSnapshot::from([
+			(String::from("test_a"), 100),
+			(String::from("test_b"), 100),
+			(String::from("test_c"), 100),
+			(String::from("test_d"), 100),
+		]);
+		let current = GasSnapshot::from([
+			(String::from("test_a"), 104),
+			(String::from("test_b"), 110),
+			(String::from("test_c"), 90),
+			(String::from("test_d"), 100),
+			(String::from("test_new"), 50),
+		]);
+
+		let deltas = compare_gas_snapshots(&baseline, &current, 5.0);
+
+		let regressions: Vec<(&str, bool)> =
+			deltas.iter().map(|delta| (delta.test.as_str(), delta.regression)).collect();
+		assert_eq!(
+			vec![("test_a", false), ("test_b", true), ("test_c", false)],
+			regressions
+		);
+		assert_eq!(-10.0, deltas[2].percent());
+	}
+}
@@ -0,0 +1,190 @@
+use std::{fmt::Write, fs, io, path::PathBuf, time::Duration};
+
+use quick_xml::escape::escape;
+
+use super::{TestFileResult, TestOutput, TestResult, TestStatus};
+
+/// Write the JUnit XML report of a test run to `path`
+pub fn write_junit_report(output: &TestOutput, path: &PathBuf) -> io::Result<()> {
+	fs::write(path, junit_report(output))
+}
+
+/// Build the JUnit XML report of a test run.
+///
+/// Each test file is a `testsuite`, and each of its tests a `testcase`.
+/// A file which could not be tested is reported as a suite with a single erroneous testcase.
+pub fn junit_report(output: &TestOutput) -> String {
+	let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+	let n_errors = output.files.iter().filter(|file| file.error.is_some()).count();
+	// Writing to a String cannot fail
+	let _ = writeln!(
+		xml,
+		"<testsuites name=\"cairo-foundry\" tests=\"{}\" failures=\"{}\" errors=\"{n_errors}\" \
+		 skipped=\"{}\" time=\"{}\">",
+		output.summary.total,
+		output.summary.failed,
+		output.summary.skipped,
+		seconds(output.summary.duration)
+	);
+	for file in &output.files {
+		write_testsuite(&mut xml, file);
+	}
+	xml.push_str("</testsuites>\n");
+	xml
+}
+
+fn write_testsuite(xml: &mut String, file: &TestFileResult) {
+	let name = file.path.display().to_string();
+	let count =
+		|status: TestStatus| file.tests.iter().filter(|test| test.success == status).count();
+	let duration: Duration = file.tests.iter().map(|test| test.duration).sum();
+	let _ = writeln!(
+		xml,
+		"  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" \
+		 time=\"{}\">",
+		escape(&name),
+		file.tests.len(),
+		count(TestStatus::FAILURE),
+		usize::from(file.error.is_some()),
+		count(TestStatus::SKIPPED),
+		seconds(duration)
+	);
+	if let Some(error) = &file.error {
+		let _ = writeln!(
+			xml,
+			"    <testcase name=\"{0}\" classname=\"{0}\" time=\"0.000\">\n      <error \
+			 message=\"{1}\">{1}</error>\n    </testcase>",
+			escape(&name),
+			escape(error)
+		);
+	}
+	for test in &file.tests {
+		write_testcase(xml, &name, test);
+	}
+	xml.push_str("  </testsuite>\n");
+}
+
+fn write_testcase(xml: &mut String, classname: &str, test: &TestResult) {
+	let _ = writeln!(
+		xml,
+		"    <testcase name=\"{}\" classname=\"{}\" time=\"{}\">",
+		escape(&test.entrypoint),
+		escape(classname),
+		seconds(test.duration)
+	);
+	let error = test.error.as_deref().unwrap_or_default();
+	match test.success {
+		TestStatus::SUCCESS => (),
+		TestStatus::SKIPPED => {
+			let _ = writeln!(xml, "      <skipped message=\"{}\"/>", escape(error));
+		},
+		TestStatus::FAILURE => {
+			// The captured output usually explains the failure, it is part of its body
+			let body = match &test.captured_stdout {
+				Some(stdout) => format!("{error}\n\nCaptured stdout:\n{stdout}"),
+				None => error.to_string(),
+			};
+			let _ = writeln!(
+				xml,
+				"      <failure message=\"{}\">{}</failure>",
+				escape(error),
+				escape(&body)
+			);
+		},
+	}
+	if let Some(stdout) = &test.captured_stdout {
+		let _ = writeln!(xml, "      <system-out>{}</system-out>", escape(stdout));
+	}
+	if let Some(stderr) = &test.captured_stderr {
+		let _ = writeln!(xml, "      <system-err>{}</system-err>", escape(stderr));
+	}
+	xml.push_str("    </testcase>\n");
+}
+
+fn seconds(duration: Duration) -> String {
+	format!("{:.3}", duration.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+	use quick_xml::{events::Event, Reader};
+
+	use super::*;
+	use crate::cli::commands::test::TestResources;
+
+	fn test_result(entrypoint: &str, success: TestStatus, error: Option<&str>) -> TestResult {
+		TestResult {
+			entrypoint: entrypoint.to_string(),
+			success,
+			duration: Duration::from_millis(12),
+			captured_stdout: Some(String::from("x is <42> & \"y\"\n")),
+			captured_stderr: None,
+			execution_output: None,
+			error: error.map(String::from),
+			raw_error: None,
+			diff: None,
+			random_seed: None,
+			resources: Some(TestResources::default()),
+			fuzz: None,
+			flaky_runs: None,
+		}
+	}
+
+	#[test]
+	fn junit_report_is_well_formed_xml() {
+		let mut output = TestOutput::default();
+		output.push_file(TestFileResult {
+			path: PathBuf::from("tests/test_file.cairo"),
+			tests: vec![
+				test_result("test_ok", TestStatus::SUCCESS, None),
+				test_result(
+					"test_ko",
+					TestStatus::FAILURE,
+					Some("assertion failed: 1 < 0"),
+				),
+				test_result("test_skip", TestStatus::SKIPPED, Some("setUp failed")),
+			],
+			error: None,
+			compilation: None,
+			coverage: None,
+		});
+		output.push_file(TestFileResult {
+			path: PathBuf::from("tests/test_broken.cairo"),
+			tests: Vec::new(),
+			error: Some(String::from(
+				"binary 'cairo-compile' failed to compile '<file>'",
+			)),
+			compilation: None,
+			coverage: None,
+		});
+
+		let xml = junit_report(&output);
+
+		let mut reader = Reader::from_str(&xml);
+		let mut testcases = Vec::new();
+		let mut n_failures = 0;
+		loop {
+			match reader.read_event().expect("Should be well-formed XML") {
+				Event::Eof => break,
+				Event::Start(element) if element.name().as_ref() == b"testcase" => {
+					let name = element
+						.try_get_attribute("name")
+						.unwrap()
+						.expect("Should have a name")
+						.unescape_value()
+						.unwrap()
+						.to_string();
+					testcases.push(name);
+				},
+				Event::Start(element) if element.name().as_ref() == b"failure" => n_failures += 1,
+				_ => (),
+			}
+		}
+		assert_eq!(
+			vec!["test_ok", "test_ko", "test_skip", "tests/test_broken.cairo"],
+			testcases
+		);
+		assert_eq!(1, n_failures);
+		assert!(xml.contains("x is &lt;42&gt; &amp; &quot;y&quot;"));
+	}
+}
@@ -1,39 +1,112 @@
 #[cfg(test)]
 pub mod tests;
 
+mod breakpoint;
+mod changed;
+mod gas_snapshot;
+mod junit;
+mod parallel;
+mod progress;
+mod snapshot;
+mod state_dump;
+mod watch;
+
 use cairo_rs::{
 	serde::deserialize_program::{deserialize_program_json, ProgramJson},
-	types::{errors::program_errors, program::Program},
+	types::{errors::program_errors, exec_scope::ExecutionScopes, program::Program},
 	vm::{
 		errors::{cairo_run_errors::CairoRunError, vm_errors::VirtualMachineError},
 		hook::Hooks,
+		runners::cairo_runner::CairoRunner,
+		vm_core::VirtualMachine,
 	},
 };
 use clap::{Args, ValueHint};
 use colored::Colorize;
+use log::{debug, warn};
+use num_bigint::BigInt;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use serde::Serialize;
-use std::{fmt::Display, fs, io, path::PathBuf, sync::Arc, time::Instant};
+use std::{
+	collections::{BTreeMap, HashMap, HashSet},
+	fmt::Display,
+	fs, io,
+	num::NonZeroUsize,
+	path::{Component, Path, PathBuf},
+	sync::{
+		mpsc::{self, RecvTimeoutError},
+		Arc, Mutex,
+	},
+	thread,
+	time::{Duration, Instant},
+};
 use thiserror::Error;
 use uuid::Uuid;
 
-use super::{list::path_is_valid_directory, CommandExecution};
+use super::{
+	list::path_is_valid_directory_or_file, CommandExecution, EXIT_CODE_ERROR,
+	EXIT_CODE_INTERRUPTED, EXIT_CODE_NO_TEST_FILES, EXIT_CODE_TEST_FAILURE,
+};
+use crate::cli::interrupt;
+use breakpoint::Breakpoint;
+use changed::{git_changed_files, select_changed_test_files};
+use gas_snapshot::{
+	compare_gas_snapshots, gas_snapshot, read_gas_snapshot, write_gas_snapshot, GasDelta,
+	GasSnapshot, GAS_SNAPSHOT_FILE_NAME,
+};
+use progress::{count_tests, Progress, SharedProgress};
+use snapshot::{check_snapshot, snapshot_path, SNAPSHOT_TAG};
+use state_dump::StateDump;
 
 use crate::{
 	cairo_run::cairo_run,
-	compile::{self, compile},
+	compile::{
+		self,
+		cache::{self, CacheStatus},
+		compile_with_cache, Compiler, CAIRO_COMPILE_BINARY,
+	},
+	fuzz::{
+		is_fuzz_entrypoint, random_felts, shrink, Counterexample, FuzzReport, DEFAULT_FUZZ_RUNS,
+	},
 	hints::{
+		cheatcodes::update_cheatcodes,
+		check_expected_events,
 		hint_processor::function_like_hint_processor::FunctionLikeHintProcessor,
-		output_buffer::{clear_buffer, get_buffer, init_buffer},
+		output_buffer::{
+			clear_execution, get_buffer, get_stderr_buffer, OutputBufferGuard, OutputObserver,
+			DEFAULT_MAX_OUTPUT,
+		},
 		processor::setup_hint_processor,
-		EXPECT_REVERT_FLAG,
+		random::{clear_random, seed_random, test_seed},
+		take_assertion_diff, MockCalls, ASSERTION_FAILED_PREFIX, EXPECT_REVERT_FLAG,
+		EXPECT_REVERT_MISMATCH_PREFIX, EXPECT_REVERT_REGION_NOT_REVERTED, PANIC_PREFIX,
+	},
+	hooks::{
+		self,
+		coverage::{merge_line_coverage, to_lcov, LineCoverage, SharedCoverage},
+		profile::{Profile, SharedProfile},
+		Deadline, MAX_STEPS_ERROR_PREFIX, STAGNATION_ERROR_PREFIX, TIMEOUT_ERROR_PREFIX,
 	},
-	hooks,
 	io::{
-		compiled_programs::{list_test_entrypoints, ListTestEntrypointsError},
+		calldata::{
+			data_file_path, read_calldata_file, read_data_file, read_storage_file, Calldata,
+			CalldataError, SeedStorage,
+		},
+		compiled_programs::{
+			list_entrypoint_arguments, list_instruction_locations, list_test_entrypoints,
+			read_discovery_cache, write_discovery_cache, ListTestEntrypointsError, TestEntrypoint,
+		},
+		felt_format::felt_format,
+		pattern::Pattern,
 		test_files::{list_test_files, ListTestsFilesError},
 	},
 };
 
+/// Entrypoint executed before the tests of a file
+pub const SETUP_ENTRYPOINT: &str = "setUp";
+/// Entrypoint executed after the tests of a file
+pub const TEARDOWN_ENTRYPOINT: &str = "tearDown";
+
 /// Enum containing the possible errors that you may encounter in the ``Test`` module
 #[derive(Error, Debug)]
 // Todo: Maybe use anyhow at this level
@@ -51,8 +124,30 @@ pub enum TestCommandError {
 	Compile(#[from] compile::Error),
 	#[error(transparent)]
 	Program(#[from] program_errors::ProgramError),
+	#[error(
+		"entrypoint '{entrypoint}' not found in compiled program, available entrypoints: {}",
+		.available.join(", ")
+	)]
+	EntrypointNotFound {
+		entrypoint: String,
+		available: Vec<String>,
+	},
 	#[error(transparent)]
 	CairoRun(#[from] CairoRunError),
+	#[error("Failed to write the JUnit report to {0}: {1}")]
+	JunitReport(PathBuf, io::Error),
+	#[error("Failed to write the coverage report to {0}: {1}")]
+	CoverageReport(PathBuf, io::Error),
+	#[error("cannot read test directory {0}: {1}")]
+	UnreadableRoot(PathBuf, io::Error),
+	#[error("cannot read test file {0}: {1}")]
+	UnreadableTestFile(PathBuf, io::Error),
+	#[error("Failed to read or write the gas snapshot {0}: {1}")]
+	GasSnapshot(PathBuf, io::Error),
+	#[error("Failed to watch the test files: {0}")]
+	Watch(#[from] notify::Error),
+	#[error(transparent)]
+	Calldata(#[from] CalldataError),
 	#[error(transparent)]
 	ListTestsFiles(#[from] ListTestsFilesError),
 	#[error(transparent)]
@@ -61,44 +156,993 @@ pub enum TestCommandError {
 
 /// Structure containing the path to a cairo directory.
 /// Used to execute all the tests files contained in this directory
-#[derive(Args, Debug)]
+#[derive(Args, Debug, Clone)]
 pub struct TestArgs {
-	/// Path to a cairo directory
-	#[clap(short, long, value_hint=ValueHint::DirPath, value_parser=path_is_valid_directory, default_value="./")]
+	/// Path to a cairo directory, or to a single test file
+	#[clap(short, long, value_hint=ValueHint::AnyPath, value_parser=path_is_valid_directory_or_file, default_value="./")]
 	pub root: PathBuf,
-	#[clap(short, long, default_value_t = 1000000)]
+	/// Test files (or directories) to run, in place of `--root` (eg: `cairo-foundry test
+	/// tests/test_foo.cairo --match test_bar`). The tests of several paths are run together, each
+	/// test file once, the paths of the files (in the cache and in the gas snapshot) being
+	/// relative to the common directory of the paths
+	#[clap(value_name = "PATH", value_hint = ValueHint::AnyPath, value_parser = path_is_valid_directory_or_file, conflicts_with = "root")]
+	pub paths: Vec<PathBuf>,
+	/// Maximum number of steps of a single test, unless overridden by the test itself with the
+	/// `set_max_steps` hint
+	#[clap(short, long, default_value_t = DEFAULT_MAX_STEPS)]
 	pub max_steps: u64,
+	/// Only run the tests whose name contains this pattern (or matches it, if it is a glob)
+	#[clap(long = "match", value_name = "PATTERN")]
+	pub filter: Option<String>,
+	/// Do not run the tests whose name contains this pattern (or matches it, if it is a glob)
+	#[clap(long, value_name = "PATTERN")]
+	pub skip: Option<String>,
+	/// Only run the tests tagged with one of these tags (eg: `// @slow` above the test)
+	#[clap(long = "tag", value_name = "TAG")]
+	pub tags: Vec<String>,
+	/// Do not run the tests tagged with one of these tags
+	#[clap(long = "exclude-tag", value_name = "TAG")]
+	pub excluded_tags: Vec<String>,
+	/// Name or path of the cairo compiler binary
+	#[clap(long, env = "CAIRO_COMPILE_BIN", default_value = CAIRO_COMPILE_BINARY)]
+	pub compiler: String,
+	/// Directories searched for the imported modules, several directories can be given by
+	/// repeating the option or by separating them with colons (eg: `--cairo-path lib:vendor`)
+	#[clap(
+		long,
+		env = "CAIRO_PATH",
+		value_name = "DIRS",
+		value_hint = ValueHint::DirPath,
+		value_delimiter = ':'
+	)]
+	pub cairo_path: Vec<PathBuf>,
+	/// Extra arguments passed to the cairo compiler (eg: `--compiler-args "--proof_mode"`)
+	#[clap(
+		long,
+		value_name = "ARGS",
+		allow_hyphen_values = true,
+		value_delimiter = ' '
+	)]
+	pub compiler_args: Vec<String>,
+	/// Maximum duration of a single test, in seconds
+	#[clap(long, value_name = "SECONDS")]
+	pub timeout: Option<u64>,
+	/// Maximum duration of the tests of a single file, in seconds: once it is exceeded, the
+	/// remaining tests of the file are skipped (the running test is only interrupted by
+	/// `--timeout`)
+	#[clap(long, value_name = "SECONDS")]
+	pub timeout_per_file: Option<u64>,
+	/// Fail a test as a possible infinite loop, rather than waiting for its step limit, once its
+	/// registers (pc, ap and fp) come back to the same values N times in a row, each time within
+	/// N steps (disabled by default)
+	#[clap(long, value_name = "N", value_parser = clap::value_parser!(usize).range(1..))]
+	pub stagnation_window: Option<usize>,
+	/// Print the resources used by each test, sorted by step count, at the end of the run
+	#[clap(long)]
+	pub gas_report: bool,
+	/// Compare the step count of each test against the `.gas-snapshot` file of `root`, which is
+	/// written if it does not exist yet (or with `--update-snapshots`)
+	#[clap(long)]
+	pub gas_snapshot: bool,
+	/// Increase of the step count of a test, in percent, above which `--gas-snapshot` fails
+	#[clap(long, value_name = "PERCENT", default_value_t = 0.0)]
+	pub gas_snapshot_threshold: f64,
+	/// Number of runs of each fuzzed test (`fuzz_` entrypoints)
+	#[clap(long, value_name = "RUNS", default_value_t = DEFAULT_FUZZ_RUNS)]
+	pub fuzz_runs: usize,
+	/// Seed of the random inputs of the fuzzed tests, a random one is used if not specified
+	#[clap(long, value_name = "SEED")]
+	pub fuzz_seed: Option<u64>,
+	/// Seed of the numbers drawn by the `rand` hint, a random one is used if not specified
+	#[clap(long, value_name = "SEED")]
+	pub seed: Option<u64>,
+	/// Run the `setUp` and `tearDown` entrypoints once per file, rather than around each test
+	#[clap(long)]
+	pub setup_once: bool,
+	/// JSON file mapping test entrypoints to their arguments (eg: `{"test_sum": [2, "0x3"]}`),
+	/// fuzzed tests keep their random inputs
+	#[clap(long, value_name = "PATH", value_hint = ValueHint::FilePath, value_parser = read_calldata_file)]
+	pub calldata: Option<Calldata>,
+	/// JSON file mapping contract addresses to the storage slots preloaded before each test (eg:
+	/// `{"0x123": {"0x1": 42}}`), read and written by the `load` and `store` hints
+	#[clap(long, value_name = "PATH", value_hint = ValueHint::FilePath, value_parser = read_storage_file)]
+	pub seed_storage: Option<SeedStorage>,
+	/// Record the execution output of the tests tagged with `@snapshot`, rather than comparing
+	/// it against their `.snap` file
+	#[clap(long)]
+	pub update_snapshots: bool,
+	/// Run the test files, and the tests of each file, in a random order (reproducible with the
+	/// given seed, a random one is used if not specified)
+	#[clap(long, value_name = "SEED")]
+	pub shuffle: Option<Option<u64>>,
+	/// Write the profile of each test to this directory, as `<file>/<entrypoint>.folded` (folded
+	/// stacks, to be rendered by a flamegraph tool), `<file>` being the path of the test file
+	/// relatively to `root`, without its extension
+	#[clap(long, value_name = "DIR", value_hint = ValueHint::DirPath)]
+	pub profile: Option<PathBuf>,
+	/// Write the final registers and memory of each passing test to this directory, as
+	/// `<file>/<entrypoint>.json`, `<file>` being the path of the test file relatively to `root`,
+	/// without its extension
+	#[clap(long, value_name = "DIR", value_hint = ValueHint::DirPath)]
+	pub dump_state: Option<PathBuf>,
+	/// Print the registers and the last memory cells of the tests each time they reach the
+	/// instruction at this pc (eg: the one of an error message), then fail them
+	#[clap(long, value_name = "PC")]
+	pub break_at: Option<usize>,
+	/// Keep on executing the tests once `--break-at` printed their state, rather than failing them
+	#[clap(long, requires = "break_at")]
+	pub break_continue: bool,
+	/// Write the line coverage of the tests to this file, in the lcov format
+	#[clap(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+	pub coverage: Option<PathBuf>,
+	/// Stop the run at the first failing test (or file which cannot be tested)
+	#[clap(long)]
+	pub fail_fast: bool,
+	/// Only compile the test files, without running their tests. Their compiled files are written
+	/// to the cache, so that it can be warmed in a build stage whose cache is reused by the test
+	/// stage (eg: in a CI)
+	#[clap(long, visible_alias = "update-cache")]
+	pub compile_only: bool,
+	/// Re-run the tests affected by a change each time a cairo file of `root` changes
+	#[clap(long)]
+	pub watch: bool,
+	/// Glob matching the test files (default: `test_*.cairo` and `*_test.cairo`)
+	#[clap(long, value_name = "GLOB")]
+	pub test_glob: Option<String>,
+	/// Skip the files and directories matching this glob (matched against their name, or against
+	/// their path relative to `root` if it contains a `/`)
+	#[clap(long = "exclude", value_name = "GLOB")]
+	pub excludes: Vec<String>,
+	/// Only run the test files which changed compared to the `--base` git ref, and the ones
+	/// importing a changed module (every test file is run outside of a git repository)
+	#[clap(long)]
+	pub only_changed: bool,
+	/// Git ref the changes of `--only-changed` are computed against
+	#[clap(long, value_name = "REF", default_value = "HEAD")]
+	pub base: String,
+	/// Run each (non fuzzed) test this many times, reporting it as flaky if its runs do not all
+	/// have the same status
+	#[clap(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(usize).range(1..))]
+	pub repeat: usize,
+	/// Fail if `root` contains no test file, rather than only warning about it
+	#[clap(long)]
+	pub fail_if_no_tests: bool,
+	/// Write the output of the tests to the terminal as it is produced, each line prefixed with
+	/// the name of its test, rather than capturing it
+	#[clap(long)]
+	pub nocapture: bool,
+	/// Maximum size of the captured stdout (and stderr) of a single test, in bytes, the output
+	/// written beyond it is dropped
+	#[clap(long, value_name = "BYTES", default_value_t = DEFAULT_MAX_OUTPUT)]
+	pub max_output: usize,
+	/// Number of test files compiled concurrently, before their tests are run one after the other
+	/// (default: the number of CPUs)
+	#[clap(short, long, value_name = "N", value_parser = clap::value_parser!(usize).range(1..))]
+	pub jobs: Option<usize>,
+	/// Parse the entrypoints of every compiled file, rather than reading the ones of the cached
+	/// files from their discovery cache (a `.entrypoints.json` file next to the compiled file)
+	#[clap(long)]
+	pub no_discovery_cache: bool,
+	/// Do not display the progress line of the run (`[42/317] running test_foo`), which is only
+	/// written when stderr is a terminal, and never with `--quiet`, `--nocapture` or `--json`
+	#[clap(long)]
+	pub no_progress: bool,
+	/// Level of detail of the output, repeat for more details:
+	/// only the failures by default, every test with `-v`, their resources and whether their file
+	/// has been compiled with `-vv`
+	#[clap(short, long, action = clap::ArgAction::Count)]
+	pub verbose: u8,
+	/// Only print the failing tests and the summary
+	#[clap(short, long, conflicts_with = "verbose")]
+	pub quiet: bool,
+	/// Write a JUnit XML report of the run to this file (ignored with `--watch`)
+	#[clap(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+	pub junit: Option<PathBuf>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Default maximum number of steps of a single test
+pub const DEFAULT_MAX_STEPS: u64 = 1000000;
+
+/// Options controlling which tests are executed and how
+#[derive(Debug, Clone)]
+pub struct TestOptions {
+	/// Maximum number of steps of a single test
+	pub max_steps: u64,
+	/// Selection of the test entrypoints to execute
+	pub filter: EntrypointFilter,
+	/// Maximum duration of a single test
+	pub timeout: Option<Duration>,
+	/// Duration after which the remaining tests of a file are skipped
+	pub file_timeout: Option<Duration>,
+	/// Number of repetitions of the registers after which a test is considered stuck in a loop,
+	/// loops are not detected if None
+	pub stagnation_window: Option<usize>,
+	/// Number of runs of each fuzzed test
+	pub fuzz_runs: usize,
+	/// Seed of the random inputs of the fuzzed tests
+	pub fuzz_seed: u64,
+	/// Seed of the numbers drawn by the `rand` hint, each test deriving its own seed from it
+	pub random_seed: u64,
+	/// Run the lifecycle entrypoints once per file, rather than around each test
+	pub setup_once: bool,
+	/// Overwrite the snapshots of the tests, rather than comparing their output against them
+	pub update_snapshots: bool,
+	/// Arguments of the (non fuzzed) test entrypoints
+	pub calldata: Calldata,
+	/// Storage of the contracts at the start of each test
+	pub seed_storage: SeedStorage,
+	/// Stop at the first failing test, without running the next ones
+	pub fail_fast: bool,
+	/// Seed of the random order of the tests, they are run in lexicographic order if None
+	pub shuffle_seed: Option<u64>,
+	/// Directory the profiles of the executions are written to, they are not profiled if None
+	pub profile_dir: Option<PathBuf>,
+	/// Directory the final states of the passing tests are written to, they are not dumped if None
+	pub state_dump_dir: Option<PathBuf>,
+	/// Name the profile and the state of a test are written under, when it is not the name of its
+	/// entrypoint (ie: for the cases of a table-driven test)
+	pub case_name: Option<String>,
+	/// Instructions executed by the tests, they are not recorded if None
+	pub coverage: Option<SharedCoverage>,
+	/// Instruction at which the state of the tests is printed, there is no breakpoint if None
+	pub breakpoint: Option<Breakpoint>,
+	/// Number of runs of each (non fuzzed) test, to detect the flaky ones
+	pub repeat: usize,
+	/// Write the output of the tests to the terminal as it is produced, rather than capturing it
+	pub nocapture: bool,
+	/// Maximum size of the captured stdout (and stderr) of a single test, in bytes
+	pub max_output: usize,
+	/// Notified of each line of the captured output of the tests as soon as it is written, for
+	/// the applications following the tests as they run (the command line only displays the
+	/// output once the test completed)
+	pub output_observer: Option<OutputObserver>,
+	/// Read the entrypoints of the cached compiled files from their discovery cache
+	pub discovery_cache: bool,
+	/// Progress line updated as each test starts, it is not displayed if None
+	pub progress: Option<SharedProgress>,
+}
+
+impl Default for TestOptions {
+	fn default() -> Self {
+		Self {
+			max_steps: DEFAULT_MAX_STEPS,
+			filter: EntrypointFilter::default(),
+			timeout: None,
+			file_timeout: None,
+			stagnation_window: None,
+			fuzz_runs: DEFAULT_FUZZ_RUNS,
+			fuzz_seed: rand::random(),
+			random_seed: rand::random(),
+			setup_once: false,
+			update_snapshots: false,
+			calldata: Calldata::new(),
+			seed_storage: SeedStorage::new(),
+			fail_fast: false,
+			shuffle_seed: None,
+			profile_dir: None,
+			state_dump_dir: None,
+			case_name: None,
+			coverage: None,
+			breakpoint: None,
+			repeat: 1,
+			nocapture: false,
+			max_output: DEFAULT_MAX_OUTPUT,
+			output_observer: None,
+			discovery_cache: true,
+			progress: None,
+		}
+	}
+}
+
+impl From<&TestArgs> for TestOptions {
+	fn from(args: &TestArgs) -> Self {
+		Self {
+			max_steps: args.max_steps,
+			filter: EntrypointFilter::new(args.filter.as_deref(), args.skip.as_deref())
+				.with_tags(&args.tags, &args.excluded_tags),
+			timeout: args.timeout.map(Duration::from_secs),
+			file_timeout: args.timeout_per_file.map(Duration::from_secs),
+			stagnation_window: args.stagnation_window,
+			fuzz_runs: args.fuzz_runs,
+			fuzz_seed: args.fuzz_seed.unwrap_or_else(rand::random),
+			random_seed: args.seed.unwrap_or_else(rand::random),
+			setup_once: args.setup_once,
+			update_snapshots: args.update_snapshots,
+			calldata: args.calldata.clone().unwrap_or_default(),
+			seed_storage: args.seed_storage.clone().unwrap_or_default(),
+			fail_fast: args.fail_fast,
+			shuffle_seed: args.shuffle.map(|seed| seed.unwrap_or_else(rand::random)),
+			profile_dir: args.profile.clone(),
+			state_dump_dir: args.dump_state.clone(),
+			case_name: None,
+			coverage: args.coverage.as_ref().map(|_| SharedCoverage::default()),
+			breakpoint: args.break_at.map(|pc| Breakpoint {
+				pc,
+				resume: args.break_continue,
+			}),
+			repeat: args.repeat,
+			nocapture: args.nocapture,
+			max_output: args.max_output,
+			output_observer: None,
+			discovery_cache: !args.no_discovery_cache,
+			progress: None,
+		}
+	}
+}
+
+/// Selection of the test entrypoints to execute, built from the `--match`, `--skip`, `--tag` and
+/// `--exclude-tag` options.
+#[derive(Debug, Default, Clone)]
+pub struct EntrypointFilter {
+	matching: Option<Pattern>,
+	skipping: Option<Pattern>,
+	tags: Vec<String>,
+	excluded_tags: Vec<String>,
+}
+
+impl EntrypointFilter {
+	pub fn new(matching: Option<&str>, skipping: Option<&str>) -> Self {
+		Self {
+			matching: matching.map(Pattern::new),
+			skipping: skipping.map(Pattern::new),
+			..Default::default()
+		}
+	}
+
+	/// Only select the entrypoints with one of `tags` (if any), and none of `excluded_tags`
+	pub fn with_tags(self, tags: &[String], excluded_tags: &[String]) -> Self {
+		Self {
+			tags: tags.to_vec(),
+			excluded_tags: excluded_tags.to_vec(),
+			..self
+		}
+	}
+
+	/// Returns true if the entrypoint is selected by both its name and its tags
+	pub fn selects(&self, entrypoint: &TestEntrypoint) -> bool {
+		self.is_selected(&entrypoint.name) && self.is_selected_by_tags(&entrypoint.tags)
+	}
+
+	/// Returns true if the entrypoint matches the `--match` pattern (if any)
+	/// and does not match the `--skip` pattern (if any)
+	pub fn is_selected(&self, entrypoint: &str) -> bool {
+		let is_matching =
+			self.matching.as_ref().map_or(true, |pattern| pattern.is_match(entrypoint));
+		let is_skipped =
+			self.skipping.as_ref().map_or(false, |pattern| pattern.is_match(entrypoint));
+		is_matching && !is_skipped
+	}
+
+	/// Returns true if the tags contain one of the `--tag` tags (if any)
+	/// and none of the `--exclude-tag` tags
+	pub fn is_selected_by_tags(&self, tags: &[String]) -> bool {
+		let is_tagged = self.tags.is_empty() || self.tags.iter().any(|tag| tags.contains(tag));
+		let is_excluded = self.excluded_tags.iter().any(|tag| tags.contains(tag));
+		is_tagged && !is_excluded
+	}
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
 pub enum TestStatus {
 	SUCCESS,
 	FAILURE,
+	SKIPPED,
+}
+
+/// Resources consumed by the execution of a test
+#[derive(Debug, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct TestResources {
+	/// Number of steps executed by the VM
+	pub n_steps: usize,
+	/// Number of instances used, by builtin
+	pub builtins: BTreeMap<String, usize>,
+	/// Used size of the memory segments, in cells, by segment (see `get_segment_sizes`)
+	pub segments: BTreeMap<String, usize>,
 }
 
-/// Structure representing the result of one or multiple test.
-/// Contains the output of the test, as well as the status.
+impl Display for TestResources {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} steps", self.n_steps)?;
+		for (builtin, n_instances) in self.builtins.iter().filter(|(_, n)| **n > 0) {
+			write!(f, ", {n_instances} {builtin}")?;
+		}
+		Ok(())
+	}
+}
+
+/// Structure representing the result of a single test entrypoint.
+/// Contains the status of the test, how long it took, as well as everything it outputted.
+#[derive(Debug, Serialize)]
 pub struct TestResult {
-	pub output: String,
+	/// Name of the test entrypoint
+	pub entrypoint: String,
+	#[serde(rename = "status")]
 	pub success: TestStatus,
+	pub duration: Duration,
+	/// Output written by the hints during the execution
+	pub captured_stdout: Option<String>,
+	/// Error output written by the hints during the execution
+	pub captured_stderr: Option<String>,
+	/// Output of the cairo runner (ie: what has been written to the `output` builtin)
+	pub execution_output: Option<String>,
+	/// Reason of the failure, if any
+	pub error: Option<String>,
+	/// Error returned by the VM, when `error` is a more readable version of it
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub raw_error: Option<String>,
+	/// Unified diff of the operands of the failed assertion, if the test failed on an `assert_eq`
+	/// (or an `assert_array_eq`)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub diff: Option<String>,
+	/// Seed of the numbers drawn by the test with the `rand` hint, if it drew any (derived from
+	/// the seed of the run and the name of the test, see `test_seed`)
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub random_seed: Option<u64>,
+	/// Resources used by the test, if it completed
+	pub resources: Option<TestResources>,
+	/// Outcome of the fuzzing, for fuzzed tests only
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub fuzz: Option<FuzzReport>,
+	/// Status of each run of a test repeated with `--repeat`, if they are not all the same
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub flaky_runs: Option<Vec<TestStatus>>,
 }
 
-impl From<(String, TestStatus)> for TestResult {
-	fn from(from: (String, TestStatus)) -> Self {
-		Self {
-			output: from.0,
-			success: from.1,
+impl TestResult {
+	/// Number of passing runs, and total number of runs, of a flaky test
+	pub fn flaky_counts(&self) -> Option<(usize, usize)> {
+		self.flaky_runs.as_ref().map(|runs| {
+			(
+				runs.iter().filter(|status| **status == TestStatus::SUCCESS).count(),
+				runs.len(),
+			)
+		})
+	}
+}
+
+/// The alternate form (`{:#}`) also displays the resources used by a successful test
+impl Display for TestResult {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.success {
+			TestStatus::SUCCESS => {
+				write!(
+					f,
+					"[{}] {} ({:?}",
+					"OK".green(),
+					self.entrypoint,
+					self.duration
+				)?;
+				if let Some(resources) = self.resources.as_ref().filter(|_| f.alternate()) {
+					write!(f, ", {resources}")?;
+				}
+				if let Some(fuzz) = &self.fuzz {
+					write!(f, ", {} runs", fuzz.runs)?;
+				}
+				writeln!(f, ")")?;
+			},
+			TestStatus::SKIPPED => match &self.error {
+				Some(reason) =>
+					writeln!(f, "[{}] {} ({reason})", "SKIPPED".yellow(), self.entrypoint)?,
+				None => writeln!(f, "[{}] {}", "SKIPPED".yellow(), self.entrypoint)?,
+			},
+			TestStatus::FAILURE => writeln!(
+				f,
+				"[{}] {}\nError: {}\n",
+				if self.flaky_runs.is_some() {
+					"FLAKY".red()
+				} else {
+					"FAILED".red()
+				},
+				self.entrypoint,
+				self.error.as_deref().unwrap_or_default()
+			)?,
+		};
+		if let Some(diff) = &self.diff {
+			write!(f, "[{}]:\n{}", "diff".yellow(), diff)?;
+		}
+		if let Some(captured_stdout) = &self.captured_stdout {
+			write!(f, "[{}]:\n{}", "captured stdout".blue(), captured_stdout)?;
+		}
+		if let Some(captured_stderr) = &self.captured_stderr {
+			write!(f, "[{}]:\n{}", "captured stderr".red(), captured_stderr)?;
+		}
+		if let Some(execution_output) = &self.execution_output {
+			write!(
+				f,
+				"[{}]:\n{}",
+				"execution output".purple(),
+				felt_format().format_lines(execution_output)
+			)?;
 		}
+		if self.success == TestStatus::SUCCESS {
+			writeln!(f)?;
+		}
+		Ok(())
 	}
 }
 
+/// Results of all the tests executed for a single cairo file
+#[derive(Debug, Serialize)]
+pub struct TestFileResult {
+	/// Path to the original cairo file
+	pub path: PathBuf,
+	pub tests: Vec<TestResult>,
+	/// How the compiled file was obtained, if the file could be compiled
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub compilation: Option<Compilation>,
+	/// Lines executed by the tests, if requested with `--coverage`
+	#[serde(skip)]
+	pub coverage: Option<LineCoverage>,
+	/// Reason why the file could not be tested (ie: it failed to compile, or its `setUp` or
+	/// `tearDown` entrypoint failed), if any
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+}
+
+/// How the compiled version of a test file was obtained
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct Compilation {
+	/// Whether the compiled file was taken from the cache, rather than recompiled
+	pub cached: bool,
+	/// Time spent compiling the file (or looking it up in the cache) and listing its entrypoints
+	pub duration: Duration,
+}
+
+/// Count of the test files served from the cache and of the recompiled ones
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CompilationSummary {
+	pub cache_hits: usize,
+	pub recompiled: usize,
+	/// Total time spent compiling the files (or looking them up in the cache)
+	pub duration: Duration,
+}
+
+impl Display for CompilationSummary {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"{} file(s) served from the cache, {} recompiled in {:.1}s",
+			self.cache_hits,
+			self.recompiled,
+			self.duration.as_secs_f64()
+		)
+	}
+}
+
+impl TestFileResult {
+	/// SUCCESS if none of the tests (nor the lifecycle entrypoints) failed, FAILURE otherwise
+	pub fn status(&self) -> TestStatus {
+		if self.error.is_some() || self.tests.iter().any(|test| test.success == TestStatus::FAILURE)
+		{
+			TestStatus::FAILURE
+		} else {
+			TestStatus::SUCCESS
+		}
+	}
+
+	/// Whether the file could not be compiled (or tested at all), rather than having failing
+	/// tests or lifecycle entrypoints
+	pub fn failed_to_compile(&self) -> bool {
+		self.error.is_some() && self.compilation.is_none()
+	}
+
+	/// Time spent running the tests of the file
+	pub fn execution_duration(&self) -> Duration {
+		self.tests.iter().map(|test| test.duration).sum()
+	}
+}
+
+impl TestFileResult {
+	fn write_header(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Running tests in file {}", self.path.display())?;
+		match self.compilation {
+			Some(Compilation { cached: true, .. }) if f.alternate() => writeln!(f, " (cached)")?,
+			Some(Compilation { cached: false, .. }) if f.alternate() => writeln!(f, " (compiled)")?,
+			_ => writeln!(f)?,
+		}
+		if let Some(error) = &self.error {
+			writeln!(f, "{}", format!("Error: {error}").red())?;
+		}
+		Ok(())
+	}
+}
+
+/// The alternate form (`{:#}`) also displays whether the file has been compiled, and the
+/// resources used by each test.
+/// The time spent compiling the file and running its tests is displayed last.
+impl Display for TestFileResult {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		self.write_header(f)?;
+		for test in &self.tests {
+			if f.alternate() {
+				write!(f, "{test:#}")?;
+			} else {
+				write!(f, "{test}")?;
+			}
+		}
+		if let Some(compilation) = &self.compilation {
+			writeln!(
+				f,
+				"{}: compiled in {}ms, ran {} test(s) in {}ms",
+				self.path.display(),
+				compilation.duration.as_millis(),
+				self.tests.len(),
+				self.execution_duration().as_millis()
+			)?;
+		}
+		writeln!(f)
+	}
+}
+
+/// Count of the executed tests, by status
+#[derive(Debug, Serialize, Default, PartialEq, Eq)]
+pub struct TestSummary {
+	pub total: usize,
+	pub passed: usize,
+	pub failed: usize,
+	pub skipped: usize,
+	/// Failed tests whose runs did not all fail, with `--repeat`
+	pub flaky: usize,
+	/// Largest used size of each memory segment over the completed tests, in cells
+	#[serde(skip_serializing_if = "BTreeMap::is_empty")]
+	pub max_segment_sizes: BTreeMap<String, usize>,
+	/// Time spent running every test file, compilation included
+	pub duration: Duration,
+}
+
+impl TestSummary {
+	pub fn add(&mut self, status: TestStatus) {
+		self.total += 1;
+		match status {
+			TestStatus::SUCCESS => self.passed += 1,
+			TestStatus::FAILURE => self.failed += 1,
+			TestStatus::SKIPPED => self.skipped += 1,
+		}
+	}
+}
+
+impl Display for TestSummary {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} passed, {} failed", self.passed, self.failed)?;
+		if self.flaky > 0 {
+			write!(f, " ({} flaky)", self.flaky)?;
+		}
+		write!(
+			f,
+			", {} skipped in {:.1}s",
+			self.skipped,
+			self.duration.as_secs_f64()
+		)
+	}
+}
+
+/// Resources used by a single test, as displayed in the gas report
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct GasReportEntry {
+	pub path: PathBuf,
+	pub entrypoint: String,
+	#[serde(flatten)]
+	pub resources: TestResources,
+}
+
+/// Resources used by every completed test, sorted by step count (descending)
+#[derive(Debug, Serialize, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct GasReport(pub Vec<GasReportEntry>);
+
+impl GasReport {
+	pub fn new(files: &[TestFileResult]) -> Self {
+		let mut entries: Vec<GasReportEntry> = files
+			.iter()
+			.flat_map(|file| {
+				file.tests.iter().filter_map(|test| {
+					test.resources.clone().map(|resources| GasReportEntry {
+						path: file.path.clone(),
+						entrypoint: test.entrypoint.clone(),
+						resources,
+					})
+				})
+			})
+			.collect();
+		entries.sort_by(|a, b| b.resources.n_steps.cmp(&a.resources.n_steps));
+		Self(entries)
+	}
+}
+
+impl Display for GasReport {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "Gas report:")?;
+		writeln!(
+			f,
+			"{:>12} | {:<60} | {:<40} | segments",
+			"steps", "test", "builtins"
+		)?;
+		for entry in &self.0 {
+			let builtins = entry
+				.resources
+				.builtins
+				.iter()
+				.filter(|(_, n_instances)| **n_instances > 0)
+				.map(|(builtin, n_instances)| format!("{builtin}: {n_instances}"))
+				.collect::<Vec<_>>()
+				.join(", ");
+			writeln!(
+				f,
+				"{:>12} | {:<60} | {:<40} | {}",
+				entry.resources.n_steps,
+				format!("{}::{}", entry.path.display(), entry.entrypoint),
+				builtins,
+				format_segment_sizes(&entry.resources.segments)
+			)?;
+		}
+		Ok(())
+	}
+}
+
+/// Format the non empty segments as `execution: 12, range_check: 2`
+fn format_segment_sizes(segments: &BTreeMap<String, usize>) -> String {
+	segments
+		.iter()
+		.filter(|(_, size)| **size > 0)
+		.map(|(segment, size)| format!("{segment}: {size}"))
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
 /// Execute command output
 #[derive(Debug, Serialize, Default)]
-pub struct TestOutput(String);
+pub struct TestOutput {
+	pub files: Vec<TestFileResult>,
+	pub summary: TestSummary,
+	/// Seed of the fuzzing session, if any fuzzed test has been executed
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub fuzz_seed: Option<u64>,
+	/// Seed of the numbers drawn by the `rand` hint, if any test drew one
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub random_seed: Option<u64>,
+	/// Seed of the random order of the tests, if they have been shuffled
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub shuffle_seed: Option<u64>,
+	/// Only computed when requested with `--gas-report`
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub gas_report: Option<GasReport>,
+	/// Changes of the step counts compared to the gas snapshot, with `--gas-snapshot`
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub gas_deltas: Option<Vec<GasDelta>>,
+	/// Level of detail of the text output
+	#[serde(skip)]
+	pub verbosity: Verbosity,
+	/// Whether the test files have only been compiled (`--compile-only`)
+	#[serde(skip)]
+	pub compile_only: bool,
+	/// Directory in which no test file has been found, if any
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub no_test_files: Option<PathBuf>,
+	/// Whether finding no test file is a failure (`--fail-if-no-tests`)
+	#[serde(skip)]
+	pub fail_if_no_tests: bool,
+	/// Whether the run has been interrupted (with Ctrl-C) before all the tests were run
+	#[serde(skip_serializing_if = "std::ops::Not::not")]
+	pub interrupted: bool,
+}
+
+/// Level of detail of the text output of the test command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+	/// Only the failing tests and the summary (`--quiet`)
+	Quiet,
+	/// The failing tests, grouped by file, and the summary
+	#[default]
+	Failures,
+	/// Every test, grouped by file (`-v`)
+	Tests,
+	/// Every test with its resources, and whether its file was compiled (`-vv`)
+	Details,
+}
+
+impl TestOutput {
+	pub fn push_file(&mut self, file: TestFileResult) {
+		for test in &file.tests {
+			self.summary.add(test.success);
+			if test.flaky_runs.is_some() {
+				self.summary.flaky += 1;
+			}
+			for (segment, size) in test.resources.iter().flat_map(|resources| &resources.segments) {
+				let max_size = self.summary.max_segment_sizes.entry(segment.clone()).or_default();
+				*max_size = (*max_size).max(*size);
+			}
+		}
+		self.files.push(file);
+	}
+
+	/// Count of the files served from the cache and of the recompiled ones
+	pub fn compilation_summary(&self) -> CompilationSummary {
+		let mut summary = CompilationSummary::default();
+		for compilation in self.files.iter().filter_map(|file| file.compilation) {
+			if compilation.cached {
+				summary.cache_hits += 1;
+			} else {
+				summary.recompiled += 1;
+			}
+			summary.duration += compilation.duration;
+		}
+		summary
+	}
+
+	/// Lines executed by the tests of every file, if their coverage has been recorded
+	pub fn line_coverage(&self) -> LineCoverage {
+		let mut line_coverage = LineCoverage::new();
+		for file_coverage in self.files.iter().filter_map(|file| file.coverage.as_ref()) {
+			merge_line_coverage(&mut line_coverage, file_coverage);
+		}
+		line_coverage
+	}
+
+	/// Exit code of the command:
+	/// - `EXIT_CODE_INTERRUPTED` if the run has been interrupted
+	/// - `EXIT_CODE_NO_TEST_FILES` if no test file has been found, with `--fail-if-no-tests`
+	/// - `EXIT_CODE_ERROR` if a file could not be compiled, the other files being run anyway
+	/// - `EXIT_CODE_TEST_FAILURE` if at least one test failed, the `setUp` or `tearDown` entrypoint
+	///   of a file failed, or the step count of a test regressed compared to the gas snapshot
+	/// - 0 otherwise
+	pub fn exit_code(&self) -> u8 {
+		if self.interrupted {
+			EXIT_CODE_INTERRUPTED
+		} else if self.no_test_files.is_some() && self.fail_if_no_tests {
+			EXIT_CODE_NO_TEST_FILES
+		} else if self.files.iter().any(TestFileResult::failed_to_compile) {
+			EXIT_CODE_ERROR
+		} else if self.summary.failed > 0
+			|| self.files.iter().any(|file| file.status() == TestStatus::FAILURE)
+			|| self.gas_deltas.iter().flatten().any(|delta| delta.regression)
+		{
+			EXIT_CODE_TEST_FAILURE
+		} else {
+			0
+		}
+	}
+}
+
+impl TestOutput {
+	/// Display the compilation result of each file, as no test has been run, and how many files
+	/// have been compiled rather than served from the cache
+	fn fmt_compile_only(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let mut failed = 0;
+		for file in &self.files {
+			match &file.error {
+				Some(error) => {
+					failed += 1;
+					let error = format!("Failed to compile {}: {error}", file.path.display());
+					writeln!(f, "{}", error.red())?;
+				},
+				None if self.verbosity > Verbosity::Quiet => {
+					let cached = match file.compilation {
+						Some(Compilation { cached: true, .. }) => " (cached)",
+						_ => "",
+					};
+					writeln!(f, "Compiled {}{cached}", file.path.display())?;
+				},
+				None => (),
+			}
+		}
+		let compilations = self.compilation_summary();
+		let summary = format!(
+			"{} file(s) compiled ({} served from the cache), {failed} failed in {:.1}s",
+			self.files.len() - failed,
+			compilations.cache_hits,
+			self.summary.duration.as_secs_f64()
+		);
+		if failed == 0 {
+			writeln!(f, "{}", summary.green())
+		} else {
+			writeln!(f, "{}", summary.red())
+		}
+	}
+}
 
 impl Display for TestOutput {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "{}", &self.0)
+		if let Some(root) = &self.no_test_files {
+			let message = format!("No test files found under {}", root.display());
+			return if self.fail_if_no_tests {
+				writeln!(f, "{}", message.red())
+			} else {
+				writeln!(f, "{}", message.yellow())
+			}
+		}
+		if self.compile_only {
+			return self.fmt_compile_only(f)
+		}
+		if let Some(shuffle_seed) = self.shuffle_seed {
+			writeln!(f, "Shuffling tests with seed {shuffle_seed}\n")?;
+		}
+		if let Some(fuzz_seed) = self.fuzz_seed {
+			writeln!(f, "Fuzzing with seed {fuzz_seed}\n")?;
+		}
+		if let Some(random_seed) = self.random_seed {
+			writeln!(
+				f,
+				"Drawing random numbers with seed {random_seed} (replay with --seed)\n"
+			)?;
+		}
+		for file in &self.files {
+			let failed_tests = file.tests.iter().filter(|test| test.success == TestStatus::FAILURE);
+			match self.verbosity {
+				_ if self.verbosity <= Verbosity::Failures
+					&& file.status() == TestStatus::SUCCESS =>
+					(),
+				Verbosity::Quiet => {
+					if let Some(error) = &file.error {
+						let error = format!("Error in file {}: {error}", file.path.display());
+						writeln!(f, "{}", error.red())?;
+					}
+					for test in failed_tests {
+						write!(f, "{test}")?;
+					}
+				},
+				Verbosity::Failures => {
+					file.write_header(f)?;
+					for test in failed_tests {
+						write!(f, "{test}")?;
+					}
+					writeln!(f)?;
+				},
+				Verbosity::Tests => write!(f, "{file}")?,
+				Verbosity::Details => write!(f, "{file:#}")?,
+			}
+		}
+		if let Some(gas_report) = &self.gas_report {
+			writeln!(f, "{gas_report}")?;
+			if !self.summary.max_segment_sizes.is_empty() {
+				writeln!(
+					f,
+					"Largest memory segments: {}\n",
+					format_segment_sizes(&self.summary.max_segment_sizes)
+				)?;
+			}
+		}
+		if let Some(gas_deltas) = self.gas_deltas.as_ref().filter(|deltas| !deltas.is_empty()) {
+			writeln!(f, "Gas snapshot:")?;
+			for delta in gas_deltas {
+				writeln!(f, "{delta}")?;
+			}
+			writeln!(f)?;
+		}
+		let flaky_tests: Vec<(&PathBuf, &TestResult)> = self
+			.files
+			.iter()
+			.flat_map(|file| file.tests.iter().map(move |test| (&file.path, test)))
+			.filter(|(_, test)| test.flaky_runs.is_some())
+			.collect();
+		if !flaky_tests.is_empty() {
+			writeln!(f, "Flaky tests:")?;
+			for (path, test) in flaky_tests {
+				let (passed, runs) = test.flaky_counts().unwrap_or_default();
+				writeln!(
+					f,
+					"  {}::{} (passed {passed} of {runs} runs)",
+					path.display(),
+					test.entrypoint
+				)?;
+			}
+			writeln!(f)?;
+		}
+		if self.verbosity >= Verbosity::Tests {
+			writeln!(f, "{}", self.compilation_summary())?;
+		}
+		if self.interrupted {
+			writeln!(
+				f,
+				"{}",
+				"Interrupted, the remaining tests have not been run".yellow()
+			)?;
+		}
+		let summary = self.summary.to_string();
+		if self.exit_code() == 0 {
+			writeln!(f, "{}", summary.green())
+		} else {
+			writeln!(f, "{}", summary.red())
+		}
 	}
 }
 
@@ -107,187 +1151,1374 @@ impl Display for TestOutput {
 /// - post_step_instruction
 ///
 /// see [src/hooks.rs]
-fn setup_hooks() -> Hooks {
+pub(super) fn setup_hooks() -> Hooks {
 	Hooks::new(
 		Arc::new(hooks::pre_step_instruction),
 		Arc::new(hooks::post_step_instruction),
 	)
 }
 
-/// Compile a cairo file, returning a truple
-/// (path_to_original_code, path_to_compiled_code, entrypoints)
-fn compile_and_list_entrypoints(
+/// Same as ``setup_hooks``, with each step recorded in `opt_profile` and `opt_coverage`, and
+/// checked against `opt_breakpoint` (reporting the state of `entrypoint`)
+fn setup_recording_hooks(
+	opt_profile: Option<SharedProfile>,
+	opt_coverage: Option<SharedCoverage>,
+	opt_breakpoint: Option<Breakpoint>,
+	entrypoint: &str,
+) -> Hooks {
+	let entrypoint = entrypoint.to_string();
+	Hooks::new(
+		Arc::new(
+			move |vm: &mut VirtualMachine,
+			      exec_scopes: &mut ExecutionScopes,
+			      constants: &HashMap<String, BigInt>| {
+				if let Some(breakpoint) = &opt_breakpoint {
+					breakpoint.check(vm, &entrypoint)?;
+				}
+				if let Some(Ok(mut coverage)) =
+					opt_coverage.as_ref().map(|coverage| coverage.lock())
+				{
+					coverage.record(vm.get_pc().offset);
+				}
+				hooks::pre_step_instruction(vm, exec_scopes, constants)
+			},
+		),
+		Arc::new(
+			move |vm: &mut VirtualMachine,
+			      exec_scopes: &mut ExecutionScopes,
+			      constants: &HashMap<String, BigInt>| {
+				hooks::post_step_instruction(vm, exec_scopes, constants)?;
+				if let Some(Ok(mut profile)) = opt_profile.as_ref().map(|profile| profile.lock()) {
+					profile.sample(vm);
+				}
+				Ok(())
+			},
+		),
+	)
+}
+
+/// Run the tests of a single cairo file.
+///
+/// This is what the `test` command does for each test file, and can be used to embed the test
+/// runner in another program. The file is compiled (unless it is already cached, see
+/// `compile_with_cache`), then its test entrypoints selected by `options` are executed.
+///
+/// A compiled file which cannot be deserialized (ie: a truncated cache artifact) has its cache
+/// entry invalidated, and is recompiled once before reporting the error.
+///
+/// Returns `None` if the file contains no selected test, or a `TestFileResult` with the result of
+/// each of them otherwise.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::path::PathBuf;
+/// # use cairo_foundry::{cli::commands::test::{run_file, TestCommandError, TestOptions, TestStatus}, compile::Compiler};
+/// # fn foo() -> Result<(), TestCommandError> {
+/// let root = PathBuf::from("tests");
+/// let options = TestOptions {
+///     max_steps: 10000,
+///     ..Default::default()
+/// };
+/// if let Some(file_result) = run_file(&root.join("test_math.cairo"), &root, &Compiler::default(), &options)? {
+///     assert!(file_result.tests.iter().all(|test| test.success == TestStatus::SUCCESS));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn run_file(
+	path_to_code: &PathBuf,
+	root: &PathBuf,
+	compiler: &Compiler,
+	options: &TestOptions,
+) -> Result<Option<TestFileResult>, TestCommandError> {
+	match compile_and_run_file(path_to_code, root, compiler, options) {
+		Err(err) if is_corrupt_compiled_file(&err) => {
+			warn!(
+				"invalid compiled file for {}, recompiling it: {err}",
+				path_to_code.display()
+			);
+			cache::invalidate_cache(path_to_code, root).map_err(compile::Error::from)?;
+			compile_and_run_file(path_to_code, root, compiler, options)
+		},
+		result => result,
+	}
+}
+
+/// Whether an error is caused by a compiled file which cannot be deserialized
+fn is_corrupt_compiled_file(err: &TestCommandError) -> bool {
+	matches!(
+		err,
+		TestCommandError::JsonDeSerialization(_)
+			| TestCommandError::ListTestEntripoints(
+				ListTestEntrypointsError::InvalidCompiledProgramFormat(_)
+			)
+	)
+}
+
+/// Compile a cairo file (unless it is already cached) and list its entrypoints, without running
+/// them. As in `run_file`, a compiled file which cannot be deserialized is recompiled once.
+fn compile_file(
+	path_to_code: &PathBuf,
+	root: &PathBuf,
+	compiler: &Compiler,
+	discovery_cache: bool,
+) -> Result<Compilation, TestCommandError> {
+	let compile_once = || -> Result<Compilation, TestCommandError> {
+		let start = Instant::now();
+		let (.., cache_status) =
+			compile_and_list_entrypoints(path_to_code.clone(), root, compiler, discovery_cache)?;
+		Ok(Compilation {
+			cached: matches!(cache_status, CacheStatus::Cached(_)),
+			duration: start.elapsed(),
+		})
+	};
+	match compile_once() {
+		Err(err) if is_corrupt_compiled_file(&err) => {
+			warn!(
+				"invalid compiled file for {}, recompiling it: {err}",
+				path_to_code.display()
+			);
+			cache::invalidate_cache(path_to_code, root).map_err(compile::Error::from)?;
+			compile_once()
+		},
+		result => result,
+	}
+}
+
+fn compile_and_run_file(
+	path_to_code: &PathBuf,
+	root: &PathBuf,
+	compiler: &Compiler,
+	options: &TestOptions,
+) -> Result<Option<TestFileResult>, TestCommandError> {
+	let start = Instant::now();
+	let (path_to_original, path_to_compiled, test_entrypoints, cache_status) =
+		compile_and_list_entrypoints(
+			path_to_code.clone(),
+			root,
+			compiler,
+			options.discovery_cache,
+		)?;
+	let compilation = Compilation {
+		cached: matches!(cache_status, CacheStatus::Cached(_)),
+		duration: start.elapsed(),
+	};
+	let file_result = run_tests_for_one_file(
+		&mut setup_hint_processor(),
+		root,
+		path_to_original,
+		path_to_compiled,
+		test_entrypoints,
+		setup_hooks(),
+		options,
+	)?;
+	Ok(file_result.map(|file_result| TestFileResult {
+		compilation: Some(compilation),
+		..file_result
+	}))
+}
+
+/// Compile a cairo file (unless it is already cached), returning a tuple
+/// (path_to_original_code, path_to_compiled_code, entrypoints, cache_status)
+///
+/// With `discovery_cache`, the entrypoints of a cached compiled file are read from its discovery
+/// cache (see `read_discovery_cache`), rather than parsed from the compiled file again.
+pub(super) fn compile_and_list_entrypoints(
 	path_to_code: PathBuf,
-) -> Result<(PathBuf, PathBuf, Vec<String>), TestCommandError> {
-	let path_to_compiled = compile(&path_to_code)?;
-	let entrypoints = list_test_entrypoints(&path_to_compiled)?;
-	Ok((path_to_code, path_to_compiled, entrypoints))
+	root: &PathBuf,
+	compiler: &Compiler,
+	discovery_cache: bool,
+) -> Result<(PathBuf, PathBuf, Vec<TestEntrypoint>, CacheStatus), TestCommandError> {
+	let (path_to_compiled, cache_status) = compile_with_cache(&path_to_code, root, compiler)?;
+	let hash = match &cache_status {
+		CacheStatus::Cached(cache) => {
+			debug!("Using cached compiled file {}", path_to_compiled.display());
+			&cache.hash
+		},
+		CacheStatus::Uncached(hash) => hash,
+	};
+	// A file which has just been compiled has to be parsed
+	let opt_cached_entrypoints = match &cache_status {
+		CacheStatus::Cached(_) if discovery_cache => read_discovery_cache(&path_to_compiled, hash),
+		_ => None,
+	};
+	let entrypoints = match opt_cached_entrypoints {
+		Some(entrypoints) => {
+			debug!(
+				"Using the discovery cache of {}",
+				path_to_compiled.display()
+			);
+			entrypoints
+		},
+		None => {
+			let entrypoints = list_test_entrypoints(&path_to_compiled, &path_to_code)?;
+			if discovery_cache {
+				write_discovery_cache(&path_to_compiled, hash, &entrypoints);
+			}
+			entrypoints
+		},
+	};
+	Ok((path_to_code, path_to_compiled, entrypoints, cache_status))
+}
+
+/// Retrieve the execution output of a completed run, if not empty
+fn get_execution_output(runner: &mut CairoRunner, vm: &mut VirtualMachine) -> Option<String> {
+	match runner.get_output(vm) {
+		Ok(runner_output) if !runner_output.is_empty() => Some(runner_output),
+		Ok(_) => None,
+		Err(e) => {
+			warn!("failed to get output from the cairo runner: {e}");
+			None
+		},
+	}
+}
+
+/// Retrieve the resources used by a completed run
+fn get_resources(runner: &CairoRunner, vm: &mut VirtualMachine) -> Option<TestResources> {
+	match runner.get_execution_resources(vm) {
+		Ok(resources) => Some(TestResources {
+			n_steps: resources.n_steps,
+			builtins: resources.builtin_instance_counter.into_iter().collect(),
+			segments: get_segment_sizes(vm),
+		}),
+		Err(e) => {
+			warn!("failed to get execution resources from the cairo runner: {e}");
+			None
+		},
+	}
 }
 
-fn purge_hint_buffer(execution_uuid: &Uuid, output: &mut String) {
-	// Safe to unwrap as long as `init_buffer` has been called before
-	let buffer = get_buffer(execution_uuid).unwrap();
-	if !buffer.is_empty() {
-		output.push_str(&format!("[{}]:\n{}", "captured stdout".blue(), buffer));
+/// Used size of each memory segment, in cells: the `program` and `execution` segments, the
+/// segment of each builtin, and the segments allocated during the execution (eg: by `alloc`)
+/// counted together as `allocated`
+fn get_segment_sizes(vm: &mut VirtualMachine) -> BTreeMap<String, usize> {
+	vm.compute_segments_effective_sizes();
+	// The runner creates the program and execution segments, then the ones of the builtins
+	let mut names = vec![String::from("program"), String::from("execution")];
+	names.extend(vm.get_builtin_runners().iter().map(|(name, _)| name.clone()));
+	let mut segments = BTreeMap::new();
+	let mut index = 0;
+	while let Some(size) = vm.get_segment_used_size(index) {
+		let name = names.get(index).cloned().unwrap_or_else(|| String::from("allocated"));
+		*segments.entry(name).or_default() += size;
+		index += 1;
 	}
-	clear_buffer(execution_uuid);
+	segments
 }
 
+/// Fails with the functions of the program if it does not contain `entrypoint`,
+/// rather than with the opaque error of `Program::from_json`
+fn ensure_entrypoint_exists(
+	program: &ProgramJson,
+	entrypoint: &str,
+) -> Result<(), TestCommandError> {
+	if program.identifiers.contains_key(&format!("__main__.{entrypoint}")) {
+		return Ok(())
+	}
+	let mut available: Vec<String> = program
+		.identifiers
+		.iter()
+		.filter(|(_, identifier)| identifier.type_.as_deref() == Some("function"))
+		.filter_map(|(name, _)| name.strip_prefix("__main__."))
+		.map(String::from)
+		.collect();
+	available.sort();
+	Err(TestCommandError::EntrypointNotFound {
+		entrypoint: entrypoint.to_string(),
+		available,
+	})
+}
+
+/// Time given to the deadline checked between the steps of a test once its timeout is exceeded,
+/// before the test is considered stuck in a hint: the deadline reports the test along with its
+/// output
+const HINT_TIMEOUT_GRACE: Duration = Duration::from_millis(100);
+
 /// Execute a single test.
 /// Take a program and a test name as input, search for this entrypoint in the compiled file
 /// and execute it.
+/// `args` are the explicit arguments given to the entrypoint, `mock_calls` the calls mocked
+/// before its execution (they are updated with the ones it mocks).
 /// It will then return a TestResult, representing the output of the test.
-fn test_single_entrypoint(
+///
+/// With a `timeout`, the test is executed on its own thread (with its own hint processor), so
+/// that a hint which never returns cannot hang the run: the deadline is only checked between the
+/// steps of the VM. A test still running once its timeout is exceeded fails with the output it
+/// captured so far, and its thread is left to finish in the background.
+pub(super) fn test_single_entrypoint(
 	program: ProgramJson,
 	test_entrypoint: &str,
 	hint_processor: &mut FunctionLikeHintProcessor,
 	hooks: Option<Hooks>,
-	max_steps: u64,
+	options: &TestOptions,
+	args: &[BigInt],
+	mock_calls: &mut MockCalls,
 ) -> Result<TestResult, TestCommandError> {
-	let start = Instant::now();
-	let mut output = String::new();
-	let execution_uuid = Uuid::new_v4();
-	init_buffer(execution_uuid);
+	let execution = Execution {
+		uuid: Uuid::new_v4(),
+		start: Instant::now(),
+	};
+	// The buffers are cleared when the guard is dropped, whether the test completes or not
+	let output_buffer = if options.nocapture {
+		OutputBufferGuard::live(execution.uuid, test_entrypoint)
+	} else {
+		let guard = OutputBufferGuard::new(execution.uuid).with_max_output(options.max_output);
+		match &options.output_observer {
+			Some(observer) => guard.with_observer(observer.clone(), test_entrypoint),
+			None => guard,
+		}
+	};
+	let timeout = match options.timeout {
+		Some(timeout) => timeout,
+		None =>
+			return run_single_entrypoint(
+				program,
+				test_entrypoint,
+				execution,
+				hint_processor,
+				hooks,
+				options,
+				args,
+				mock_calls,
+			),
+	};
+	let (sender, receiver) = mpsc::channel();
+	let (entrypoint, options, args, mut worker_mock_calls) = (
+		test_entrypoint.to_string(),
+		options.clone(),
+		args.to_vec(),
+		mock_calls.clone(),
+	);
+	let worker = thread::Builder::new().name(test_entrypoint.to_string()).spawn(move || {
+		let result = run_single_entrypoint(
+			program,
+			&entrypoint,
+			execution,
+			&mut setup_hint_processor(),
+			hooks,
+			&options,
+			&args,
+			&mut worker_mock_calls,
+		);
+		// The state written by a test which timed out after its guard has been dropped
+		clear_execution(&execution.uuid);
+		// The receiver is gone if the test timed out
+		let _ = sender.send((result, worker_mock_calls));
+	})?;
+
+	let (success, error) = match receiver.recv_timeout(timeout + HINT_TIMEOUT_GRACE) {
+		Ok((result, updated_mock_calls)) => {
+			*mock_calls = updated_mock_calls;
+			return result
+		},
+		Err(RecvTimeoutError::Timeout) => (
+			TestStatus::FAILURE,
+			Deadline {
+				start: execution.start,
+				timeout,
+			}
+			.error_message(),
+		),
+		// The worker is gone without sending its result: it panicked
+		Err(RecvTimeoutError::Disconnected) => {
+			let payload = worker.join().err();
+			let message = payload
+				.as_ref()
+				.and_then(|payload| {
+					payload
+						.downcast_ref::<&str>()
+						.map(|message| message.to_string())
+						.or_else(|| payload.downcast_ref::<String>().cloned())
+				})
+				.unwrap_or_default();
+			(
+				TestStatus::FAILURE,
+				format!("the test runner panicked: {message}"),
+			)
+		},
+	};
+	let result = TestResult {
+		entrypoint: test_entrypoint.to_string(),
+		success,
+		duration: execution.start.elapsed(),
+		captured_stdout: output_buffer.content(),
+		captured_stderr: output_buffer.stderr_content(),
+		execution_output: None,
+		error: Some(error),
+		raw_error: None,
+		diff: None,
+		random_seed: clear_random(&execution.uuid),
+		resources: None,
+		fuzz: None,
+		flaky_runs: None,
+	};
+	// Clear the state of the execution now rather than when the worker returns, if it ever does
+	drop(output_buffer);
+	Ok(result)
+}
+
+/// Identifier of the execution of a test, and the instant it started at
+#[derive(Debug, Clone, Copy)]
+struct Execution {
+	uuid: Uuid,
+	start: Instant,
+}
+
+/// Execute a single test on the current thread (see `test_single_entrypoint`), its buffers being
+/// initialized by the caller
+#[allow(clippy::too_many_arguments)]
+fn run_single_entrypoint(
+	program: ProgramJson,
+	test_entrypoint: &str,
+	execution: Execution,
+	hint_processor: &mut FunctionLikeHintProcessor,
+	hooks: Option<Hooks>,
+	options: &TestOptions,
+	args: &[BigInt],
+	mock_calls: &mut MockCalls,
+) -> Result<TestResult, TestCommandError> {
+	let Execution {
+		uuid: execution_uuid,
+		start,
+	} = execution;
+	let opt_deadline = options.timeout.map(|timeout| Deadline { start, timeout });
+	let output_name = options.case_name.as_deref().unwrap_or(test_entrypoint);
 
+	ensure_entrypoint_exists(&program, test_entrypoint)?;
 	let program = Program::from_json(program, Some(test_entrypoint))?;
+	// Profiling, coverage and breakpoints replace the default hooks by the same ones recording
+	// each step, so that the tests run without them do not pay for them
+	let opt_profile = match (&options.profile_dir, &hooks) {
+		(Some(_), Some(_)) => Some(Arc::new(Mutex::new(Profile::new(&program)))),
+		_ => None,
+	};
+	let opt_coverage = options.coverage.clone().filter(|_| hooks.is_some());
+	let opt_breakpoint = options.breakpoint.filter(|_| hooks.is_some());
+	let hooks = match (&opt_profile, &opt_coverage, &opt_breakpoint) {
+		(None, None, None) => hooks,
+		_ => Some(setup_recording_hooks(
+			opt_profile.clone(),
+			opt_coverage,
+			opt_breakpoint,
+			test_entrypoint,
+		)),
+	};
 
-	let res_cairo_run = cairo_run(program, hint_processor, execution_uuid, hooks, max_steps);
+	if !options.seed_storage.is_empty() {
+		update_cheatcodes(execution_uuid, |cheatcodes| {
+			cheatcodes.storage = options.seed_storage.clone()
+		});
+	}
+	seed_random(
+		execution_uuid,
+		test_seed(options.random_seed, test_entrypoint),
+	);
+	// A hint which failed during a previous execution is not the cause of this one's error
+	hint_processor.take_failed_hint();
+	let res_cairo_run = cairo_run(
+		program,
+		hint_processor,
+		execution_uuid,
+		hooks,
+		options.max_steps,
+		opt_deadline,
+		options.stagnation_window,
+		args,
+		mock_calls,
+	);
 	let duration = start.elapsed();
-	let (opt_runner_and_output, test_success) = match res_cairo_run {
-		Ok(res) => {
-			output.push_str(&format!(
-				"[{}] {} ({:?})\n",
-				"OK".green(),
-				test_entrypoint,
-				duration
-			));
-			(Some(res), TestStatus::SUCCESS)
-		},
+	if let (Some(profile_dir), Some(profile)) = (&options.profile_dir, &opt_profile) {
+		if let Ok(profile) = profile.lock() {
+			profile.write(&profile_dir.join(format!("{output_name}.folded")))?;
+		}
+	}
+	let mut raw_error = None;
+	let (opt_runner_and_vm, mut success, mut error) = match res_cairo_run {
+		// The deadline is only checked between two instructions, a test may have exceeded it
+		// during its last step
+		Ok(_) if opt_deadline.map_or(false, |deadline| deadline.is_reached()) => (
+			None,
+			TestStatus::FAILURE,
+			opt_deadline.map(|deadline| deadline.error_message()),
+		),
+		Ok(res) => (Some(res), TestStatus::SUCCESS, None),
 		Err(CairoRunError::VirtualMachine(VirtualMachineError::CustomHint(
 			custom_error_message,
-		))) if custom_error_message == "skip" => {
-			output.push_str(&format!("[{}] {}\n", "SKIPPED".yellow(), test_entrypoint,));
-			(None, TestStatus::SUCCESS)
-		},
+		))) if custom_error_message == "skip" => (None, TestStatus::SKIPPED, None),
 		Err(CairoRunError::VirtualMachine(VirtualMachineError::CustomHint(
 			custom_error_message,
-		))) if custom_error_message == EXPECT_REVERT_FLAG => {
-			output.push_str(&format!(
-				"[{}] {}\nError: execution did not revert while expect_revert() was specified\n\n",
-				"FAILED".red(),
-				test_entrypoint,
-			));
-			(None, TestStatus::FAILURE)
+		))) if custom_error_message.starts_with(MAX_STEPS_ERROR_PREFIX) => {
+			let n_steps = custom_error_message
+				.trim_start_matches(MAX_STEPS_ERROR_PREFIX)
+				.trim_start_matches(':')
+				.trim();
+			let error = format!("exceeded max steps ({n_steps}); increase with --max-steps");
+			raw_error = Some(custom_error_message);
+			(None, TestStatus::FAILURE, Some(error))
 		},
+		Err(CairoRunError::VirtualMachine(VirtualMachineError::CustomHint(
+			custom_error_message,
+		))) if custom_error_message == EXPECT_REVERT_FLAG => (
+			None,
+			TestStatus::FAILURE,
+			Some(String::from(
+				"execution did not revert while expect_revert() was specified",
+			)),
+		),
+		Err(CairoRunError::VirtualMachine(VirtualMachineError::CustomHint(
+			custom_error_message,
+		))) if custom_error_message.starts_with(TIMEOUT_ERROR_PREFIX)
+			|| custom_error_message.starts_with(STAGNATION_ERROR_PREFIX)
+			|| custom_error_message.starts_with(EXPECT_REVERT_MISMATCH_PREFIX)
+			|| custom_error_message == EXPECT_REVERT_REGION_NOT_REVERTED
+			|| custom_error_message.starts_with(ASSERTION_FAILED_PREFIX)
+			|| custom_error_message.starts_with(PANIC_PREFIX) =>
+			(None, TestStatus::FAILURE, Some(custom_error_message)),
+		// The error of a failing hint is reported along with its code and pc
 		Err(e) => {
-			output.push_str(&format!(
-				"[{}] {}\nError: {:?}\n\n",
-				"FAILED".red(),
-				test_entrypoint,
-				e
-			));
-			(None, TestStatus::FAILURE)
+			let error = match hint_processor.take_failed_hint() {
+				Some(failed_hint) => format!("{e:?} ({failed_hint})"),
+				None => format!("{e:?}"),
+			};
+			(None, TestStatus::FAILURE, Some(error))
 		},
 	};
 
-	purge_hint_buffer(&execution_uuid, &mut output);
-	let (mut runner, mut vm) = match opt_runner_and_output {
-		Some(runner_and_vm) => runner_and_vm,
-		None => return Ok((output, test_success).into()),
+	// An assertion which failed within an `expect_revert` did not make the test fail
+	let diff = take_assertion_diff(&execution_uuid).filter(|_| success == TestStatus::FAILURE);
+	let random_seed = clear_random(&execution_uuid);
+
+	// The expected events are only checked once the execution completed
+	if success == TestStatus::SUCCESS {
+		if let Err(message) = check_expected_events(&execution_uuid) {
+			success = TestStatus::FAILURE;
+			error = Some(message);
+		}
+	}
+
+	let captured_stdout = get_buffer(&execution_uuid).filter(|buffer| !buffer.is_empty());
+	let captured_stderr = get_stderr_buffer(&execution_uuid).filter(|buffer| !buffer.is_empty());
+
+	// Retrieve the execution output and resources if the run completed
+	let (execution_output, resources) = match opt_runner_and_vm {
+		Some((mut runner, mut vm)) => {
+			let output = get_execution_output(&mut runner, &mut vm);
+			let resources = get_resources(&runner, &mut vm);
+			if let (Some(dump_dir), TestStatus::SUCCESS) = (&options.state_dump_dir, &success) {
+				StateDump::new(&mut vm).write(&dump_dir.join(format!("{output_name}.json")))?;
+			}
+			(output, resources)
+		},
+		None => (None, None),
 	};
 
-	// Display the execution output if present
-	match runner.get_output(&mut vm) {
-		Ok(runner_output) =>
-			if !runner_output.is_empty() {
-				output.push_str(&format!(
-					"[{}]:\n{}",
-					"execution output".purple(),
-					&runner_output
-				));
+	Ok(TestResult {
+		entrypoint: test_entrypoint.to_string(),
+		success,
+		duration,
+		captured_stdout,
+		captured_stderr,
+		execution_output,
+		error,
+		raw_error,
+		diff,
+		random_seed,
+		resources,
+		fuzz: None,
+		flaky_runs: None,
+	})
+}
+
+/// Execute a single test `options.repeat` times (see `test_single_entrypoint`).
+///
+/// If the runs do not all have the same status, the test is flaky: it fails with the result of
+/// its first failing run, along with the status of each run.
+/// A skipped test is not repeated. Every run starts with the same `mock_calls`, they are updated
+/// with the ones mocked by the first run.
+fn repeat_single_entrypoint(
+	program: ProgramJson,
+	test_entrypoint: &str,
+	hint_processor: &mut FunctionLikeHintProcessor,
+	hooks: Option<Hooks>,
+	options: &TestOptions,
+	args: &[BigInt],
+	mock_calls: &mut MockCalls,
+) -> Result<TestResult, TestCommandError> {
+	let initial_mock_calls = mock_calls.clone();
+	let first = test_single_entrypoint(
+		program.clone(),
+		test_entrypoint,
+		hint_processor,
+		hooks.clone(),
+		options,
+		args,
+		mock_calls,
+	)?;
+	if options.repeat <= 1 || first.success == TestStatus::SKIPPED {
+		return Ok(first)
+	}
+
+	let mut runs = vec![first.success];
+	let mut first_failure = None;
+	for _ in 1..options.repeat {
+		let result = test_single_entrypoint(
+			program.clone(),
+			test_entrypoint,
+			hint_processor,
+			hooks.clone(),
+			options,
+			args,
+			&mut initial_mock_calls.clone(),
+		)?;
+		runs.push(result.success);
+		if first_failure.is_none() && result.success == TestStatus::FAILURE {
+			first_failure = Some(result);
+		}
+	}
+	if runs.iter().all(|status| *status == first.success) {
+		return Ok(first)
+	}
+
+	let mut result = match first_failure {
+		Some(failure) if first.success != TestStatus::FAILURE => failure,
+		_ => first,
+	};
+	result.success = TestStatus::FAILURE;
+	result.flaky_runs = Some(runs);
+	let (passed, total) = result.flaky_counts().unwrap_or_default();
+	let reason = result.error.take().unwrap_or_else(|| String::from("the test did not pass"));
+	result.error = Some(format!("flaky, passed {passed} of {total} runs: {reason}"));
+	Ok(result)
+}
+
+/// Execute a fuzzed test.
+/// The entrypoint is executed up to `options.fuzz_runs` times, with `n_args` random felts
+/// as arguments, stopping at the first run which does not succeed.
+/// The inputs only depend on `options.fuzz_seed`, so that a session can be replayed.
+/// The inputs of the failing run, the seed they were generated from, and their minimized version
+/// (see `shrink`) are reported in the returned TestResult.
+/// Runs executed while shrinking are bound by the same `max_steps` and `timeout` options.
+/// Every run starts with the same `mock_calls`.
+fn fuzz_single_entrypoint(
+	program: ProgramJson,
+	test_entrypoint: &str,
+	n_args: usize,
+	hint_processor: &mut FunctionLikeHintProcessor,
+	hooks: Option<Hooks>,
+	options: &TestOptions,
+	mock_calls: &MockCalls,
+) -> Result<TestResult, TestCommandError> {
+	let start = Instant::now();
+	let mut seeds = StdRng::seed_from_u64(options.fuzz_seed);
+	let mut runs = 0;
+
+	let (mut result, counterexample) = loop {
+		runs += 1;
+		let seed = seeds.gen::<u64>();
+		let args = random_felts(seed, n_args);
+		let result = test_single_entrypoint(
+			program.clone(),
+			test_entrypoint,
+			hint_processor,
+			hooks.clone(),
+			options,
+			&args,
+			&mut mock_calls.clone(),
+		)?;
+		match result.success {
+			TestStatus::FAILURE => {
+				let minimized_args = shrink(&args, |args| {
+					test_single_entrypoint(
+						program.clone(),
+						test_entrypoint,
+						hint_processor,
+						hooks.clone(),
+						options,
+						args,
+						&mut mock_calls.clone(),
+					)
+					.map(|result| result.success == TestStatus::FAILURE)
+				})?;
+				break (
+					result,
+					Some(Counterexample::new(
+						options.fuzz_seed,
+						seed,
+						&args,
+						&minimized_args,
+					)),
+				)
 			},
-		Err(e) => eprintln!("failed to get output from the cairo runner: {e}"),
+			TestStatus::SKIPPED => break (result, None),
+			TestStatus::SUCCESS if runs >= options.fuzz_runs => break (result, None),
+			TestStatus::SUCCESS => (),
+		}
 	};
 
-	output.push('\n');
-	Ok((output, test_success).into())
+	if let Some(counterexample) = &counterexample {
+		result.error = Some(format!(
+			"{}\nFailing input: {}",
+			result.error.unwrap_or_default(),
+			counterexample
+		));
+	}
+	result.duration = start.elapsed();
+	// The resources of the last run are not representative of the test
+	result.resources = None;
+	result.fuzz = Some(FuzzReport {
+		runs,
+		counterexample,
+	});
+	Ok(result)
+}
+
+/// Returns true for the `setUp` and `tearDown` entrypoints, which are not tests
+pub fn is_lifecycle_entrypoint(entrypoint: &str) -> bool {
+	entrypoint == SETUP_ENTRYPOINT || entrypoint == TEARDOWN_ENTRYPOINT
+}
+
+/// Execute a lifecycle entrypoint (`setUp` or `tearDown`).
+/// `mock_calls` are updated with the calls it mocks, so that they apply to the next executions.
+///
+/// return the reason of its failure, if it failed
+fn run_lifecycle_entrypoint(
+	program: ProgramJson,
+	entrypoint: &str,
+	hint_processor: &mut FunctionLikeHintProcessor,
+	hooks: Option<Hooks>,
+	options: &TestOptions,
+	mock_calls: &mut MockCalls,
+) -> Result<Option<String>, TestCommandError> {
+	let result = test_single_entrypoint(
+		program,
+		entrypoint,
+		hint_processor,
+		hooks,
+		options,
+		&[],
+		mock_calls,
+	)?;
+	Ok(match result.success {
+		TestStatus::FAILURE => Some(format!(
+			"{entrypoint} failed: {}",
+			result.error.unwrap_or_default()
+		)),
+		TestStatus::SUCCESS | TestStatus::SKIPPED => None,
+	})
+}
+
+/// Result of a test which has not been executed
+fn skipped_test_result(entrypoint: &str, reason: &str) -> TestResult {
+	TestResult {
+		entrypoint: entrypoint.to_string(),
+		success: TestStatus::SKIPPED,
+		duration: Duration::ZERO,
+		captured_stdout: None,
+		captured_stderr: None,
+		execution_output: None,
+		error: Some(reason.to_string()),
+		raw_error: None,
+		diff: None,
+		random_seed: None,
+		resources: None,
+		fuzz: None,
+		flaky_runs: None,
+	}
+}
+
+/// Directory of the outputs of a test file (eg: its profiles) under `dir`: the path of the file
+/// relatively to `root`, without its extension, so that the outputs of the files with the same
+/// name in different directories do not overwrite each other
+fn file_output_dir(dir: &Path, path: &Path, root: &Path) -> PathBuf {
+	let relative_path = path
+		.strip_prefix(root)
+		.unwrap_or_else(|_| Path::new(path.file_name().unwrap_or_default()));
+	dir.join(relative_path.with_extension(""))
 }
 
 /// Run every test contained in a cairo file.
-/// this function will deserialize a compiled cairo file, and call ``test_single_entrypoint`` on
-/// each entrypoint provided.
-/// It will then return a TestResult corresponding to all the tests (SUCCESS if all the test
-/// succeded, FAILURE otherwise).
+/// this function will deserialize a compiled cairo file, and call ``test_single_entrypoint`` (or
+/// ``fuzz_single_entrypoint`` for fuzzed tests) on each entrypoint selected by the ``options``.
+///
+/// If the file exposes a `setUp` entrypoint, it is executed before each test (or once before
+/// all of them, with ``options.setup_once``), and the calls it mocks apply to the tests.
+/// A failing `setUp` marks the file as failed, and its remaining tests as skipped.
+/// A `tearDown` entrypoint is executed the same way, after the tests.
+/// Once the tests of the file ran for longer than ``options.file_timeout``, the remaining ones are
+/// skipped.
+///
+/// It will then return a TestFileResult containing the result of each test,
+/// or None if no entrypoint of the file was selected.
 fn run_tests_for_one_file(
 	hint_processor: &mut FunctionLikeHintProcessor,
+	root: &Path,
 	path_to_original: PathBuf,
 	path_to_compiled: PathBuf,
-	test_entrypoints: Vec<String>,
+	test_entrypoints: Vec<TestEntrypoint>,
 	hooks: Hooks,
-	max_steps: u64,
-) -> Result<TestResult, TestCommandError> {
-	let file = fs::File::open(path_to_compiled).unwrap();
+	options: &TestOptions,
+) -> Result<Option<TestFileResult>, TestCommandError> {
+	let has_setup = test_entrypoints.iter().any(|entrypoint| entrypoint.name == SETUP_ENTRYPOINT);
+	// The directives of the file apply to all its entrypoints, lifecycle ones included
+	let opt_file_max_steps = test_entrypoints.first().and_then(|entrypoint| entrypoint.max_steps);
+	let has_teardown =
+		test_entrypoints.iter().any(|entrypoint| entrypoint.name == TEARDOWN_ENTRYPOINT);
+	let mut test_entrypoints: Vec<TestEntrypoint> = test_entrypoints
+		.into_iter()
+		.filter(|test_entrypoint| {
+			!is_lifecycle_entrypoint(&test_entrypoint.name)
+				&& options.filter.selects(test_entrypoint)
+		})
+		.collect();
+	if test_entrypoints.is_empty() {
+		return Ok(None)
+	}
+	if let Some(shuffle_seed) = options.shuffle_seed {
+		test_entrypoints.shuffle(&mut StdRng::seed_from_u64(shuffle_seed));
+	}
+	// The profiles, the states and the coverage are recorded per file
+	let file_options = TestOptions {
+		max_steps: opt_file_max_steps.unwrap_or(options.max_steps),
+		profile_dir: options
+			.profile_dir
+			.as_ref()
+			.map(|profile_dir| file_output_dir(profile_dir, &path_to_original, root)),
+		state_dump_dir: options
+			.state_dump_dir
+			.as_ref()
+			.map(|dump_dir| file_output_dir(dump_dir, &path_to_original, root)),
+		coverage: options.coverage.as_ref().map(|_| SharedCoverage::default()),
+		..options.clone()
+	};
+	let options = &file_options;
+
+	// A table-driven test is expanded into one case per row of its data file
+	let mut test_cases = Vec::new();
+	for test_entrypoint in test_entrypoints {
+		let opt_data_file = data_file_path(&path_to_original, &test_entrypoint.name)
+			.filter(|_| !is_fuzz_entrypoint(&test_entrypoint.name));
+		match opt_data_file {
+			Some(data_file) => {
+				let rows = read_data_file(&data_file)?;
+				test_cases.extend(
+					rows.into_iter().enumerate().map(|row| (test_entrypoint.clone(), Some(row))),
+				);
+			},
+			None => test_cases.push((test_entrypoint, None)),
+		}
+	}
+
+	let file = fs::File::open(&path_to_compiled)?;
 	let reader = io::BufReader::new(file);
 	let program_json = deserialize_program_json(reader)?;
 
-	let output = format!("Running tests in file {}\n", path_to_original.display());
-	let res = test_entrypoints
-		.into_iter()
-		.map(|test_entrypoint| {
-			test_single_entrypoint(
+	let mut file_result = TestFileResult {
+		path: path_to_original,
+		tests: Vec::new(),
+		error: None,
+		compilation: None,
+		coverage: None,
+	};
+
+	let file_start = Instant::now();
+	let mut setup_mock_calls = MockCalls::new();
+	if has_setup && options.setup_once {
+		file_result.error = run_lifecycle_entrypoint(
+			program_json.clone(),
+			SETUP_ENTRYPOINT,
+			hint_processor,
+			Some(hooks.clone()),
+			options,
+			&mut setup_mock_calls,
+		)?;
+	}
+
+	for (
+		TestEntrypoint {
+			name: test_entrypoint,
+			tags,
+			..
+		},
+		opt_row,
+	) in test_cases
+	{
+		// The cases of a table-driven test are named after the index of their row
+		let case_name = match &opt_row {
+			Some((index, _)) => format!("{test_entrypoint}[{index}]"),
+			None => test_entrypoint.clone(),
+		};
+		// A table-driven test counts once in the progress, as when the tests were counted
+		if let Some(Ok(mut progress)) = options.progress.as_ref().map(|progress| progress.lock()) {
+			if opt_row.as_ref().map_or(true, |(index, _)| *index == 0) {
+				progress.start(&case_name);
+			}
+		}
+		if file_result.error.is_some() {
+			if options.fail_fast {
+				break
+			}
+			file_result.tests.push(skipped_test_result(&case_name, "setUp failed"));
+			continue
+		}
+		if let Some(file_timeout) = options
+			.file_timeout
+			.filter(|file_timeout| file_start.elapsed() >= *file_timeout)
+		{
+			let reason = format!(
+				"the file exceeded its timeout of {}s",
+				file_timeout.as_secs_f64()
+			);
+			file_result.tests.push(skipped_test_result(&case_name, &reason));
+			continue
+		}
+
+		let mut mock_calls = setup_mock_calls.clone();
+		if has_setup && !options.setup_once {
+			file_result.error = run_lifecycle_entrypoint(
+				program_json.clone(),
+				SETUP_ENTRYPOINT,
+				hint_processor,
+				Some(hooks.clone()),
+				options,
+				&mut mock_calls,
+			)?;
+			if file_result.error.is_some() {
+				if options.fail_fast {
+					break
+				}
+				file_result.tests.push(skipped_test_result(&case_name, "setUp failed"));
+				continue
+			}
+		}
+
+		let mut result = if is_fuzz_entrypoint(&test_entrypoint) {
+			let arguments = list_entrypoint_arguments(&path_to_compiled, &test_entrypoint)?;
+			fuzz_single_entrypoint(
 				program_json.clone(),
 				&test_entrypoint,
+				arguments.len(),
 				hint_processor,
 				Some(hooks.clone()),
-				max_steps,
-			)
-		})
-		.collect::<Result<Vec<_>, TestCommandError>>()?
-		.into_iter()
-		.fold((output, TestStatus::SUCCESS), |mut a, b| {
-			a.0.push_str(&b.output);
-			// SUCCESS if both a.1 and b.success are SUCCESS, otherwise, FAILURE
-			a.1 = if a.1 == TestStatus::SUCCESS && b.success == TestStatus::SUCCESS {
-				TestStatus::SUCCESS
-			} else {
-				TestStatus::FAILURE
+				options,
+				&mock_calls,
+			)?
+		} else {
+			let (args, args_source) = match &opt_row {
+				Some((_, row)) => (row.as_slice(), "its data file row"),
+				None => (
+					options.calldata.get(&test_entrypoint).map_or(&[][..], Vec::as_slice),
+					"the calldata",
+				),
 			};
-			a
-		});
-	Ok(res.into())
+			let arguments = list_entrypoint_arguments(&path_to_compiled, &test_entrypoint)?;
+			if args.len() != arguments.len() {
+				let mut result = skipped_test_result(
+					&case_name,
+					&format!(
+						"{test_entrypoint} expects {} argument(s) ({}), got {} in {args_source}",
+						arguments.len(),
+						arguments.join(", "),
+						args.len()
+					),
+				);
+				result.success = TestStatus::FAILURE;
+				file_result.tests.push(result);
+				if options.fail_fast {
+					break
+				}
+				continue
+			}
+			let case_options;
+			let test_options = match &opt_row {
+				Some(_) => {
+					case_options = TestOptions {
+						case_name: Some(case_name.clone()),
+						..options.clone()
+					};
+					&case_options
+				},
+				None => options,
+			};
+			repeat_single_entrypoint(
+				program_json.clone(),
+				&test_entrypoint,
+				hint_processor,
+				Some(hooks.clone()),
+				test_options,
+				args,
+				&mut mock_calls,
+			)?
+		};
+
+		let is_snapshot = tags.iter().any(|tag| tag == SNAPSHOT_TAG);
+		if is_snapshot && result.success == TestStatus::SUCCESS {
+			let mismatch = check_snapshot(
+				&snapshot_path(&file_result.path, &case_name),
+				result.execution_output.as_deref().unwrap_or_default(),
+				options.update_snapshots,
+			)?;
+			if mismatch.is_some() {
+				result.success = TestStatus::FAILURE;
+				result.error = mismatch;
+			}
+		}
+
+		if has_teardown && !options.setup_once {
+			let teardown_error = run_lifecycle_entrypoint(
+				program_json.clone(),
+				TEARDOWN_ENTRYPOINT,
+				hint_processor,
+				Some(hooks.clone()),
+				options,
+				&mut mock_calls,
+			)?;
+			if let (Some(teardown_error), TestStatus::SUCCESS) = (teardown_error, result.success) {
+				result.success = TestStatus::FAILURE;
+				result.error = Some(teardown_error);
+			}
+		}
+
+		if let Some((_, row)) = &opt_row {
+			result.entrypoint = case_name;
+			if result.success == TestStatus::FAILURE {
+				let inputs: Vec<String> = row.iter().map(BigInt::to_string).collect();
+				let error = result.error.take().unwrap_or_default();
+				result.error = Some(format!("{error} (inputs: {})", inputs.join(", ")));
+			}
+		}
+
+		let failed = result.success == TestStatus::FAILURE;
+		file_result.tests.push(result);
+		if (options.fail_fast && failed) || interrupt::is_interrupted() {
+			break
+		}
+	}
+
+	if has_teardown && options.setup_once && file_result.error.is_none() {
+		file_result.error = run_lifecycle_entrypoint(
+			program_json,
+			TEARDOWN_ENTRYPOINT,
+			hint_processor,
+			Some(hooks),
+			options,
+			&mut setup_mock_calls,
+		)?;
+	}
+
+	if let Some(Ok(coverage)) = options.coverage.as_ref().map(|coverage| coverage.lock()) {
+		let instruction_locations = list_instruction_locations(&path_to_compiled)?;
+		file_result.coverage = Some(coverage.line_coverage(&instruction_locations));
+	}
+
+	Ok(Some(file_result))
 }
 
-impl CommandExecution<TestOutput, TestCommandError> for TestArgs {
-	fn exec(&self) -> Result<TestOutput, TestCommandError> {
-		// Declare hints
-		let mut hint_processor = setup_hint_processor();
-		let hooks = setup_hooks();
+/// Deepest directory containing every directory of `dirs` (an empty path if they have nothing in
+/// common, which stands for the current directory when they are all relative)
+fn common_directory(dirs: &[PathBuf]) -> PathBuf {
+	let mut common = dirs.first().cloned().unwrap_or_default();
+	while !dirs.iter().all(|dir| dir.starts_with(&common)) {
+		if !common.pop() {
+			return PathBuf::new()
+		}
+	}
+	common
+}
+
+impl TestArgs {
+	fn verbosity(&self) -> Verbosity {
+		match (self.quiet, self.verbose) {
+			(true, _) => Verbosity::Quiet,
+			(false, 0) => Verbosity::Failures,
+			(false, 1) => Verbosity::Tests,
+			(false, _) => Verbosity::Details,
+		}
+	}
 
-		list_test_files(&self.root)?
-			//.into_par_iter()
+	/// Whether the progress line is displayed (see `--no-progress`)
+	fn shows_progress(&self) -> bool {
+		!self.no_progress
+			&& !self.quiet
+			&& !self.nocapture
+			&& !self.compile_only
+			&& atty::is(atty::Stream::Stderr)
+	}
+
+	/// Number of test files compiled concurrently (see `--jobs`)
+	fn jobs(&self) -> usize {
+		self.jobs
+			.unwrap_or_else(|| thread::available_parallelism().map_or(1, NonZeroUsize::get))
+	}
+
+	/// Directories (or test files) the tests are discovered in: the `paths` given as arguments,
+	/// or `root`.
+	///
+	/// Several paths are canonicalized unless they are all relative (or all absolute) and do not
+	/// go up a directory, so that their common directory can be found from their components.
+	pub(super) fn roots(&self) -> Vec<PathBuf> {
+		if self.paths.is_empty() {
+			return vec![self.root.clone()]
+		}
+		let goes_up = |path: &PathBuf| path.components().any(|c| c == Component::ParentDir);
+		let all_absolute = self.paths.iter().all(|path| path.is_absolute());
+		let all_relative = self.paths.iter().all(|path| path.is_relative());
+		if self.paths.len() == 1
+			|| ((all_absolute || all_relative) && !self.paths.iter().any(goes_up))
+		{
+			return self.paths.clone()
+		}
+		self.paths
+			.iter()
+			.map(|path| path.canonicalize().unwrap_or_else(|_| path.clone()))
+			.collect()
+	}
+
+	/// Directory the paths of the test files are relative to (in the cache and in the gas
+	/// snapshot): `root`, or its parent directory if it is a single test file. With several
+	/// roots, the directory containing all of them.
+	pub(super) fn root_dir(&self) -> PathBuf {
+		let dirs: Vec<PathBuf> = self
+			.roots()
 			.into_iter()
-			.map(compile_and_list_entrypoints)
-			.map(|res| -> Result<TestResult, TestCommandError> {
-				match res {
-					Ok((path_to_original, path_to_compiled, test_entrypoints)) =>
-						run_tests_for_one_file(
-							&mut hint_processor,
-							path_to_original,
-							path_to_compiled,
-							test_entrypoints,
-							hooks.clone(),
-							self.max_steps,
-						),
-					Err(err) => Err(err),
+			.map(|root| {
+				if root.is_file() {
+					root.parent().map(PathBuf::from).unwrap_or_default()
+				} else {
+					root
 				}
 			})
-			.for_each(|test_result| match test_result {
-				Ok(result) => {
-					println!("{}", result.output);
+			.collect();
+		common_directory(&dirs)
+	}
+
+	/// Check that the roots can be read, so that a directory which exists but cannot be read is
+	/// reported as such rather than by an error of the test discovery
+	fn check_root(&self) -> Result<(), TestCommandError> {
+		for root in self.roots() {
+			if root.is_file() {
+				fs::File::open(&root)
+					.map_err(|e| TestCommandError::UnreadableTestFile(root.clone(), e))?;
+			} else {
+				fs::read_dir(&root)
+					.map_err(|e| TestCommandError::UnreadableRoot(root.clone(), e))?;
+			}
+		}
+		Ok(())
+	}
+
+	/// List the test files of the roots (or the roots themselves if they are files), according to
+	/// the `--test-glob`, `--exclude` and `--only-changed` options.
+	///
+	/// The files found in several roots (eg: nested ones) are only listed once, in the first one
+	fn list_test_files(&self) -> Result<Vec<PathBuf>, ListTestsFilesError> {
+		let mut listed = HashSet::new();
+		let mut test_files = Vec::new();
+		for root in self.roots() {
+			for test_file in self.list_root_test_files(&root)? {
+				if listed.insert(test_file.canonicalize().unwrap_or_else(|_| test_file.clone())) {
+					test_files.push(test_file);
+				}
+			}
+		}
+		Ok(test_files)
+	}
+
+	/// List the test files of a single root (see `list_test_files`)
+	fn list_root_test_files(&self, root: &Path) -> Result<Vec<PathBuf>, ListTestsFilesError> {
+		if root.is_file() {
+			return Ok(vec![root.to_path_buf()])
+		}
+		let test_files = list_test_files(root, self.test_glob.as_deref(), &self.excludes)?;
+		if !self.only_changed {
+			return Ok(test_files)
+		}
+		match git_changed_files(root, &self.base) {
+			Ok(changed) => Ok(select_changed_test_files(
+				test_files,
+				&self.root_dir(),
+				&changed,
+			)),
+			Err(e) => {
+				warn!("running every test file, failed to list the changed files: {e}");
+				Ok(test_files)
+			},
+		}
+	}
+
+	/// Compare the step counts of a run against the gas snapshot of `root`.
+	/// The snapshot is written if it does not exist yet, or if `--update-snapshots` is set, keeping
+	/// the step counts of the tests which have not been run.
+	fn check_gas_snapshot(&self, output: &mut TestOutput) -> Result<(), TestCommandError> {
+		let path = self.root_dir().join(GAS_SNAPSHOT_FILE_NAME);
+		let gas_snapshot_error = |e| TestCommandError::GasSnapshot(path.clone(), e);
+		let current = gas_snapshot(&output.files, &self.root_dir());
+		if !self.update_snapshots && path.exists() {
+			let baseline = read_gas_snapshot(&path).map_err(gas_snapshot_error)?;
+			output.gas_deltas = Some(compare_gas_snapshots(
+				&baseline,
+				&current,
+				self.gas_snapshot_threshold,
+			));
+			return Ok(())
+		}
+
+		let mut snapshot = if path.exists() {
+			read_gas_snapshot(&path).map_err(gas_snapshot_error)?
+		} else {
+			GasSnapshot::new()
+		};
+		snapshot.extend(current);
+		write_gas_snapshot(&path, &snapshot).map_err(gas_snapshot_error)
+	}
+
+	/// Compile the given test files (unless they are cached) and run their tests
+	fn run_test_files(&self, mut test_files: Vec<PathBuf>) -> TestOutput {
+		let mut options = TestOptions::from(self);
+		let compiler = Compiler {
+			binary: self.compiler.clone(),
+			cairo_path: self.cairo_path.clone(),
+			args: self.compiler_args.clone(),
+		};
+
+		let start = Instant::now();
+		let mut output = TestOutput {
+			no_test_files: Some(self.root_dir()).filter(|_| test_files.is_empty()),
+			fail_if_no_tests: self.fail_if_no_tests,
+			..Default::default()
+		};
+		if let Some(shuffle_seed) = options.shuffle_seed {
+			test_files.shuffle(&mut StdRng::seed_from_u64(shuffle_seed));
+			output.shuffle_seed = Some(shuffle_seed);
+		}
+
+		let mut compilations = parallel::compile_test_files(
+			&test_files,
+			&self.root_dir(),
+			&compiler,
+			options.discovery_cache,
+			self.jobs(),
+		);
+		if self.shows_progress() {
+			let total = count_tests(&test_files, &self.root_dir(), &compiler, &options);
+			options.progress = Some(Arc::new(Mutex::new(Progress::new(total))));
+		}
+		for path_to_code in test_files {
+			if interrupt::is_interrupted() {
+				break
+			}
+			let opt_compilation = compilations.remove(&path_to_code);
+			if self.compile_only {
+				let (compilation, error) = match opt_compilation.unwrap_or_else(|| {
+					compile_file(
+						&path_to_code,
+						&self.root_dir(),
+						&compiler,
+						options.discovery_cache,
+					)
+					.map_err(|err| err.to_string())
+				}) {
+					Ok(compilation) => (Some(compilation), None),
+					Err(error) => (None, Some(error)),
+				};
+				output.push_file(TestFileResult {
+					path: path_to_code,
+					tests: Vec::new(),
+					error,
+					compilation,
+					coverage: None,
+				});
+				if options.fail_fast && output.exit_code() != 0 {
+					break
+				}
+				continue
+			}
+			if let Some(Err(error)) = opt_compilation {
+				output.push_file(TestFileResult {
+					path: path_to_code,
+					tests: Vec::new(),
+					error: Some(error),
+					compilation: None,
+					coverage: None,
+				});
+				if options.fail_fast {
+					break
+				}
+				continue
+			}
+			match run_file(&path_to_code, &self.root_dir(), &compiler, &options) {
+				// The file has been found in the cache, it has actually been compiled beforehand
+				Ok(Some(mut file_result)) => {
+					if let Some(Ok(compilation)) = opt_compilation {
+						file_result.compilation = Some(compilation);
+					}
+					output.push_file(file_result)
 				},
-				Err(err) => println!("{}", format!("Error: {err}").red()),
-			});
+				Ok(None) => (),
+				// A file which cannot be tested fails, without stopping the other files
+				// (unless `--fail-fast` is set)
+				Err(err) => output.push_file(TestFileResult {
+					path: path_to_code,
+					tests: Vec::new(),
+					error: Some(err.to_string()),
+					compilation: None,
+					coverage: None,
+				}),
+			}
+			if options.fail_fast && output.exit_code() != 0 {
+				break
+			}
+		}
+
+		if let Some(Ok(progress)) = options.progress.as_ref().map(|progress| progress.lock()) {
+			progress.clear();
+		}
+
+		let has_fuzzed_tests =
+			output.files.iter().flat_map(|file| &file.tests).any(|test| test.fuzz.is_some());
+		if has_fuzzed_tests {
+			output.fuzz_seed = Some(options.fuzz_seed);
+		}
+		let has_random_tests = output
+			.files
+			.iter()
+			.flat_map(|file| &file.tests)
+			.any(|test| test.random_seed.is_some());
+		if has_random_tests {
+			output.random_seed = Some(options.random_seed);
+		}
+
+		if self.gas_report {
+			output.gas_report = Some(GasReport::new(&output.files));
+		}
 
-		Ok(Default::default())
+		output.summary.duration = start.elapsed();
+		output.verbosity = self.verbosity();
+		output.compile_only = self.compile_only;
+		output.interrupted = interrupt::is_interrupted();
+		output
+	}
+}
+
+impl CommandExecution<TestOutput, TestCommandError> for TestArgs {
+	fn exec(&self) -> Result<TestOutput, TestCommandError> {
+		self.check_root()?;
+		if self.watch {
+			return watch::watch(self)
+		}
+
+		let mut output = self.run_test_files(self.list_test_files()?);
+		if self.gas_snapshot {
+			self.check_gas_snapshot(&mut output)?;
+		}
+		if let Some(path) = &self.junit {
+			junit::write_junit_report(&output, path)
+				.map_err(|e| TestCommandError::JunitReport(path.clone(), e))?;
+		}
+		if let Some(path) = &self.coverage {
+			fs::write(path, to_lcov(&output.line_coverage()))
+				.map_err(|e| TestCommandError::CoverageReport(path.clone(), e))?;
+		}
+		Ok(output)
 	}
 }
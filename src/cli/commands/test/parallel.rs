@@ -0,0 +1,98 @@
+use std::{
+	collections::HashMap,
+	path::PathBuf,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Mutex,
+	},
+	thread,
+};
+
+use log::debug;
+
+use super::{compile_file, Compilation};
+use crate::{cli::interrupt, compile::Compiler};
+
+/// Compile the test files (unless they are cached) on `jobs` threads, so that the compilation of
+/// a cold cache is not bound by the compiler invocations being run one after the other.
+///
+/// The tests are still run one file after the other, in the order of `test_files`, once every
+/// file is compiled.
+///
+/// return the compilation of each file, or its error message. Nothing is compiled (and the map is
+/// empty) with a single job or a single file, the files being compiled as they are run then.
+pub(super) fn compile_test_files(
+	test_files: &[PathBuf],
+	root: &PathBuf,
+	compiler: &Compiler,
+	discovery_cache: bool,
+	jobs: usize,
+) -> HashMap<PathBuf, Result<Compilation, String>> {
+	if jobs <= 1 || test_files.len() <= 1 {
+		return HashMap::new()
+	}
+
+	let compilations = Mutex::new(HashMap::new());
+	let next_file = AtomicUsize::new(0);
+	thread::scope(|scope| {
+		for _ in 0..jobs.min(test_files.len()) {
+			scope.spawn(|| {
+				while let Some(path) = test_files.get(next_file.fetch_add(1, Ordering::Relaxed)) {
+					if interrupt::is_interrupted() {
+						break
+					}
+					debug!("compiling {} ahead of its tests", path.display());
+					let compilation = compile_file(path, root, compiler, discovery_cache)
+						.map_err(|err| err.to_string());
+					if let Ok(mut compilations) = compilations.lock() {
+						compilations.insert(path.clone(), compilation);
+					}
+				}
+			});
+		}
+	});
+	compilations.into_inner().unwrap_or_default()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+	use super::*;
+	use std::{fs, os::unix::fs::PermissionsExt, time::Instant};
+	use uuid::Uuid;
+
+	#[test]
+	fn files_are_compiled_concurrently() {
+		let root = std::env::temp_dir().join(Uuid::new_v4().to_string());
+		fs::create_dir_all(&root).unwrap();
+		// A compiler taking half a second to produce an (empty) program
+		let binary = root.join("slow-cairo-compile");
+		fs::write(
+			&binary,
+			"#!/bin/sh\nif [ \"$1\" = \"--version\" ]; then echo slow-cairo-compile; exit 0; fi\n\
+			 sleep 0.5\necho '{\"identifiers\": {}}'\n",
+		)
+		.unwrap();
+		fs::set_permissions(&binary, fs::Permissions::from_mode(0o755)).unwrap();
+		let test_files: Vec<PathBuf> = (0..4)
+			.map(|i| {
+				let path = root.join(format!("test_{i}.cairo"));
+				fs::write(&path, format!("// {i}\n")).unwrap();
+				path
+			})
+			.collect();
+		let compiler = Compiler {
+			binary: binary.display().to_string(),
+			..Default::default()
+		};
+
+		let start = Instant::now();
+		let compilations = compile_test_files(&test_files, &root, &compiler, true, 4);
+		let elapsed = start.elapsed();
+
+		assert_eq!(4, compilations.len());
+		// Compiling the files one after the other would take at least 2s
+		assert!(elapsed.as_secs_f64() < 1.5, "compiled in {elapsed:?}");
+		assert!(compile_test_files(&test_files, &root, &compiler, true, 1).is_empty());
+		fs::remove_dir_all(root).unwrap();
+	}
+}
@@ -0,0 +1,111 @@
+use std::{
+	io::{self, Write},
+	path::PathBuf,
+	sync::{Arc, Mutex},
+};
+
+use super::{compile_and_list_entrypoints, is_lifecycle_entrypoint, TestOptions};
+use crate::compile::Compiler;
+
+/// Moves the cursor to the start of the line and erases it
+const CLEAR_LINE: &str = "\r\x1b[2K";
+
+/// Progress line of a run (eg: `[42/317] running test_foo`), written to stderr and replaced as
+/// each test starts
+#[derive(Debug, Default)]
+pub struct Progress {
+	total: usize,
+	started: usize,
+}
+
+/// A progress shared between the test runner and the report of the tests of each file
+pub type SharedProgress = Arc<Mutex<Progress>>;
+
+impl Progress {
+	pub fn new(total: usize) -> Self {
+		Self { total, started: 0 }
+	}
+
+	/// Line displayed while `test` runs
+	fn next_line(&mut self, test: &str) -> String {
+		self.started += 1;
+		// A file recompiled since the tests were counted may have more of them
+		format!(
+			"[{}/{}] running {test}",
+			self.started,
+			self.total.max(self.started)
+		)
+	}
+
+	/// Replace the progress line by the one of `test`
+	pub fn start(&mut self, test: &str) {
+		let line = self.next_line(test);
+		let mut stderr = io::stderr();
+		let _ = write!(stderr, "{CLEAR_LINE}{line}");
+		let _ = stderr.flush();
+	}
+
+	/// Erase the progress line, once the run is over
+	pub fn clear(&self) {
+		let mut stderr = io::stderr();
+		let _ = write!(stderr, "{CLEAR_LINE}");
+		let _ = stderr.flush();
+	}
+}
+
+/// Number of tests of the `test_files` selected by `options`, each table-driven test counting
+/// once. The files are compiled (unless they are cached), the ones which cannot be compiled count
+/// no test.
+pub(super) fn count_tests(
+	test_files: &[PathBuf],
+	root: &PathBuf,
+	compiler: &Compiler,
+	options: &TestOptions,
+) -> usize {
+	test_files
+		.iter()
+		.filter_map(|path| {
+			compile_and_list_entrypoints(path.clone(), root, compiler, options.discovery_cache).ok()
+		})
+		.map(|(_, _, entrypoints, _)| {
+			entrypoints
+				.iter()
+				.filter(|entrypoint| {
+					!is_lifecycle_entrypoint(&entrypoint.name) && options.filter.selects(entrypoint)
+				})
+				.count()
+		})
+		.sum()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn progress_line_counts_the_started_tests() {
+		let mut progress = Progress::new(2);
+
+		assert_eq!("[1/2] running test_foo", progress.next_line("test_foo"));
+		assert_eq!("[2/2] running test_bar", progress.next_line("test_bar"));
+		assert_eq!("[3/3] running test_baz", progress.next_line("test_baz"));
+	}
+
+	#[test]
+	fn selected_tests_of_every_file_are_counted() {
+		let test_files = vec![
+			PathBuf::from("src/cli/commands/test/test_cairo_programs/ordering.cairo"),
+			PathBuf::from("src/cli/commands/test/test_cairo_programs/lifecycle.cairo"),
+		];
+		let root = PathBuf::from("src/cli/commands/test/test_cairo_programs");
+
+		let total = count_tests(
+			&test_files,
+			&root,
+			&Compiler::default(),
+			&TestOptions::default(),
+		);
+
+		assert_eq!(3 + 1, total);
+	}
+}
@@ -0,0 +1,80 @@
+use similar::TextDiff;
+use std::{
+	fs, io,
+	path::{Path, PathBuf},
+};
+
+/// Tag of the tests whose execution output is compared against a snapshot
+pub const SNAPSHOT_TAG: &str = "snapshot";
+
+const SNAPSHOT_FILE_EXTENSION: &str = "snap";
+
+/// Path of the snapshot of a test, next to its cairo file.
+/// eg: `tests/test_foo.test_bar.snap` for the `test_bar` entrypoint of `tests/test_foo.cairo`
+pub fn snapshot_path(path_to_original: &Path, entrypoint: &str) -> PathBuf {
+	let stem = path_to_original.file_stem().unwrap_or_default().to_string_lossy();
+	path_to_original.with_file_name(format!("{stem}.{entrypoint}.{SNAPSHOT_FILE_EXTENSION}"))
+}
+
+/// Compare the execution output of a test with its snapshot.
+/// The snapshot is recorded if it does not exist yet, or if `update` is set.
+///
+/// return a description of the mismatch (with a unified diff), if the output does not match
+pub fn check_snapshot(
+	path_to_snapshot: &Path,
+	output: &str,
+	update: bool,
+) -> Result<Option<String>, io::Error> {
+	if update || !path_to_snapshot.exists() {
+		fs::write(path_to_snapshot, output)?;
+		return Ok(None)
+	}
+
+	let snapshot = fs::read_to_string(path_to_snapshot)?;
+	if snapshot == output {
+		return Ok(None)
+	}
+
+	let diff = TextDiff::from_lines(&snapshot, output)
+		.unified_diff()
+		.header("snapshot", "output")
+		.to_string();
+	Ok(Some(format!(
+		"execution output does not match snapshot {} (run with --update-snapshots to \
+		 update it)\n{diff}",
+		path_to_snapshot.display()
+	)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use uuid::Uuid;
+
+	#[test]
+	fn snapshot_is_stored_next_to_the_cairo_file() {
+		assert_eq!(
+			PathBuf::from("tests/test_foo.test_bar.snap"),
+			snapshot_path(Path::new("tests/test_foo.cairo"), "test_bar")
+		);
+	}
+
+	#[test]
+	fn snapshot_is_recorded_then_compared() {
+		let path = std::env::temp_dir().join(format!("{}.snap", Uuid::new_v4()));
+
+		assert_eq!(None, check_snapshot(&path, "50\n", false).unwrap());
+		assert_eq!("50\n", fs::read_to_string(&path).unwrap());
+		assert_eq!(None, check_snapshot(&path, "50\n", false).unwrap());
+
+		let mismatch = check_snapshot(&path, "55\n", false).unwrap().expect("Should mismatch");
+		assert!(mismatch.contains("-50"));
+		assert!(mismatch.contains("+55"));
+		assert_eq!("50\n", fs::read_to_string(&path).unwrap());
+
+		assert_eq!(None, check_snapshot(&path, "55\n", true).unwrap());
+		assert_eq!("55\n", fs::read_to_string(&path).unwrap());
+
+		fs::remove_file(path).unwrap();
+	}
+}
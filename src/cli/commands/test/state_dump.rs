@@ -0,0 +1,82 @@
+use cairo_rs::{
+	types::relocatable::{MaybeRelocatable, Relocatable},
+	vm::vm_core::VirtualMachine,
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+/// Final state of the VM after a completed execution, written with `--dump-state`
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct StateDump {
+	pub pc: String,
+	pub ap: String,
+	pub fp: String,
+	/// Memory cells of each segment, by segment index, `None` for the cells which are not set.
+	/// Felts are written in decimal, and relocatable values as `segment:offset`.
+	pub segments: BTreeMap<usize, Vec<Option<String>>>,
+}
+
+pub(super) fn format_relocatable(relocatable: &Relocatable) -> String {
+	format!("{}:{}", relocatable.segment_index, relocatable.offset)
+}
+
+pub(super) fn format_value(value: &MaybeRelocatable) -> String {
+	match value {
+		MaybeRelocatable::Int(felt) => felt.to_string(),
+		MaybeRelocatable::RelocatableValue(relocatable) => format_relocatable(relocatable),
+	}
+}
+
+impl StateDump {
+	/// Registers and memory segments of the `vm`
+	pub fn new(vm: &mut VirtualMachine) -> Self {
+		vm.compute_segments_effective_sizes();
+		let mut segments = BTreeMap::new();
+		let mut index = 0;
+		while let Some(size) = vm.get_segment_used_size(index) {
+			let start = MaybeRelocatable::from((index as isize, 0));
+			let cells = vm
+				.get_range(&start, size)
+				.map(|cells| {
+					cells
+						.iter()
+						.map(|cell| cell.as_ref().map(|value| format_value(value)))
+						.collect()
+				})
+				.unwrap_or_default();
+			segments.insert(index, cells);
+			index += 1;
+		}
+		Self {
+			pc: format_relocatable(&vm.get_pc()),
+			ap: format_relocatable(&vm.get_ap()),
+			fp: format_relocatable(&vm.get_fp()),
+			segments,
+		}
+	}
+
+	/// Write the state as json to `path`, creating its parent directories
+	pub fn write(&self, path: &Path) -> Result<(), io::Error> {
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		fs::write(path, serde_json::to_string_pretty(self)?)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use num_bigint::BigInt;
+
+	#[test]
+	fn values_are_formatted_as_felts_or_addresses() {
+		assert_eq!("42", format_value(&MaybeRelocatable::Int(BigInt::from(42))));
+		assert_eq!(
+			"1:3",
+			format_value(&MaybeRelocatable::RelocatableValue(Relocatable::from((
+				1, 3
+			))))
+		);
+	}
+}
@@ -0,0 +1,4 @@
+func test_sum(a, b) {
+    assert a + b = 5;
+    return ();
+}
@@ -0,0 +1,9 @@
+func test_first_failure() {
+    assert 1 = 2;
+    return ();
+}
+
+func test_second_failure() {
+    assert 1 = 3;
+    return ();
+}
@@ -0,0 +1,6 @@
+func test_failing_hint() {
+    let x = 42;
+    // `missing` is not a variable of the hint, it cannot be printed
+    %{ print("x is", missing) %}
+    return ();
+}
@@ -0,0 +1,8 @@
+func setUp() {
+    assert 21 = 42;
+    return ();
+}
+
+func test_never_executed() {
+    return ();
+}
@@ -0,0 +1,11 @@
+func test_zeta() {
+    return ();
+}
+
+func test_alpha() {
+    return ();
+}
+
+func test_mid() {
+    return ();
+}
@@ -0,0 +1,9 @@
+func helper() -> felt {
+    return 1;
+}
+
+func test_calls_helper() {
+    let x = helper();
+    assert x = 1;
+    return ();
+}
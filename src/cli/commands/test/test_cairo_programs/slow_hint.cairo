@@ -0,0 +1,6 @@
+func test_slow_hint() {
+    let x = 42;
+    %{ print("x is", x) %}
+    %{ test_sleep() %}
+    return ();
+}
@@ -0,0 +1,4 @@
+func test_sum(a, b, sum) {
+    assert a + b = sum;
+    return ();
+}
@@ -0,0 +1,9 @@
+// @slow
+func test_slow() {
+    return ();
+}
+
+// @fast @unit
+func test_fast() {
+    return ();
+}
@@ -1,10 +1,49 @@
-use crate::cli::commands::{test::TestArgs, CommandExecution};
-use cairo_rs::serde::deserialize_program::deserialize_program_json;
-use std::{fs::File, io::BufReader, path::PathBuf};
+use crate::{
+	cli::commands::{
+		test::TestArgs, CommandExecution, EXIT_CODE_ERROR, EXIT_CODE_INTERRUPTED,
+		EXIT_CODE_NO_TEST_FILES, EXIT_CODE_TEST_FAILURE,
+	},
+	compile::{
+		cache::{self, CacheStatus},
+		Compiler, CAIRO_COMPILE_BINARY,
+	},
+	fuzz::DEFAULT_FUZZ_RUNS,
+	hints::{
+		output_buffer::{OutputObserver, DEFAULT_MAX_OUTPUT},
+		register_hint, CustomHint, MockCalls,
+	},
+	hooks::TIMEOUT_ERROR_PREFIX,
+	io::{
+		calldata::{Calldata, SeedStorage},
+		compiled_programs::{discovery_cache_path, list_entrypoint_arguments, TestEntrypoint},
+	},
+};
+use assert_matches::assert_matches;
+use cairo_rs::{
+	hint_processor::hint_processor_definition::HintReference,
+	serde::deserialize_program::{deserialize_program_json, ApTracking},
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+use rstest::rstest;
+use std::{
+	collections::{BTreeMap, HashMap},
+	fs::{self, File},
+	io::BufReader,
+	path::PathBuf,
+	sync::{Arc, Mutex},
+	thread,
+	time::{Duration, Instant},
+};
+use uuid::Uuid;
 
 use super::{
-	compile_and_list_entrypoints, setup_hint_processor, setup_hooks, test_single_entrypoint,
-	TestCommandError, TestResult,
+	breakpoint::{Breakpoint, BREAKPOINT_ERROR_PREFIX},
+	compile_and_list_entrypoints, fuzz_single_entrypoint, run_file, setup_hint_processor,
+	setup_hooks, test_single_entrypoint, Compilation, EntrypointFilter, GasReport,
+	TestCommandError, TestFileResult, TestOptions, TestOutput, TestResources, TestResult,
+	TestStatus, TestSummary, Verbosity,
 };
 
 pub fn run_single_test(
@@ -12,7 +51,9 @@ pub fn run_single_test(
 	test_path: &PathBuf,
 	max_steps: u64,
 ) -> Result<TestResult, TestCommandError> {
-	let (_, path_to_compiled, _) = compile_and_list_entrypoints(test_path.to_owned())?;
+	let root = test_path.parent().map(PathBuf::from).unwrap_or_default();
+	let (_, path_to_compiled, ..) =
+		compile_and_list_entrypoints(test_path.to_owned(), &root, &Compiler::default(), true)?;
 	let file = File::open(path_to_compiled).unwrap();
 	let reader = BufReader::new(file);
 	let program_json = deserialize_program_json(reader)?;
@@ -22,16 +63,1238 @@ pub fn run_single_test(
 		test_name,
 		&mut setup_hint_processor(),
 		Some(setup_hooks()),
-		max_steps,
+		&TestOptions {
+			max_steps,
+			..Default::default()
+		},
+		&[],
+		&mut MockCalls::new(),
+	)
+}
+
+pub fn run_single_fuzz_test(
+	test_name: &str,
+	test_path: &PathBuf,
+	fuzz_runs: usize,
+	fuzz_seed: u64,
+) -> Result<TestResult, TestCommandError> {
+	let root = test_path.parent().map(PathBuf::from).unwrap_or_default();
+	let (_, path_to_compiled, ..) =
+		compile_and_list_entrypoints(test_path.to_owned(), &root, &Compiler::default(), true)?;
+	let arguments = list_entrypoint_arguments(&path_to_compiled, test_name)?;
+	let file = File::open(path_to_compiled).unwrap();
+	let reader = BufReader::new(file);
+	let program_json = deserialize_program_json(reader)?;
+
+	fuzz_single_entrypoint(
+		program_json,
+		test_name,
+		arguments.len(),
+		&mut setup_hint_processor(),
+		Some(setup_hooks()),
+		&TestOptions {
+			max_steps: 1000000,
+			fuzz_runs,
+			fuzz_seed,
+			..Default::default()
+		},
+		&MockCalls::new(),
 	)
 }
 
+pub fn run_test_file(
+	test_path: &PathBuf,
+	options: &TestOptions,
+) -> Result<Option<TestFileResult>, TestCommandError> {
+	let root = test_path.parent().map(PathBuf::from).unwrap_or_default();
+	run_file(test_path, &root, &Compiler::default(), options)
+}
+
+fn test_args(root: &str) -> TestArgs {
+	TestArgs {
+		root: PathBuf::from(root),
+		paths: Vec::new(),
+		max_steps: 1000000,
+		filter: None,
+		skip: None,
+		tags: Vec::new(),
+		excluded_tags: Vec::new(),
+		compiler: CAIRO_COMPILE_BINARY.to_string(),
+		cairo_path: Vec::new(),
+		compiler_args: Vec::new(),
+		timeout: None,
+		timeout_per_file: None,
+		stagnation_window: None,
+		gas_report: false,
+		gas_snapshot: false,
+		gas_snapshot_threshold: 0.0,
+		fuzz_runs: DEFAULT_FUZZ_RUNS,
+		fuzz_seed: None,
+		seed: None,
+		setup_once: false,
+		calldata: None,
+		seed_storage: None,
+		update_snapshots: false,
+		shuffle: None,
+		profile: None,
+		dump_state: None,
+		break_at: None,
+		break_continue: false,
+		coverage: None,
+		fail_fast: false,
+		compile_only: false,
+		watch: false,
+		test_glob: None,
+		excludes: Vec::new(),
+		only_changed: false,
+		base: String::from("HEAD"),
+		repeat: 1,
+		fail_if_no_tests: false,
+		nocapture: false,
+		max_output: DEFAULT_MAX_OUTPUT,
+		jobs: None,
+		no_discovery_cache: false,
+		no_progress: false,
+		verbose: 0,
+		quiet: false,
+		junit: None,
+	}
+}
+
 #[test]
 fn test_cairo_contracts() {
-	TestArgs {
-		root: PathBuf::from("./test_cairo_contracts"),
+	test_args("./test_cairo_contracts").exec().unwrap();
+}
+
+#[test]
+fn root_can_be_a_single_test_file() {
+	let output = test_args("./test_nested_dirs/test_root.cairo").exec().unwrap();
+
+	assert_eq!(1, output.files.len());
+	assert_eq!(
+		PathBuf::from("./test_nested_dirs/test_root.cairo"),
+		output.files[0].path
+	);
+	assert_eq!(TestStatus::SUCCESS, output.files[0].status());
+}
+
+#[test]
+fn positional_test_file_runs_only_the_matching_tests() {
+	let mut args = test_args("./test_cairo_contracts");
+	args.paths = vec![PathBuf::from("./test_nested_dirs/sub/math_test.cairo")];
+	args.filter = Some(String::from("test_math"));
+
+	let output = args.exec().unwrap();
+	assert_eq!(1, output.files.len());
+	assert_eq!(
+		PathBuf::from("./test_nested_dirs/sub/math_test.cairo"),
+		output.files[0].path
+	);
+	let names: Vec<&str> =
+		output.files[0].tests.iter().map(|test| test.entrypoint.as_str()).collect();
+	assert_eq!(vec!["test_math"], names);
+}
+
+#[test]
+fn tests_of_several_paths_are_aggregated() {
+	let mut args = test_args("./");
+	args.paths = vec![
+		PathBuf::from("./test_nested_dirs/sub"),
+		PathBuf::from("./test_nested_dirs/sub/deeper"),
+		PathBuf::from("./test_nested_dirs/test_root.cairo"),
+	];
+	assert_eq!(PathBuf::from("./test_nested_dirs"), args.root_dir());
+
+	let output = args.exec().unwrap();
+	// The files of the nested path are only run once
+	let mut paths: Vec<&PathBuf> = output.files.iter().map(|file| &file.path).collect();
+	paths.sort();
+	assert_eq!(
+		vec![
+			&PathBuf::from("./test_nested_dirs/sub/deeper/test_deep.cairo"),
+			&PathBuf::from("./test_nested_dirs/sub/math_test.cairo"),
+			&PathBuf::from("./test_nested_dirs/test_root.cairo"),
+		],
+		paths
+	);
+	let tests: usize = output.files.iter().map(|file| file.tests.len()).sum();
+	assert_eq!(tests, output.summary.total);
+	assert_eq!(output.summary.total, output.summary.passed);
+}
+
+#[cfg(unix)]
+#[test]
+fn unreadable_root_is_reported() {
+	use std::os::unix::fs::PermissionsExt;
+
+	let root = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	fs::create_dir_all(&root).unwrap();
+	fs::set_permissions(&root, fs::Permissions::from_mode(0o000)).unwrap();
+	// Permissions are not enforced for a privileged user
+	let readable = fs::read_dir(&root).is_ok();
+
+	let result = test_args(root.to_str().unwrap()).exec();
+	fs::set_permissions(&root, fs::Permissions::from_mode(0o755)).unwrap();
+	fs::remove_dir_all(&root).unwrap();
+	if readable {
+		return
+	}
+	let err = result.unwrap_err();
+	assert_matches!(err, TestCommandError::UnreadableRoot(..));
+	assert!(
+		err.to_string()
+			.starts_with(&format!("cannot read test directory {}", root.display())),
+		"{err}"
+	);
+}
+
+#[test]
+fn storage_is_seeded_before_each_test() {
+	let args = TestArgs {
+		filter: Some(String::from("test_load_seeded_storage")),
+		seed_storage: Some(SeedStorage::from([(
+			(BigInt::from(0x789), BigInt::from(1)),
+			BigInt::from(42),
+		)])),
+		..test_args("src/hints/cheatcodes/test_cairo_programs/cheatcodes.cairo")
+	};
+	let output = args.exec().unwrap();
+
+	assert_eq!(1, output.summary.total);
+	assert_eq!(0, output.exit_code(), "{output}");
+}
+
+#[test]
+fn loops_which_do_not_change_the_state_are_detected() {
+	let args = TestArgs {
+		stagnation_window: Some(10),
+		..test_args("src/hooks/test_cairo_programs/tight_loop.cairo")
+	};
+	let output = args.exec().unwrap();
+
+	let result = &output.files[0].tests[0];
+	assert_eq!(TestStatus::FAILURE, result.success);
+	let error = result.error.as_deref().unwrap_or_default();
+	assert!(
+		error.starts_with("possible infinite loop at pc "),
+		"{error}"
+	);
+
+	// Without the detection, the loop runs until the step limit
+	let output = test_args("src/hooks/test_cairo_programs/tight_loop.cairo").exec().unwrap();
+	let error = output.files[0].tests[0].error.as_deref().unwrap_or_default();
+	assert!(error.starts_with("exceeded max steps"), "{error}");
+}
+
+#[test]
+fn only_test_files_are_compiled() {
+	let output = test_args("./test_nested_dirs").exec().unwrap();
+
+	// lib.cairo does not compile, it would fail if it were considered as a test file
+	assert_eq!(3, output.files.len());
+	assert!(output.files.iter().all(|file| file.status() == TestStatus::SUCCESS));
+	assert_eq!(0, output.exit_code());
+}
+
+#[test]
+fn excluded_test_files_are_not_compiled() {
+	let args = TestArgs {
+		excludes: vec![String::from("sub")],
+		..test_args("./test_nested_dirs")
+	};
+	let output = args.exec().unwrap();
+
+	let paths: Vec<&PathBuf> = output.files.iter().map(|file| &file.path).collect();
+	assert_eq!(
+		vec![&PathBuf::from("./test_nested_dirs/test_root.cairo")],
+		paths
+	);
+}
+
+#[test]
+fn compilation_failure_only_fails_its_file() {
+	let output = test_args("src/cli/commands/test/test_cairo_programs").exec().unwrap();
+
+	let failing_file = output
+		.files
+		.iter()
+		.find(|file| file.path.ends_with("test_syntax_error.cairo"))
+		.expect("Should contain the file failing to compile");
+	assert_eq!(TestStatus::FAILURE, failing_file.status());
+	assert!(failing_file.error.is_some());
+
+	let valid_file = output
+		.files
+		.iter()
+		.find(|file| file.path.ends_with("test_valid.cairo"))
+		.expect("Should contain the valid file");
+	assert_eq!(TestStatus::SUCCESS, valid_file.status());
+	assert_eq!(EXIT_CODE_ERROR, output.exit_code());
+}
+
+#[test]
+fn compile_only_does_not_run_the_tests() {
+	let args = TestArgs {
+		compile_only: true,
+		..test_args("src/cli/commands/test/test_cairo_programs")
+	};
+	let output = args.exec().unwrap();
+
+	assert_eq!(0, output.summary.total);
+	let failing_file = output
+		.files
+		.iter()
+		.find(|file| file.path.ends_with("test_syntax_error.cairo"))
+		.expect("Should contain the file failing to compile");
+	assert!(failing_file.error.is_some());
+	assert!(output
+		.files
+		.iter()
+		.filter(|file| file.error.is_none())
+		.all(|file| file.compilation.is_some() && file.tests.is_empty()));
+	assert_eq!(EXIT_CODE_ERROR, output.exit_code());
+
+	let display = output.to_string();
+	assert!(display.contains("Failed to compile"), "{display}");
+	assert!(display.contains("failed in"), "{display}");
+
+	// The cache has been updated, the files are not compiled again
+	let output = args.exec().unwrap();
+	assert_eq!(0, output.compilation_summary().recompiled);
+	let compiled = output.files.iter().filter(|file| file.error.is_none()).count();
+	assert!(
+		output.to_string().contains(&format!("({compiled} served from the cache)")),
+		"{output}"
+	);
+}
+
+#[test]
+fn gas_snapshot_is_written_then_compared() {
+	let root = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	fs::create_dir_all(&root).unwrap();
+	fs::copy(
+		"src/cli/commands/test/test_cairo_programs/ordering.cairo",
+		root.join("test_ordering.cairo"),
+	)
+	.unwrap();
+	let args = TestArgs {
+		gas_snapshot: true,
+		gas_snapshot_threshold: 10.0,
+		..test_args(root.to_str().unwrap())
+	};
+
+	let output = args.exec().unwrap();
+	assert_eq!(None, output.gas_deltas);
+	let path = root.join(".gas-snapshot");
+	let snapshot = fs::read_to_string(&path).unwrap();
+	assert_eq!(3, snapshot.lines().count());
+	assert!(snapshot.starts_with("test_ordering.cairo::test_alpha "));
+
+	let output = args.exec().unwrap();
+	assert_eq!(Some(Vec::new()), output.gas_deltas);
+	assert_eq!(0, output.exit_code());
+
+	// A lower baseline makes the current step count a regression
+	fs::write(
+		&path,
+		"test_ordering.cairo::test_alpha 1
+",
+	)
+	.unwrap();
+	let output = args.exec().unwrap();
+	let gas_deltas = output.gas_deltas.as_ref().expect("Should be compared");
+	assert_eq!(1, gas_deltas.len());
+	assert!(gas_deltas[0].regression);
+	assert_eq!(EXIT_CODE_TEST_FAILURE, output.exit_code());
+	fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn filter_selects_matching_entrypoints() {
+	let filter = EntrypointFilter::new(Some("array_sum"), None);
+	assert!(filter.is_selected("test_array_sum_positive"));
+	assert!(!filter.is_selected("test_mock_call"));
+
+	let filter = EntrypointFilter::new(Some("test_*_positive"), None);
+	assert!(filter.is_selected("test_array_sum_positive"));
+	assert!(!filter.is_selected("test_array_sum_positive2"));
+}
+
+#[test]
+fn filter_rejects_skipped_entrypoints() {
+	let filter = EntrypointFilter::new(None, Some("negative"));
+	assert!(filter.is_selected("test_array_sum_positive"));
+	assert!(!filter.is_selected("test_array_sum_negative"));
+
+	let filter = EntrypointFilter::new(Some("array_sum"), Some("*_negative"));
+	assert!(filter.is_selected("test_array_sum_positive"));
+	assert!(!filter.is_selected("test_array_sum_negative"));
+
+	assert!(EntrypointFilter::default().is_selected("test_array_sum_negative"));
+}
+
+#[test]
+fn filter_selects_entrypoints_by_tags() {
+	let tags = |tags: &[&str]| tags.iter().map(|tag| tag.to_string()).collect::<Vec<_>>();
+
+	let filter = EntrypointFilter::default().with_tags(&tags(&["slow"]), &tags(&["flaky"]));
+	assert!(filter.is_selected_by_tags(&tags(&["slow"])));
+	assert!(filter.is_selected_by_tags(&tags(&["integration", "slow"])));
+	assert!(!filter.is_selected_by_tags(&tags(&["slow", "flaky"])));
+	assert!(!filter.is_selected_by_tags(&tags(&[])));
+
+	let filter = EntrypointFilter::default().with_tags(&[], &tags(&["slow"]));
+	assert!(filter.is_selected_by_tags(&tags(&[])));
+	assert!(filter.is_selected_by_tags(&tags(&["unknown"])));
+	assert!(!filter.is_selected_by_tags(&tags(&["slow"])));
+}
+
+#[test]
+fn tagged_tests_are_selected() {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/tags.cairo");
+	let options = TestOptions {
 		max_steps: 1000000,
+		filter: EntrypointFilter::default().with_tags(&[], &[String::from("slow")]),
+		..Default::default()
+	};
+	let file_result = run_test_file(&path, &options).unwrap().expect("Should contain tests");
+
+	let entrypoints: Vec<_> =
+		file_result.tests.iter().map(|test| test.entrypoint.as_str()).collect();
+	assert_eq!(vec!["test_fast"], entrypoints);
+}
+
+#[rstest]
+#[case(vec![2, 3], TestStatus::SUCCESS, None)]
+#[case(vec![1, 3], TestStatus::FAILURE, None)]
+#[case(
+	vec![5],
+	TestStatus::FAILURE,
+	Some("test_sum expects 2 argument(s) (a, b), got 1 in the calldata")
+)]
+fn calldata_is_passed_to_the_entrypoint(
+	#[case] args: Vec<i32>,
+	#[case] expected_status: TestStatus,
+	#[case] expected_error: Option<&str>,
+) {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/calldata.cairo");
+	let options = TestOptions {
+		calldata: Calldata::from([(
+			String::from("test_sum"),
+			args.into_iter().map(BigInt::from).collect(),
+		)]),
+		..Default::default()
+	};
+	let file_result = run_test_file(&path, &options).unwrap().expect("Should contain tests");
+
+	let result = &file_result.tests[0];
+	assert_eq!(expected_status, result.success);
+	if let Some(expected_error) = expected_error {
+		assert_eq!(Some(expected_error), result.error.as_deref());
+	}
+}
+
+#[test]
+fn table_driven_test_runs_once_per_row() {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/table.cairo");
+	let file_result = run_test_file(&path, &TestOptions::default())
+		.unwrap()
+		.expect("Should contain tests");
+
+	let results: Vec<(&str, TestStatus)> = file_result
+		.tests
+		.iter()
+		.map(|test| (test.entrypoint.as_str(), test.success))
+		.collect();
+	assert_eq!(
+		vec![
+			("test_sum[0]", TestStatus::SUCCESS),
+			("test_sum[1]", TestStatus::SUCCESS),
+			("test_sum[2]", TestStatus::FAILURE),
+		],
+		results
+	);
+	let error = file_result.tests[2].error.as_deref().unwrap_or_default();
+	assert!(error.ends_with("(inputs: 2, 2, 5)"), "{error}");
+}
+
+#[test]
+fn profiles_of_table_driven_rows_do_not_collide() {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/table.cairo");
+	let profile_dir = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	let options = TestOptions {
+		profile_dir: Some(profile_dir.clone()),
+		..Default::default()
+	};
+	run_test_file(&path, &options).unwrap().expect("Should contain tests");
+
+	for row in 0..3 {
+		let profile = profile_dir.join("table").join(format!("test_sum[{row}].folded"));
+		assert!(profile.is_file(), "{}", profile.display());
+	}
+	fs::remove_dir_all(profile_dir).unwrap();
+}
+
+#[rstest]
+#[case(false, 2)]
+#[case(true, 1)]
+fn fail_fast_stops_at_the_first_failure(#[case] fail_fast: bool, #[case] expected_tests: usize) {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/fail_fast.cairo");
+	let options = TestOptions {
+		fail_fast,
+		..Default::default()
+	};
+	let file_result = run_test_file(&path, &options).unwrap().expect("Should contain tests");
+
+	assert_eq!(expected_tests, file_result.tests.len());
+	assert!(file_result.tests.iter().all(|test| test.success == TestStatus::FAILURE));
+}
+
+#[test]
+fn failing_hint_is_reported_with_its_code_and_pc() {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/failing_hint.cairo");
+	let result = run_single_test("test_failing_hint", &path, 1000000).unwrap();
+
+	assert_eq!(TestStatus::FAILURE, result.success);
+	let error = result.error.unwrap_or_default();
+	assert!(
+		error.contains("(in hint `print(\"x is\", missing)` at pc "),
+		"{error}"
+	);
+}
+
+fn run_ordering_tests(shuffle_seed: Option<u64>) -> Vec<String> {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/ordering.cairo");
+	let options = TestOptions {
+		shuffle_seed,
+		..Default::default()
+	};
+	let file_result = run_test_file(&path, &options).unwrap().expect("Should contain tests");
+	file_result.tests.into_iter().map(|test| test.entrypoint).collect()
+}
+
+#[test]
+fn tests_run_in_lexicographic_order_by_default() {
+	assert_eq!(
+		vec!["test_alpha", "test_mid", "test_zeta"],
+		run_ordering_tests(None)
+	);
+	assert_eq!(run_ordering_tests(None), run_ordering_tests(None));
+}
+
+#[test]
+fn shuffled_order_only_depends_on_the_seed() {
+	let mut shuffled = run_ordering_tests(Some(42));
+	assert_eq!(shuffled, run_ordering_tests(Some(42)));
+
+	shuffled.sort();
+	assert_eq!(run_ordering_tests(None), shuffled);
+}
+
+#[test]
+fn remaining_tests_are_skipped_once_the_file_timeout_is_exceeded() {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/ordering.cairo");
+	let options = TestOptions {
+		file_timeout: Some(Duration::ZERO),
+		..Default::default()
+	};
+	let file_result = run_test_file(&path, &options).unwrap().expect("Should contain tests");
+
+	assert_eq!(3, file_result.tests.len());
+	for test in &file_result.tests {
+		assert_eq!(TestStatus::SKIPPED, test.success);
+		assert_eq!(
+			Some("the file exceeded its timeout of 0s"),
+			test.error.as_deref()
+		);
+	}
+
+	let options = TestOptions {
+		file_timeout: Some(Duration::from_secs(3600)),
+		..Default::default()
+	};
+	let file_result = run_test_file(&path, &options).unwrap().expect("Should contain tests");
+	assert!(file_result.tests.iter().all(|test| test.success == TestStatus::SUCCESS));
+}
+
+/// Hint spending a long time in rust, without giving back control to the VM
+struct Sleep;
+
+impl CustomHint for Sleep {
+	fn name(&self) -> &str {
+		"test_sleep"
+	}
+
+	fn execute(
+		&self,
+		_vm: &mut VirtualMachine,
+		_exec_scopes: &mut ExecutionScopes,
+		_ids_data: &HashMap<String, HintReference>,
+		_ap_tracking: &ApTracking,
+		_constants: &HashMap<String, BigInt>,
+		_args: &[String],
+	) -> Result<(), VirtualMachineError> {
+		thread::sleep(Duration::from_secs(10));
+		Ok(())
+	}
+}
+
+#[test]
+fn test_stuck_in_a_hint_times_out() {
+	register_hint(Sleep);
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/slow_hint.cairo");
+	let options = TestOptions {
+		timeout: Some(Duration::from_millis(200)),
+		..Default::default()
+	};
+	let start = Instant::now();
+	let file_result = run_test_file(&path, &options).unwrap().expect("Should contain tests");
+
+	assert!(start.elapsed() < Duration::from_secs(5));
+	let test = &file_result.tests[0];
+	assert_eq!(TestStatus::FAILURE, test.success);
+	let error = test.error.as_deref().unwrap_or_default();
+	assert!(error.starts_with(TIMEOUT_ERROR_PREFIX), "{error}");
+	assert_eq!(Some("x is 42\n"), test.captured_stdout.as_deref());
+}
+
+#[test]
+fn profile_is_written_per_entrypoint() {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/profile.cairo");
+	let profile_dir = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	let options = TestOptions {
+		profile_dir: Some(profile_dir.clone()),
+		..Default::default()
+	};
+	run_test_file(&path, &options).unwrap().expect("Should contain tests");
+
+	let folded =
+		fs::read_to_string(profile_dir.join("profile").join("test_calls_helper.folded")).unwrap();
+	assert!(
+		folded.lines().any(|line| line.starts_with("test_calls_helper;helper ")),
+		"{folded}"
+	);
+	fs::remove_dir_all(profile_dir).unwrap();
+}
+
+/// Root containing two copies of `profile.cairo` with the same name, in different directories
+fn root_with_same_named_test_files() -> PathBuf {
+	let root = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	for dir in ["a", "b"] {
+		fs::create_dir_all(root.join(dir)).unwrap();
+		fs::copy(
+			"src/cli/commands/test/test_cairo_programs/profile.cairo",
+			root.join(dir).join("test_profile.cairo"),
+		)
+		.unwrap();
 	}
-	.exec()
+	root
+}
+
+#[test]
+fn profiles_of_same_named_files_do_not_collide() {
+	let root = root_with_same_named_test_files();
+	let profile_dir = root.join("profiles");
+	let args = TestArgs {
+		profile: Some(profile_dir.clone()),
+		..test_args(root.to_str().unwrap())
+	};
+	args.exec().unwrap();
+
+	for dir in ["a", "b"] {
+		assert!(profile_dir
+			.join(dir)
+			.join("test_profile")
+			.join("test_calls_helper.folded")
+			.is_file());
+	}
+	fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn state_is_dumped_per_passing_entrypoint() {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/profile.cairo");
+	let dump_dir = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	let options = TestOptions {
+		state_dump_dir: Some(dump_dir.clone()),
+		..Default::default()
+	};
+	run_test_file(&path, &options).unwrap().expect("Should contain tests");
+
+	let dump: serde_json::Value = serde_json::from_str(
+		&fs::read_to_string(dump_dir.join("profile").join("test_calls_helper.json")).unwrap(),
+	)
+	.unwrap();
+	assert!(dump["pc"].is_string(), "{dump}");
+	assert!(dump["segments"]["1"].is_array(), "{dump}");
+	fs::remove_dir_all(dump_dir).unwrap();
+}
+
+#[test]
+fn states_of_same_named_files_do_not_collide() {
+	let root = root_with_same_named_test_files();
+	let dump_dir = root.join("states");
+	let args = TestArgs {
+		dump_state: Some(dump_dir.clone()),
+		..test_args(root.to_str().unwrap())
+	};
+	args.exec().unwrap();
+
+	for dir in ["a", "b"] {
+		assert!(dump_dir.join(dir).join("test_profile").join("test_calls_helper.json").is_file());
+	}
+	fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn coverage_counts_executed_lines() {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/profile.cairo");
+	let options = TestOptions {
+		coverage: Some(Default::default()),
+		..Default::default()
+	};
+	let file_result = run_test_file(&path, &options).unwrap().expect("Should contain tests");
+
+	let coverage = file_result.coverage.expect("Should have been recorded");
+	let lines = coverage
+		.iter()
+		.find(|(file, _)| file.ends_with("profile.cairo"))
+		.map(|(_, lines)| lines)
+		.expect("Should cover the test file");
+	// `return 1;` in helper, and `assert x = 1;` in the test
+	assert!(lines.get(&2).map_or(false, |hits| *hits > 0), "{lines:?}");
+	assert!(lines.get(&7).map_or(false, |hits| *hits > 0), "{lines:?}");
+}
+
+#[test]
+fn truncated_compiled_file_is_recompiled() {
+	let root = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	fs::create_dir_all(&root).unwrap();
+	let path = root.join("test_truncated.cairo");
+	fs::copy(
+		"src/cli/commands/test/test_cairo_programs/ordering.cairo",
+		&path,
+	)
 	.unwrap();
+	let options = TestOptions::default();
+	run_file(&path, &root, &Compiler::default(), &options).unwrap();
+
+	let path_to_compiled = cache::create_compiled_contract_path(&path, &root).unwrap();
+	let compiled = fs::read_to_string(&path_to_compiled).unwrap();
+	fs::write(&path_to_compiled, &compiled[..compiled.len() / 2]).unwrap();
+
+	let file_result = run_file(&path, &root, &Compiler::default(), &options)
+		.unwrap()
+		.expect("Should contain tests");
+	assert_eq!(3, file_result.tests.len());
+	assert_eq!(
+		Some(false),
+		file_result.compilation.map(|compilation| compilation.cached)
+	);
+	fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn second_run_reads_the_entrypoints_from_the_discovery_cache() {
+	let root = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	fs::create_dir_all(&root).unwrap();
+	let path = root.join("test_discovered.cairo");
+	fs::copy(
+		"src/cli/commands/test/test_cairo_programs/ordering.cairo",
+		&path,
+	)
+	.unwrap();
+	let names = |entrypoints: Vec<TestEntrypoint>| -> Vec<String> {
+		entrypoints.into_iter().map(|entrypoint| entrypoint.name).collect()
+	};
+
+	let (_, path_to_compiled, entrypoints, _) =
+		compile_and_list_entrypoints(path.clone(), &root, &Compiler::default(), true).unwrap();
+	assert_eq!(
+		vec!["test_alpha", "test_mid", "test_zeta"],
+		names(entrypoints)
+	);
+	let discovery_cache = discovery_cache_path(&path_to_compiled);
+	assert!(discovery_cache.is_file());
+
+	// The entrypoints are taken from the discovery cache, without parsing the compiled file
+	let cached = fs::read_to_string(&discovery_cache).unwrap().replace("test_mid", "test_cached");
+	fs::write(&discovery_cache, cached).unwrap();
+	let (.., entrypoints, cache_status) =
+		compile_and_list_entrypoints(path.clone(), &root, &Compiler::default(), true).unwrap();
+	assert_matches!(cache_status, CacheStatus::Cached(_));
+	assert_eq!(
+		vec!["test_alpha", "test_cached", "test_zeta"],
+		names(entrypoints)
+	);
+
+	let (.., entrypoints, _) =
+		compile_and_list_entrypoints(path, &root, &Compiler::default(), false).unwrap();
+	assert_eq!(
+		vec!["test_alpha", "test_mid", "test_zeta"],
+		names(entrypoints)
+	);
+	fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn missing_entrypoint_lists_the_available_ones() {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/ordering.cairo");
+
+	let err = run_single_test("test_missing", &path, 1000).unwrap_err();
+
+	assert_matches!(
+		&err,
+		TestCommandError::EntrypointNotFound { entrypoint, .. } if entrypoint == "test_missing"
+	);
+	assert_eq!(
+		"entrypoint 'test_missing' not found in compiled program, available entrypoints: \
+		 test_alpha, test_mid, test_zeta",
+		err.to_string()
+	);
+}
+
+fn test_result(entrypoint: &str, success: TestStatus) -> TestResult {
+	TestResult {
+		entrypoint: entrypoint.to_string(),
+		success,
+		duration: Duration::from_millis(1),
+		captured_stdout: None,
+		captured_stderr: None,
+		execution_output: None,
+		error: None,
+		raw_error: None,
+		diff: None,
+		random_seed: None,
+		resources: None,
+		fuzz: None,
+		flaky_runs: None,
+	}
+}
+
+#[test]
+fn output_summarizes_test_results() {
+	let mut output = TestOutput::default();
+	output.push_file(TestFileResult {
+		path: PathBuf::from("test_file.cairo"),
+		tests: vec![
+			test_result("test_ok", TestStatus::SUCCESS),
+			test_result("test_ko", TestStatus::FAILURE),
+			test_result("test_skip", TestStatus::SKIPPED),
+		],
+		error: None,
+		compilation: None,
+		coverage: None,
+	});
+
+	assert_eq!(
+		TestSummary {
+			total: 3,
+			passed: 1,
+			failed: 1,
+			skipped: 1,
+			flaky: 0,
+			max_segment_sizes: BTreeMap::new(),
+			duration: Duration::ZERO,
+		},
+		output.summary
+	);
+}
+
+#[test]
+fn flaky_tests_are_summarized() {
+	let mut output = TestOutput::default();
+	output.push_file(TestFileResult {
+		path: PathBuf::from("test_file.cairo"),
+		tests: vec![TestResult {
+			flaky_runs: Some(vec![
+				TestStatus::SUCCESS,
+				TestStatus::FAILURE,
+				TestStatus::SUCCESS,
+			]),
+			..test_result("test_flaky", TestStatus::FAILURE)
+		}],
+		error: None,
+		compilation: None,
+		coverage: None,
+	});
+
+	assert_eq!(1, output.summary.flaky);
+	let displayed = output.to_string();
+	assert!(displayed.contains("FLAKY"), "{displayed}");
+	assert!(
+		displayed.contains("test_file.cairo::test_flaky (passed 2 of 3 runs)"),
+		"{displayed}"
+	);
+}
+
+#[test]
+fn deterministic_tests_are_not_flaky() {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/profile.cairo");
+	let options = TestOptions {
+		repeat: 3,
+		..Default::default()
+	};
+	let file_result = run_test_file(&path, &options).unwrap().expect("Should contain tests");
+
+	assert!(file_result.tests.iter().all(|test| test.flaky_runs.is_none()));
+	assert_eq!(TestStatus::SUCCESS, file_result.status());
+}
+
+/// Run `profile.cairo` with a breakpoint at the first instruction of its `helper` function
+fn run_with_breakpoint_at_helper(resume: bool) -> TestResult {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/profile.cairo");
+	let root = path.parent().map(PathBuf::from).unwrap_or_default();
+	let (_, path_to_compiled, ..) =
+		compile_and_list_entrypoints(path.clone(), &root, &Compiler::default(), true).unwrap();
+	let program_json =
+		deserialize_program_json(BufReader::new(File::open(path_to_compiled).unwrap())).unwrap();
+	let pc = program_json.identifiers["__main__.helper"].pc.unwrap();
+	let options = TestOptions {
+		breakpoint: Some(Breakpoint { pc, resume }),
+		..Default::default()
+	};
+
+	let mut file_result = run_test_file(&path, &options).unwrap().expect("Should contain tests");
+	file_result.tests.remove(0)
+}
+
+#[test]
+fn breakpoint_stops_the_test() {
+	let result = run_with_breakpoint_at_helper(false);
+
+	assert_eq!(TestStatus::FAILURE, result.success);
+	let error = result.error.unwrap();
+	assert!(error.contains(BREAKPOINT_ERROR_PREFIX), "{error}");
+}
+
+#[test]
+fn breakpoint_can_resume_the_test() {
+	let result = run_with_breakpoint_at_helper(true);
+
+	assert_eq!(TestStatus::SUCCESS, result.success, "{:?}", result.error);
+}
+
+#[test]
+fn output_observer_follows_the_captured_output() {
+	let path = PathBuf::from("src/hints/print/test_cairo_programs/print.cairo");
+	let lines = Arc::new(Mutex::new(Vec::new()));
+	let observed_lines = lines.clone();
+	let options = TestOptions {
+		filter: EntrypointFilter::new(Some("test_print"), Some("*_then_fail")),
+		output_observer: Some(OutputObserver::new(move |entrypoint, _, line| {
+			observed_lines.lock().unwrap().push(format!("[{entrypoint}] {line}"))
+		})),
+		..Default::default()
+	};
+
+	let file_result = run_test_file(&path, &options).unwrap().expect("Should contain tests");
+
+	assert_eq!(vec!["[test_print] x is 42"], *lines.lock().unwrap());
+	assert_eq!(
+		Some("x is 42\n"),
+		file_result.tests[0].captured_stdout.as_deref()
+	);
+}
+
+#[test]
+fn summary_line_counts_tests_by_status() {
+	let summary = TestSummary {
+		total: 15,
+		passed: 12,
+		failed: 2,
+		skipped: 1,
+		flaky: 0,
+		max_segment_sizes: BTreeMap::new(),
+		duration: Duration::from_millis(3400),
+	};
+
+	assert_eq!(
+		"12 passed, 2 failed, 1 skipped in 3.4s",
+		summary.to_string()
+	);
+	let summary = TestSummary {
+		flaky: 1,
+		..summary
+	};
+	assert_eq!(
+		"12 passed, 2 failed (1 flaky), 1 skipped in 3.4s",
+		summary.to_string()
+	);
+}
+
+#[test]
+fn verbosity_gates_displayed_results() {
+	let mut output = TestOutput::default();
+	output.push_file(TestFileResult {
+		path: PathBuf::from("test_passing_file.cairo"),
+		tests: vec![test_result("test_ok", TestStatus::SUCCESS)],
+		error: None,
+		compilation: Some(Compilation {
+			cached: true,
+			duration: Duration::from_millis(100),
+		}),
+		coverage: None,
+	});
+	output.push_file(TestFileResult {
+		path: PathBuf::from("test_failing_file.cairo"),
+		tests: vec![
+			test_result("test_other_ok", TestStatus::SUCCESS),
+			test_result("test_ko", TestStatus::FAILURE),
+		],
+		error: None,
+		compilation: Some(Compilation {
+			cached: false,
+			duration: Duration::from_millis(1400),
+		}),
+		coverage: None,
+	});
+
+	let failures = output.to_string();
+	assert!(!failures.contains("test_passing_file.cairo"));
+	assert!(!failures.contains("test_other_ok"));
+	assert!(failures.contains("test_ko"));
+	assert!(failures.contains("2 passed, 1 failed"));
+	assert!(!failures.contains("served from the cache"));
+
+	output.verbosity = Verbosity::Tests;
+	let verbose = output.to_string();
+	assert!(verbose.contains("test_passing_file.cairo"));
+	assert!(verbose.contains("test_other_ok"));
+	assert!(!verbose.contains("(cached)"));
+	assert!(verbose.contains("1 file(s) served from the cache, 1 recompiled in 1.5s"));
+	assert!(verbose.contains("test_failing_file.cairo: compiled in 1400ms, ran 2 test(s) in 2ms"));
+
+	output.verbosity = Verbosity::Details;
+	let very_verbose = output.to_string();
+	assert!(very_verbose.contains("test_passing_file.cairo (cached)"));
+	assert!(very_verbose.contains("test_failing_file.cairo (compiled)"));
+
+	output.verbosity = Verbosity::Quiet;
+	let quiet = output.to_string();
+	assert!(!quiet.contains("Running tests in file"));
+	assert!(!quiet.contains("test_other_ok"));
+	assert!(quiet.contains("test_ko"));
+}
+
+#[test]
+fn output_serializes_with_stable_field_names() {
+	let mut output = TestOutput::default();
+	output.push_file(TestFileResult {
+		path: PathBuf::from("test_file.cairo"),
+		tests: vec![test_result("test_ok", TestStatus::SUCCESS)],
+		error: None,
+		compilation: None,
+		coverage: None,
+	});
+
+	let json = serde_json::to_value(&output).unwrap();
+
+	assert_eq!(json["files"][0]["path"], "test_file.cairo");
+	assert_eq!(json["files"][0]["tests"][0]["entrypoint"], "test_ok");
+	assert_eq!(json["files"][0]["tests"][0]["status"], "SUCCESS");
+	assert_eq!(json["summary"]["total"], 1);
+	assert_eq!(json["summary"]["passed"], 1);
+	assert_eq!(json["summary"]["failed"], 0);
+}
+
+#[test]
+fn empty_root_is_reported() {
+	let root = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	fs::create_dir_all(&root).unwrap();
+	let mut args = test_args(root.to_str().unwrap());
+
+	let output = args.exec().unwrap();
+	assert_eq!(Some(&root), output.no_test_files.as_ref());
+	assert_eq!(0, output.exit_code());
+	assert!(output
+		.to_string()
+		.contains(&format!("No test files found under {}", root.display())));
+
+	args.fail_if_no_tests = true;
+	assert_eq!(EXIT_CODE_NO_TEST_FILES, args.exec().unwrap().exit_code());
+	fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn exit_code_reflects_failures_and_errors() {
+	let mut output = TestOutput::default();
+	output.push_file(TestFileResult {
+		path: PathBuf::from("test_file.cairo"),
+		tests: vec![
+			test_result("test_ok", TestStatus::SUCCESS),
+			test_result("test_skip", TestStatus::SKIPPED),
+		],
+		error: None,
+		compilation: None,
+		coverage: None,
+	});
+	assert_eq!(0, output.exit_code());
+
+	output.push_file(TestFileResult {
+		path: PathBuf::from("test_other_file.cairo"),
+		tests: vec![test_result("test_ko", TestStatus::FAILURE)],
+		error: None,
+		compilation: None,
+		coverage: None,
+	});
+	assert_eq!(EXIT_CODE_TEST_FAILURE, output.exit_code());
+
+	// A failing lifecycle entrypoint fails the file, which has been compiled
+	let compiled = Compilation {
+		cached: false,
+		duration: Duration::ZERO,
+	};
+	let mut output = TestOutput::default();
+	output.push_file(TestFileResult {
+		path: PathBuf::from("test_failing_setup.cairo"),
+		tests: Vec::new(),
+		error: Some(String::from("setUp failed")),
+		compilation: Some(compiled),
+		coverage: None,
+	});
+	assert_eq!(EXIT_CODE_TEST_FAILURE, output.exit_code());
+
+	output.push_file(TestFileResult {
+		path: PathBuf::from("test_invalid_file.cairo"),
+		tests: Vec::new(),
+		error: Some(String::from("Failed to compile file")),
+		compilation: None,
+		coverage: None,
+	});
+	assert_eq!(EXIT_CODE_ERROR, output.exit_code());
+}
+
+#[test]
+fn interrupted_run_reports_partial_results() {
+	let mut output = TestOutput::default();
+	output.push_file(TestFileResult {
+		path: PathBuf::from("test_file.cairo"),
+		tests: vec![test_result("test_ok", TestStatus::SUCCESS)],
+		error: None,
+		compilation: None,
+		coverage: None,
+	});
+	output.interrupted = true;
+
+	assert_eq!(EXIT_CODE_INTERRUPTED, output.exit_code());
+	let displayed = output.to_string();
+	assert!(displayed.contains("test_ok"), "{displayed}");
+	assert!(
+		displayed.contains("Interrupted, the remaining tests have not been run"),
+		"{displayed}"
+	);
+}
+
+fn test_result_with_steps(entrypoint: &str, n_steps: usize) -> TestResult {
+	TestResult {
+		resources: Some(TestResources {
+			n_steps,
+			builtins: [(String::from("range_check"), 2)].into_iter().collect(),
+			segments: [(String::from("execution"), n_steps)].into_iter().collect(),
+		}),
+		..test_result(entrypoint, TestStatus::SUCCESS)
+	}
+}
+
+#[test]
+fn gas_report_is_sorted_by_step_count() {
+	let files = vec![
+		TestFileResult {
+			path: PathBuf::from("test_file.cairo"),
+			tests: vec![
+				test_result_with_steps("test_small", 10),
+				test_result("test_ko", TestStatus::FAILURE),
+			],
+			error: None,
+			compilation: None,
+			coverage: None,
+		},
+		TestFileResult {
+			path: PathBuf::from("test_other_file.cairo"),
+			tests: vec![test_result_with_steps("test_big", 1000)],
+			error: None,
+			compilation: None,
+			coverage: None,
+		},
+	];
+
+	let gas_report = GasReport::new(&files);
+
+	assert_eq!(
+		vec!["test_big", "test_small"],
+		gas_report.0.iter().map(|entry| entry.entrypoint.as_str()).collect::<Vec<_>>()
+	);
+	assert_eq!(
+		"10 steps, 2 range_check",
+		format!("{}", gas_report.0[1].resources)
+	);
+}
+
+#[test]
+fn largest_segment_sizes_are_aggregated_in_the_summary() {
+	let mut output = TestOutput::default();
+	for n_steps in [10, 30, 20] {
+		output.push_file(TestFileResult {
+			path: PathBuf::from("test_file.cairo"),
+			tests: vec![test_result_with_steps("test_steps", n_steps)],
+			error: None,
+			compilation: None,
+			coverage: None,
+		});
+	}
+
+	assert_eq!(Some(&30), output.summary.max_segment_sizes.get("execution"));
+}
+
+#[test]
+fn segment_sizes_of_a_completed_test_are_reported() {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/profile.cairo");
+	let file_result = run_test_file(&path, &TestOptions::default())
+		.unwrap()
+		.expect("Should contain tests");
+
+	let resources = file_result.tests[0].resources.as_ref().expect("Should have completed");
+	assert!(
+		resources.segments["program"] > 0,
+		"{:?}",
+		resources.segments
+	);
+	assert!(
+		resources.segments["execution"] > 0,
+		"{:?}",
+		resources.segments
+	);
+}
+
+#[rstest]
+#[case(false)]
+#[case(true)]
+fn setup_mocks_apply_to_tests(#[case] setup_once: bool) {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/lifecycle.cairo");
+	let options = TestOptions {
+		max_steps: 1000000,
+		setup_once,
+		..Default::default()
+	};
+	let file_result = run_test_file(&path, &options).unwrap().expect("Should contain tests");
+
+	assert_eq!(None, file_result.error);
+	let entrypoints: Vec<_> =
+		file_result.tests.iter().map(|test| test.entrypoint.as_str()).collect();
+	assert_eq!(vec!["test_mock_from_setup"], entrypoints);
+	assert_eq!(TestStatus::SUCCESS, file_result.tests[0].success);
+}
+
+#[test]
+fn failing_setup_skips_tests() {
+	let path = PathBuf::from("src/cli/commands/test/test_cairo_programs/failing_setup.cairo");
+	let options = TestOptions {
+		max_steps: 1000000,
+		..Default::default()
+	};
+	let file_result = run_test_file(&path, &options).unwrap().expect("Should contain tests");
+
+	assert!(file_result.error.unwrap().starts_with("setUp failed"));
+	assert_eq!(TestStatus::SKIPPED, file_result.tests[0].success);
 }
@@ -0,0 +1,209 @@
+use std::{
+	collections::{BTreeSet, HashMap, HashSet},
+	path::{Path, PathBuf},
+	sync::mpsc::{self, RecvTimeoutError},
+	time::Duration,
+};
+
+use log::warn;
+use notify::{RecursiveMode, Watcher};
+use walkdir::WalkDir;
+
+use super::{changed::select_changed_test_files, TestArgs, TestCommandError, TestOutput};
+use crate::{cli::interrupt, compile::cache::compute_hash};
+
+/// Interval between two checks of an interruption, while waiting for a change
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Time waited for the other events of a change (eg: an editor writing a file in several steps)
+/// before re-running the tests
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(100);
+/// ANSI sequence clearing the terminal and moving the cursor to its top left corner
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[1;1H";
+
+fn is_cairo_file(path: &Path) -> bool {
+	path.extension().map_or(false, |extension| extension == "cairo")
+}
+
+/// Canonical path of a file, or of its directory if it has been removed
+fn canonicalize(path: PathBuf) -> PathBuf {
+	if let Ok(canonical_path) = path.canonicalize() {
+		return canonical_path
+	}
+	match (path.parent(), path.file_name()) {
+		(Some(parent), Some(file_name)) => match parent.canonicalize() {
+			Ok(canonical_parent) => canonical_parent.join(file_name),
+			Err(_) => path,
+		},
+		_ => path,
+	}
+}
+
+/// Hashes of the cairo files of a directory, keyed by their canonical path, so that a file
+/// reported by the watcher without its content changing (eg: saved as is) does not re-run tests
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FileHashes(HashMap<PathBuf, String>);
+
+impl FileHashes {
+	/// Hash every cairo file contained in the `roots`, files which cannot be read are ignored
+	pub fn scan(roots: &[PathBuf]) -> Self {
+		Self(
+			roots
+				.iter()
+				.flat_map(WalkDir::new)
+				.filter_map(Result::ok)
+				.map(|entry| entry.into_path())
+				.filter(|path| path.is_file() && is_cairo_file(path))
+				.map(canonicalize)
+				.filter_map(|path| compute_hash(&path).ok().map(|hash| (path, hash)))
+				.collect(),
+		)
+	}
+
+	/// Hash the cairo files among `paths` again, and return the ones created, modified or removed
+	/// since they were last hashed, sorted
+	pub fn update(&mut self, paths: impl IntoIterator<Item = PathBuf>) -> Vec<PathBuf> {
+		let paths: BTreeSet<PathBuf> =
+			paths.into_iter().filter(|path| is_cairo_file(path)).map(canonicalize).collect();
+		paths
+			.into_iter()
+			.filter(|path| {
+				let opt_hash = compute_hash(path).ok();
+				let changed = self.0.get(path) != opt_hash.as_ref();
+				match opt_hash {
+					Some(hash) => self.0.insert(path.clone(), hash),
+					None => self.0.remove(path),
+				};
+				changed
+			})
+			.collect()
+	}
+}
+
+/// Run the tests, then re-run them each time a cairo file of the roots changes.
+///
+/// The files are watched with the notifications of the platform (eg: inotify on Linux) rather than
+/// scanned periodically, so that large projects are not hashed in full twice a second.
+/// Only the test files affected by a change are re-run: the modified test files, and the ones
+/// importing a modified module when they were last compiled (as with `--only-changed`). They are
+/// only recompiled if they, or one of their dependencies, changed (see `compile_with_cache`).
+///
+/// This function only returns if the test files cannot be listed or watched, or once interrupted
+/// with Ctrl-C (with the output of the interrupted run).
+pub(super) fn watch(args: &TestArgs) -> Result<TestOutput, TestCommandError> {
+	let (sender, receiver) = mpsc::channel::<notify::Result<notify::Event>>();
+	let mut watcher = notify::recommended_watcher(sender)?;
+	for root in args.roots() {
+		watcher.watch(&root, RecursiveMode::Recursive)?;
+	}
+	let mut hashes = FileHashes::scan(&args.roots());
+	let output = args.run_test_files(args.list_test_files()?);
+	if output.interrupted {
+		return Ok(output)
+	}
+	print!("{output}");
+
+	loop {
+		let mut events = match receiver.recv_timeout(POLL_INTERVAL) {
+			Ok(event) => vec![event],
+			Err(RecvTimeoutError::Timeout) => {
+				if interrupt::is_interrupted() {
+					return Ok(TestOutput {
+						interrupted: true,
+						..Default::default()
+					})
+				}
+				continue
+			},
+			Err(RecvTimeoutError::Disconnected) =>
+				return Err(notify::Error::generic("the file watcher stopped").into()),
+		};
+		while let Ok(event) = receiver.recv_timeout(DEBOUNCE_DELAY) {
+			events.push(event);
+		}
+		let mut paths = Vec::new();
+		for event in events {
+			match event {
+				Ok(event) => paths.extend(event.paths),
+				Err(e) => warn!("failed to watch the test files: {e}"),
+			}
+		}
+		let changed_files = hashes.update(paths);
+		if changed_files.is_empty() {
+			continue
+		}
+
+		let changed: HashSet<PathBuf> = changed_files.iter().cloned().collect();
+		let files_to_run =
+			select_changed_test_files(args.list_test_files()?, &args.root_dir(), &changed);
+
+		print!("{CLEAR_SCREEN}");
+		for changed_file in &changed_files {
+			println!("{} changed, re-running tests\n", changed_file.display());
+		}
+		let output = args.run_test_files(files_to_run);
+		if output.interrupted {
+			return Ok(output)
+		}
+		print!("{output}");
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs;
+	use uuid::Uuid;
+
+	#[test]
+	fn update_returns_the_created_modified_and_removed_files() {
+		let root = std::env::temp_dir().join(Uuid::new_v4().to_string());
+		fs::create_dir_all(&root).unwrap();
+		let (test_a, test_b, lib) = (
+			root.join("test_a.cairo"),
+			root.join("test_b.cairo"),
+			root.join("lib.cairo"),
+		);
+		fs::write(&test_a, "a").unwrap();
+		fs::write(&test_b, "b").unwrap();
+		let mut hashes = FileHashes::scan(&[root.clone()]);
+
+		fs::write(&test_a, "a").unwrap();
+		fs::write(&test_b, "b2").unwrap();
+		fs::write(&lib, "lib").unwrap();
+		fs::write(root.join("notes.txt"), "").unwrap();
+		let paths = [&test_a, &test_b, &lib].map(|path| canonicalize(path.clone()));
+		assert_eq!(
+			vec![paths[2].clone(), paths[1].clone()],
+			hashes.update([
+				test_a.clone(),
+				test_b.clone(),
+				lib.clone(),
+				root.join("notes.txt")
+			])
+		);
+		assert!(hashes.update([test_a.clone(), test_b.clone()]).is_empty());
+
+		fs::remove_file(&lib).unwrap();
+		assert_eq!(vec![paths[2].clone()], hashes.update([lib]));
+		fs::remove_dir_all(root).unwrap();
+	}
+
+	#[test]
+	fn scan_hashes_cairo_files_only() {
+		let hashes = FileHashes::scan(&[PathBuf::from("./test_cairo_contracts")]);
+
+		let mut paths: Vec<_> = hashes.0.keys().cloned().collect();
+		paths.sort();
+		assert_eq!(
+			vec![
+				canonicalize(PathBuf::from(
+					"./test_cairo_contracts/test_invalid_program.cairo"
+				)),
+				canonicalize(PathBuf::from(
+					"./test_cairo_contracts/test_valid_program.cairo"
+				)),
+			],
+			paths
+		);
+	}
+}
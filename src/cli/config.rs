@@ -0,0 +1,272 @@
+use clap::{ArgMatches, ValueSource};
+use serde::Deserialize;
+use std::{
+	collections::BTreeMap,
+	fs, io,
+	path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+use super::{commands::Commands, Args};
+
+/// Name of the configuration file of a cairo-foundry project
+pub const CONFIG_FILE_NAME: &str = "cairo-foundry.toml";
+
+/// Default options read from the `cairo-foundry.toml` file of a project,
+/// the command line flags (and environment variables) take precedence over them
+#[derive(Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+	/// Directory containing the tests, relative to the configuration file
+	pub root: Option<PathBuf>,
+	pub max_steps: Option<u64>,
+	pub fuzz_runs: Option<usize>,
+	pub jobs: Option<usize>,
+	/// Maximum duration of a single test, in seconds
+	pub timeout: Option<u64>,
+	/// Name or path of the cairo compiler binary
+	pub compiler: Option<String>,
+	/// Directories searched for the imported modules, relative to the configuration file
+	pub cairo_path: Option<Vec<PathBuf>>,
+	/// Extra arguments passed to the cairo compiler
+	pub compiler_args: Option<Vec<String>>,
+	/// Directory storing the cache and the compiled files
+	pub cache_dir: Option<PathBuf>,
+	/// Named sets of options (eg: `[profile.ci]`) overriding the ones above when selected with
+	/// `--config-profile`
+	#[serde(default)]
+	pub profile: BTreeMap<String, Profile>,
+}
+
+/// Options of a profile of the configuration file, they take precedence over the options of the
+/// configuration file when the profile is selected.
+#[derive(Debug, Default, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Profile {
+	pub max_steps: Option<u64>,
+	pub fuzz_runs: Option<usize>,
+	pub jobs: Option<usize>,
+	pub timeout: Option<u64>,
+	pub compiler: Option<String>,
+	pub compiler_args: Option<Vec<String>>,
+}
+
+#[derive(Error, Debug)]
+pub enum ConfigError {
+	#[error("Failed to read the configuration file {0}: {1}")]
+	FailedToRead(String, io::Error),
+	#[error("Invalid configuration file {0}: {1}")]
+	InvalidFormat(String, toml::de::Error),
+	#[error("Invalid configuration file {0}: root \"{1}\" is not a valid directory")]
+	InvalidRoot(String, String),
+	#[error(
+		"Unknown profile '{0}', the profiles of the configuration file are: [{}]",
+		.1.join(", ")
+	)]
+	UnknownProfile(String, Vec<String>),
+}
+
+impl Config {
+	/// Read the configuration file of the project in `dir`, if any
+	pub fn load(dir: &Path) -> Result<Self, ConfigError> {
+		let path = dir.join(CONFIG_FILE_NAME);
+		let content = match fs::read_to_string(&path) {
+			Ok(content) => content,
+			Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+			Err(err) => return Err(ConfigError::FailedToRead(path.display().to_string(), err)),
+		};
+		let mut config: Self = toml::from_str(&content)
+			.map_err(|err| ConfigError::InvalidFormat(path.display().to_string(), err))?;
+
+		if let Some(root) = &config.root {
+			let root = dir.join(root);
+			if !root.is_dir() {
+				return Err(ConfigError::InvalidRoot(
+					path.display().to_string(),
+					root.display().to_string(),
+				))
+			}
+			config.root = Some(root);
+		}
+		if let Some(cairo_path) = &mut config.cairo_path {
+			cairo_path.iter_mut().for_each(|path| *path = dir.join(&*path));
+		}
+		Ok(config)
+	}
+
+	/// Merge the options of the profile `name` over the other ones
+	pub fn select_profile(mut self, name: &str) -> Result<Self, ConfigError> {
+		let profile = match self.profile.get(name) {
+			Some(profile) => profile.clone(),
+			None =>
+				return Err(ConfigError::UnknownProfile(
+					name.to_string(),
+					self.profile.keys().cloned().collect(),
+				)),
+		};
+		self.max_steps = profile.max_steps.or(self.max_steps);
+		self.fuzz_runs = profile.fuzz_runs.or(self.fuzz_runs);
+		self.jobs = profile.jobs.or(self.jobs);
+		self.timeout = profile.timeout.or(self.timeout);
+		self.compiler = profile.compiler.or(self.compiler);
+		self.compiler_args = profile.compiler_args.or(self.compiler_args);
+		Ok(self)
+	}
+}
+
+/// Whether an argument has been given on the command line or through an environment variable
+fn is_explicit(matches: &ArgMatches, id: &str) -> bool {
+	matches!(
+		matches.value_source(id),
+		Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+	)
+}
+
+impl Args {
+	/// Use the values of `config` for the arguments which have not been given explicitly
+	pub fn apply_config(&mut self, config: Config, matches: &ArgMatches) {
+		// The cache directory has no default value, it is only set if it has been given explicitly
+		if self.cache_dir.is_none() {
+			self.cache_dir = config.cache_dir;
+		}
+
+		if let (Commands::Test(args), Some(("test", matches))) =
+			(&mut self.command, matches.subcommand())
+		{
+			if let Some(root) = config.root.filter(|_| !is_explicit(matches, "root")) {
+				args.root = root;
+			}
+			if let Some(max_steps) = config.max_steps.filter(|_| !is_explicit(matches, "max_steps"))
+			{
+				args.max_steps = max_steps;
+			}
+			if let Some(fuzz_runs) = config.fuzz_runs.filter(|_| !is_explicit(matches, "fuzz_runs"))
+			{
+				args.fuzz_runs = fuzz_runs;
+			}
+			if let Some(jobs) = config.jobs.filter(|_| !is_explicit(matches, "jobs")) {
+				args.jobs = Some(jobs);
+			}
+			if let Some(timeout) = config.timeout.filter(|_| !is_explicit(matches, "timeout")) {
+				args.timeout = Some(timeout);
+			}
+			if let Some(compiler) = config.compiler.filter(|_| !is_explicit(matches, "compiler")) {
+				args.compiler = compiler;
+			}
+			if let Some(cairo_path) =
+				config.cairo_path.filter(|_| !is_explicit(matches, "cairo_path"))
+			{
+				args.cairo_path = cairo_path;
+			}
+			if let Some(compiler_args) =
+				config.compiler_args.filter(|_| !is_explicit(matches, "compiler_args"))
+			{
+				args.compiler_args = compiler_args;
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use assert_matches::assert_matches;
+	use clap::{CommandFactory, FromArgMatches};
+	use uuid::Uuid;
+
+	fn project_with_config(content: &str) -> PathBuf {
+		let dir = std::env::temp_dir().join(Uuid::new_v4().to_string());
+		fs::create_dir_all(dir.join("tests")).unwrap();
+		fs::write(dir.join(CONFIG_FILE_NAME), content).unwrap();
+		dir
+	}
+
+	#[test]
+	fn missing_config_file_is_empty() {
+		let dir = std::env::temp_dir().join(Uuid::new_v4().to_string());
+
+		assert_eq!(Config::default(), Config::load(&dir).unwrap());
+	}
+
+	#[test]
+	fn config_file_is_parsed() {
+		let dir = project_with_config("root = \"tests\"\nmax-steps = 42\ncompiler = \"cc\"\n");
+
+		assert_eq!(
+			Config {
+				root: Some(dir.join("tests")),
+				max_steps: Some(42),
+				compiler: Some(String::from("cc")),
+				cairo_path: None,
+				compiler_args: None,
+				cache_dir: None,
+				..Default::default()
+			},
+			Config::load(&dir).unwrap()
+		);
+		fs::remove_dir_all(dir).unwrap();
+	}
+
+	#[test]
+	fn selected_profile_overrides_the_config() {
+		let dir = project_with_config(
+			"max-steps = 42\nfuzz-runs = 10\n\n[profile.ci]\nmax-steps = 1000\njobs = 2\n\n\
+			 [profile.fuzz]\nfuzz-runs = 5000\n",
+		);
+		let config = || Config::load(&dir).unwrap();
+
+		let ci = config().select_profile("ci").unwrap();
+		assert_eq!(
+			(Some(1000), Some(10), Some(2)),
+			(ci.max_steps, ci.fuzz_runs, ci.jobs)
+		);
+		let fuzz = config().select_profile("fuzz").unwrap();
+		assert_eq!(
+			(Some(42), Some(5000), None),
+			(fuzz.max_steps, fuzz.fuzz_runs, fuzz.jobs)
+		);
+
+		let err = config().select_profile("nightly").unwrap_err();
+		assert_matches!(&err, ConfigError::UnknownProfile(name, _) if name == "nightly");
+		assert_eq!(
+			"Unknown profile 'nightly', the profiles of the configuration file are: [ci, fuzz]",
+			err.to_string()
+		);
+		fs::remove_dir_all(dir).unwrap();
+	}
+
+	#[test]
+	fn unknown_key_is_reported() {
+		let dir = project_with_config("max_step = 42\n");
+
+		let err = Config::load(&dir).unwrap_err();
+
+		assert_matches!(err, ConfigError::InvalidFormat(..));
+		assert!(err.to_string().contains("max_step"), "{err}");
+		fs::remove_dir_all(dir).unwrap();
+	}
+
+	#[test]
+	fn command_line_takes_precedence_over_config() {
+		let config = || Config {
+			max_steps: Some(42),
+			compiler: Some(String::from("cc")),
+			..Default::default()
+		};
+		let parse = |argv: &[&str]| {
+			let matches = Args::command().try_get_matches_from(argv).unwrap();
+			let mut args = Args::from_arg_matches(&matches).unwrap();
+			args.apply_config(config(), &matches);
+			match args.command {
+				Commands::Test(args) => (args.max_steps, args.compiler),
+				_ => unreachable!(),
+			}
+		};
+
+		assert_eq!((42, String::from("cc")), parse(&["cairo-foundry", "test"]));
+		assert_eq!(
+			(7, String::from("cc")),
+			parse(&["cairo-foundry", "test", "--max-steps", "7"])
+		);
+	}
+}
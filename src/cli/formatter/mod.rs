@@ -1,5 +1,9 @@
+use clap::ValueEnum;
 use serde::Serialize;
-use std::fmt::{self, Display};
+use std::{
+	env,
+	fmt::{self, Display},
+};
 
 pub mod json;
 pub mod text;
@@ -17,7 +21,37 @@ pub trait Formattable: fmt::Display + Serialize {}
 
 impl<T> Formattable for T where T: Display + Serialize {}
 
+/// When to color the text output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+	/// Color the output if stdout is a terminal and `NO_COLOR` is not set
+	Auto,
+	Always,
+	Never,
+}
+
+impl ColorChoice {
+	fn should_colorize(self, json: bool, no_color: bool, is_terminal: bool) -> bool {
+		match self {
+			Self::Always => true,
+			Self::Never => false,
+			Self::Auto => !json && !no_color && is_terminal,
+		}
+	}
+
+	/// Enable or disable the colors of every string colored with the `colored` crate
+	pub fn apply(self, json: bool) {
+		let no_color = env::var_os("NO_COLOR").map_or(false, |value| !value.is_empty());
+		colored::control::set_override(self.should_colorize(
+			json,
+			no_color,
+			atty::is(atty::Stream::Stdout),
+		));
+	}
+}
+
 pub fn make(args: &super::Args) -> Formatters {
+	args.color.apply(args.json);
 	if args.json {
 		Formatters::JSON(json::JsonFormatter {})
 	} else {
@@ -36,3 +70,22 @@ where
 		}
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::ColorChoice;
+
+	#[test]
+	fn auto_colors_only_text_output_on_a_terminal() {
+		assert!(ColorChoice::Auto.should_colorize(false, false, true));
+		assert!(!ColorChoice::Auto.should_colorize(false, false, false));
+		assert!(!ColorChoice::Auto.should_colorize(false, true, true));
+		assert!(!ColorChoice::Auto.should_colorize(true, false, true));
+	}
+
+	#[test]
+	fn explicit_choice_overrides_the_environment() {
+		assert!(ColorChoice::Always.should_colorize(false, true, false));
+		assert!(!ColorChoice::Never.should_colorize(false, false, true));
+	}
+}
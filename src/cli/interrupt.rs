@@ -0,0 +1,25 @@
+use std::{
+	process,
+	sync::atomic::{AtomicBool, Ordering},
+};
+
+use super::commands::EXIT_CODE_INTERRUPTED;
+
+/// Whether the run has been interrupted (with Ctrl-C)
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Handle Ctrl-C: the first one lets the running test complete, so that the results gathered so
+/// far are reported, the second one aborts immediately
+pub fn install_handler() -> Result<(), ctrlc::Error> {
+	ctrlc::set_handler(|| {
+		if INTERRUPTED.swap(true, Ordering::SeqCst) {
+			process::exit(EXIT_CODE_INTERRUPTED.into());
+		}
+		eprintln!("Interrupted, finishing the running test (press Ctrl-C again to abort)");
+	})
+}
+
+/// Whether the run has been interrupted, in which case no other test should be started
+pub fn is_interrupted() -> bool {
+	INTERRUPTED.load(Ordering::SeqCst)
+}
@@ -1,4 +1,6 @@
-use clap::Parser;
+use clap::{Parser, ValueHint};
+use log::LevelFilter;
+use std::path::PathBuf;
 
 /**
  * Commands module
@@ -7,6 +9,20 @@ use clap::Parser;
  */
 pub mod commands;
 
+/**
+ * Config module
+ *
+ * This module contains the parsing of the `cairo-foundry.toml` configuration file
+ */
+pub mod config;
+
+/**
+ * Interrupt module
+ *
+ * This module contains the handling of Ctrl-C, which stops a run after its current test
+ */
+pub mod interrupt;
+
 /**
  * Formatter module
  *
@@ -14,7 +30,9 @@ pub mod commands;
  */
 pub mod formatter;
 
+use crate::io::felt_format::FeltFormat;
 use commands::Commands;
+use formatter::ColorChoice;
 
 /// Store the command line arguments
 #[derive(Parser)]
@@ -28,4 +46,40 @@ pub struct Args {
 	/// Format the command output in JSON
 	#[clap(long)]
 	pub json: bool,
+
+	/// When to color the output (`auto` disables the colors if `NO_COLOR` is set, or if the
+	/// output is not a terminal)
+	#[clap(long, global = true, value_enum, value_name = "WHEN", default_value_t = ColorChoice::Auto)]
+	pub color: ColorChoice,
+
+	/// Directory storing the cache and the compiled files (default: the platform cache directory)
+	#[clap(long, global = true, env = "CAIRO_FOUNDRY_CACHE_DIR", value_name = "DIR", value_hint = ValueHint::DirPath)]
+	pub cache_dir: Option<PathBuf>,
+
+	/// Log, for each file looked up in the cache, whether its compiled file is reused and why
+	/// (along with the stored and current hashes of the file), to find out why a file is
+	/// recompiled
+	#[clap(long, global = true)]
+	pub verbose_cache: bool,
+
+	/// Representation of the felts printed by the tests, of their execution output and of the
+	/// values of their failed assertions. The execution output is only formatted when displayed,
+	/// it is compared to the snapshots (and written to the json output) as is
+	#[clap(long, global = true, value_enum, value_name = "FORMAT", default_value_t = FeltFormat::Decimal)]
+	pub felt_format: FeltFormat,
+
+	/// Profile of the configuration file (eg: `[profile.ci]`) whose options take precedence over
+	/// the other ones of the file, the command line flags still taking precedence over them
+	#[clap(
+		long,
+		global = true,
+		env = "CAIRO_FOUNDRY_PROFILE",
+		value_name = "NAME"
+	)]
+	pub config_profile: Option<String>,
+
+	/// Level of the diagnostics written to stderr (`off`, `error`, `warn`, `info`, `debug` or
+	/// `trace`), overriding the `RUST_LOG` environment variable
+	#[clap(long, global = true, value_name = "LEVEL")]
+	pub log_level: Option<LevelFilter>,
 }
@@ -4,10 +4,24 @@ mod tests;
 #[cfg(test)]
 use std::env;
 
-use std::{fmt::Debug, fs::read_to_string, io, path::PathBuf};
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fmt::Debug,
+	fs::{self, read_to_string, File},
+	io,
+	path::{Path, PathBuf},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		PoisonError, RwLock,
+	},
+};
 
+#[cfg(not(test))]
+use lazy_static::lazy_static;
+use log::{debug, info, log, Level};
 use serde::{Deserialize, Serialize};
 use serde_json;
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -15,6 +29,31 @@ pub struct Cache {
 	pub contract_path: PathBuf,
 	pub compiled_contract_path: PathBuf,
 	pub hash: String,
+	/// Version of the compiler which produced the compiled contract.
+	///
+	/// Cache files written before the version was recorded deserialize with an empty version, so
+	/// they are considered stale.
+	#[serde(default)]
+	pub compiler_version: String,
+	/// Extra arguments the compiler was invoked with, as they change the compiled contract
+	#[serde(default)]
+	pub compiler_args: Vec<String>,
+	/// Hash of each module the contract imports (transitively), at the time it was compiled.
+	/// The contract is stale if one of them changed.
+	///
+	/// The modules of the project are recorded by their path relative to its root directory, so
+	/// that the entry stays valid when the project is checked out at another location.
+	#[serde(default)]
+	pub dependencies: BTreeMap<PathBuf, String>,
+}
+
+/// Whether an up to date compiled version of a contract is available in the cache
+#[derive(Debug, PartialEq, Eq)]
+pub enum CacheStatus {
+	/// The contract did not change since it was last compiled
+	Cached(Cache),
+	/// The contract has to be compiled, contains the hash of its current content
+	Uncached(String),
 }
 
 // CacheDirNotSupported is a top level struct and not an enum variant because
@@ -36,27 +75,311 @@ pub enum CacheError {
 	InvalidContractExtension(PathBuf),
 	#[error(transparent)]
 	StripPrefixError(#[from] std::path::StripPrefixError),
+	#[error("failed to create directory '{0}': {1}")]
+	DirCreation(String, io::Error),
+	#[error("failed to write cache file '{0}': {1}")]
+	WriteCacheFile(String, io::Error),
+	#[error("failed to serialize cache file '{0}': {1}")]
+	SerializeError(String, serde_json::Error),
 }
 
 pub const CAIRO_FOUNDRY_CACHE_DIR: &str = "cairo-foundry-cache";
 pub const CAIRO_FOUNDRY_COMPILED_CONTRACT_DIR: &str = "compiled-cairo-files";
 
 #[cfg(not(test))]
+lazy_static! {
+	/// Base directory of the cache set with `set_cache_dir`, if any
+	static ref CACHE_DIR_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+}
+
+// Tests are run concurrently, a test overriding the base directory of the cache only overrides it
+// for its own thread
+#[cfg(test)]
+thread_local! {
+	static CACHE_DIR_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+}
+
+#[cfg(not(test))]
+fn with_cache_dir_override<T>(f: impl FnOnce(&RwLock<Option<PathBuf>>) -> T) -> T {
+	f(&CACHE_DIR_OVERRIDE)
+}
+
+#[cfg(test)]
+fn with_cache_dir_override<T>(f: impl FnOnce(&RwLock<Option<PathBuf>>) -> T) -> T {
+	CACHE_DIR_OVERRIDE.with(f)
+}
+
+/// Override the base directory of the cache and of the compiled files (`None` to use the
+/// platform cache directory)
+pub fn set_cache_dir(dir: Option<PathBuf>) {
+	with_cache_dir_override(|dir_override| {
+		*dir_override.write().unwrap_or_else(PoisonError::into_inner) = dir;
+	});
+}
+
+/// Whether the lookups in the cache are logged in detail (see `get_cache`)
+static VERBOSE_CACHE: AtomicBool = AtomicBool::new(false);
+
+/// Log each lookup in the cache at the info level, with the hashes it compared, to understand why
+/// a file is recompiled
+pub fn set_verbose_cache(verbose: bool) {
+	VERBOSE_CACHE.store(verbose, Ordering::Relaxed);
+}
+
+fn is_verbose_cache() -> bool {
+	VERBOSE_CACHE.load(Ordering::Relaxed)
+}
+
+/// Base directory of the cache: the one set with `set_cache_dir`, or the platform cache
+/// directory (eg: `~/.cache` on Linux)
 pub fn cache_dir() -> Result<PathBuf, CacheDirNotSupported> {
+	let opt_dir = with_cache_dir_override(|dir_override| {
+		dir_override.read().unwrap_or_else(PoisonError::into_inner).clone()
+	});
+	match opt_dir {
+		Some(dir) => Ok(dir),
+		None => default_cache_dir(),
+	}
+}
+
+#[cfg(not(test))]
+fn default_cache_dir() -> Result<PathBuf, CacheDirNotSupported> {
 	dirs::cache_dir().ok_or(CacheDirNotSupported)
 }
 
+// The tests which do not override the cache directory all share the same one
 #[cfg(test)]
-pub fn cache_dir() -> Result<PathBuf, CacheDirNotSupported> {
+fn default_cache_dir() -> Result<PathBuf, CacheDirNotSupported> {
 	Ok(env::temp_dir().join("cairo-foundry-tests"))
 }
 
-fn read_cache_file(path: &PathBuf) -> Result<Cache, CacheError> {
+/// Read a cache entry from a cache file
+pub fn read_cache_file(path: &PathBuf) -> Result<Cache, CacheError> {
 	let file = read_to_string(path)?;
 	let data = serde_json::from_str::<Cache>(file.as_str())?;
 	Ok(data)
 }
 
+/// Read the cache entry of a contract, identified by its path relatively to `root_dir`
+pub fn read_cache(contract_path: &PathBuf, root_dir: &PathBuf) -> Result<Cache, CacheError> {
+	read_cache_file(&get_cache_path(contract_path, root_dir)?)
+}
+
+/// Write a cache entry to its cache file, creating the parent directories if needed.
+///
+/// Directories are created with `fs::create_dir_all`, which does not fail if they already exist,
+/// so that several contracts can be cached concurrently.
+///
+/// The cache file path is computed from the contract path relatively to `root_dir`
+/// (see `get_cache_path`).
+/// The entry is written to a temporary file first, then renamed, so that an interrupted run never
+/// leaves a truncated cache file behind.
+pub fn write_cache(cache: &Cache, root_dir: &PathBuf) -> Result<(), CacheError> {
+	let cache_path = get_cache_path(&cache.contract_path, root_dir)?;
+	if let Some(parent) = cache_path.parent() {
+		fs::create_dir_all(parent)
+			.map_err(|e| CacheError::DirCreation(parent.display().to_string(), e))?;
+	}
+
+	let tmp_path = cache_path.with_extension("json.tmp");
+	let file = File::create(&tmp_path)
+		.map_err(|e| CacheError::WriteCacheFile(cache_path.display().to_string(), e))?;
+	serde_json::to_writer_pretty(file, cache)
+		.map_err(|e| CacheError::SerializeError(cache_path.display().to_string(), e))?;
+	fs::rename(&tmp_path, &cache_path)
+		.map_err(|e| CacheError::WriteCacheFile(cache_path.display().to_string(), e))?;
+	Ok(())
+}
+
+/// Compute the sha256 hash of a file content, as an hexadecimal string
+pub fn compute_hash(path: &PathBuf) -> Result<String, CacheError> {
+	let content = fs::read(path)?;
+	Ok(format!("0x{:x}", Sha256::digest(content)))
+}
+
+/// Hash the source files a contract has been compiled from, other than the contract itself.
+///
+/// The source files are the ones the instructions of the compiled contract come from, as listed
+/// in its debug info, so a module only defining constants or structs is not tracked. Files which
+/// do not exist on disk (eg: the ones generated by the compiler) are ignored.
+///
+/// return a map from the path of each imported module (relative to `root_dir` if it is inside it,
+/// canonical otherwise, as the libraries installed outside of the project) to its hash
+pub fn hash_dependencies(
+	contract_path: &PathBuf,
+	compiled_contract_path: &PathBuf,
+	root_dir: &Path,
+) -> Result<BTreeMap<PathBuf, String>, CacheError> {
+	let json = serde_json::from_str::<serde_json::Value>(&read_to_string(compiled_contract_path)?)?;
+	let files: BTreeSet<PathBuf> = json["debug_info"]["instruction_locations"]
+		.as_object()
+		.into_iter()
+		.flat_map(|locations| locations.values())
+		.filter_map(|location| location["inst"]["input_file"]["filename"].as_str())
+		.map(PathBuf::from)
+		.filter(|file| file != contract_path && file.is_file())
+		.collect();
+	files
+		.into_iter()
+		.map(|file| {
+			let hash = compute_hash(&file)?;
+			// `dependency_paths` resolves the relative paths against `root_dir`, it leaves the
+			// absolute ones as is
+			let file = match file.strip_prefix(root_dir) {
+				Ok(relative_file) => relative_file.to_path_buf(),
+				Err(_) => file.canonicalize()?,
+			};
+			Ok((file, hash))
+		})
+		.collect()
+}
+
+/// Paths of the dependencies of a cache entry, the relative ones being resolved against the
+/// `root_dir` of the contract
+pub fn dependency_paths<'a>(
+	cache: &'a Cache,
+	root_dir: &'a Path,
+) -> impl Iterator<Item = (PathBuf, &'a String)> + 'a {
+	cache.dependencies.iter().map(move |(file, hash)| (root_dir.join(file), hash))
+}
+
+/// The first dependency of a cache entry which changed (or has been removed) since the contract
+/// was compiled, if any (see `dependency_paths`)
+pub fn changed_dependency(cache: &Cache, root_dir: &Path) -> Option<PathBuf> {
+	dependency_paths(cache, root_dir)
+		.find(|(file, hash)| compute_hash(file).ok().as_ref() != Some(*hash))
+		.map(|(file, _)| file)
+}
+
+/// Why a cache entry is stale, if it is: the level its message is logged at, and the message
+fn stale_reason(
+	cache: &Cache,
+	hash: &str,
+	compiler_version: &str,
+	compiler_args: &[String],
+	root_dir: &Path,
+) -> Option<(Level, String)> {
+	if cache.compiler_version != compiler_version {
+		return Some((
+			Level::Info,
+			format!(
+				"compiled with '{}', current compiler is '{compiler_version}'",
+				cache.compiler_version
+			),
+		))
+	}
+	if cache.compiler_args != compiler_args {
+		return Some((
+			Level::Info,
+			format!(
+				"compiled with arguments {:?}, current arguments are {compiler_args:?}",
+				cache.compiler_args
+			),
+		))
+	}
+	if cache.hash != hash {
+		return Some((Level::Debug, String::from("the contract changed")))
+	}
+	if !cache.compiled_contract_path.exists() {
+		return Some((
+			Level::Debug,
+			format!(
+				"the compiled file '{}' does not exist",
+				cache.compiled_contract_path.display()
+			),
+		))
+	}
+	changed_dependency(cache, root_dir).map(|dependency| {
+		(
+			Level::Info,
+			format!("imported module '{}' changed", dependency.display()),
+		)
+	})
+}
+
+/// Message logged with `--verbose-cache` for the lookup of `contract_path` in the cache file at
+/// `cache_path`: whether it is a hit, why (`reason` being the `stale_reason` of a miss, if there
+/// is a cache entry), and the stored and current hashes of the contract
+fn verbose_cache_message(
+	contract_path: &Path,
+	cache_path: &Path,
+	stored_hash: Option<&str>,
+	hash: &str,
+	reason: Option<&str>,
+) -> String {
+	let (decision, explanation) = match (stored_hash, reason) {
+		(None, _) => ("miss", String::from("no cache")),
+		(Some(_), Some(reason)) => ("miss", format!("stale cache, {reason}")),
+		(Some(_), None) => ("hit", String::from("up to date cache")),
+	};
+	format!(
+		"cache {decision} for '{}': {explanation} (cache file '{}', stored hash: {}, current hash: \
+		 {hash})",
+		contract_path.display(),
+		cache_path.display(),
+		stored_hash.unwrap_or("none")
+	)
+}
+
+/// Look for an up to date compiled version of a contract in the cache.
+///
+/// Returns `CacheStatus::Cached` with the cache entry if neither the contract nor the modules it
+/// imports changed since it was last compiled by `compiler_version` with `compiler_args`, and the
+/// compiled file still exists, `CacheStatus::Uncached` with the hash of the contract otherwise.
+///
+/// With `set_verbose_cache`, the decision is logged at the info level along with its reason and
+/// the hashes it is based on.
+pub fn get_cache(
+	contract_path: &PathBuf,
+	root_dir: &PathBuf,
+	compiler_version: &str,
+	compiler_args: &[String],
+) -> Result<CacheStatus, CacheError> {
+	let cache_path = get_cache_path(contract_path, root_dir)?;
+	let hash = compute_hash(contract_path)?;
+
+	let opt_cache = read_cache_file(&cache_path).ok();
+	let opt_stale_reason = opt_cache
+		.as_ref()
+		.and_then(|cache| stale_reason(cache, &hash, compiler_version, compiler_args, root_dir));
+	if is_verbose_cache() {
+		info!(
+			"{}",
+			verbose_cache_message(
+				contract_path,
+				&cache_path,
+				opt_cache.as_ref().map(|cache| cache.hash.as_str()),
+				&hash,
+				opt_stale_reason.as_ref().map(|(_, reason)| reason.as_str())
+			)
+		);
+	} else {
+		match (&opt_cache, &opt_stale_reason) {
+			(None, _) => debug!("no cache for '{}'", contract_path.display()),
+			(Some(_), Some((level, reason))) => log!(
+				*level,
+				"stale cache for '{}': {reason}",
+				contract_path.display()
+			),
+			(Some(_), None) => debug!("up to date cache for '{}'", contract_path.display()),
+		}
+	}
+
+	match (opt_cache, opt_stale_reason) {
+		(Some(cache), None) => Ok(CacheStatus::Cached(cache)),
+		_ => Ok(CacheStatus::Uncached(hash)),
+	}
+}
+
+/// Remove the cache entry of a contract (if any), forcing its next compilation
+pub fn invalidate_cache(contract_path: &PathBuf, root_dir: &PathBuf) -> Result<(), CacheError> {
+	let cache_path = get_cache_path(contract_path, root_dir)?;
+	match fs::remove_file(cache_path) {
+		Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+		_ => Ok(()),
+	}
+}
+
 fn is_valid_cairo_contract(contract_path: &PathBuf) -> Result<(), CacheError> {
 	let extension = contract_path
 		.extension()
@@ -80,6 +403,24 @@ fn get_cache_path(contract_path: &PathBuf, root_dir: &PathBuf) -> Result<PathBuf
 	Ok(cache_path)
 }
 
+/// Path of the compiled version of a contract, its parent directories are created if needed.
+///
+/// Compiled contracts are stored in `CAIRO_FOUNDRY_COMPILED_CONTRACT_DIR`, under the cache
+/// directory, at the path of the contract relatively to `root_dir` (see
+/// `get_compiled_contract_path`), so that contracts with the same name in different directories
+/// do not overwrite each other.
+pub fn create_compiled_contract_path(
+	contract_path: &PathBuf,
+	root_dir: &PathBuf,
+) -> Result<PathBuf, CacheError> {
+	let compiled_contract_path = get_compiled_contract_path(contract_path, root_dir)?;
+	if let Some(parent) = compiled_contract_path.parent() {
+		fs::create_dir_all(parent)
+			.map_err(|e| CacheError::DirCreation(parent.display().to_string(), e))?;
+	}
+	Ok(compiled_contract_path)
+}
+
 fn get_compiled_contract_path(
 	contract_path: &PathBuf,
 	root_dir: &PathBuf,
@@ -1,13 +1,17 @@
-use std::path::PathBuf;
+use std::{collections::BTreeMap, fs, path::PathBuf, thread};
 
 use assert_matches::assert_matches;
 
 use super::{
-	cache_dir, get_cache_path, get_compiled_contract_path, read_cache_file, Cache, CacheError,
+	cache_dir, changed_dependency, compute_hash, create_compiled_contract_path, get_cache,
+	get_cache_path, get_compiled_contract_path, hash_dependencies, invalidate_cache,
+	read_cache_file, set_cache_dir, stale_reason, verbose_cache_message, write_cache, Cache,
+	CacheError, CacheStatus,
 };
 
 const CAIRO_FOUNDRY_CACHE_DIR: &str = "cairo-foundry-cache";
 const CAIRO_FOUNDRY_COMPILED_CONTRACT_DIR: &str = "compiled-cairo-files";
+const COMPILER_VERSION: &str = "cairo-compile 0.10.3";
 
 #[test]
 fn read_cache_with_valid_input() {
@@ -19,6 +23,9 @@ fn read_cache_with_valid_input() {
 		contract_path: PathBuf::from("test_cairo_contracts/test_valid_program.cairo"),
 		compiled_contract_path: PathBuf::from("test_compiled_contracts/test_valid_program.json"),
 		hash: "0x0000000000000000000000000000000000000000000000000000000000000001".to_string(),
+		compiler_version: String::new(),
+		compiler_args: Vec::new(),
+		dependencies: BTreeMap::new(),
 	};
 
 	assert_eq!(cache, expected);
@@ -175,3 +182,532 @@ fn get_compiled_contract_path_for_invalid_root_dir() {
 	let cache_path = get_compiled_contract_path(&contract_path, &current_dir);
 	assert_matches!(cache_path, Err(CacheError::StripPrefixError(_)));
 }
+
+#[test]
+fn create_compiled_contract_path_keeps_directory_structure() {
+	let root_dir = PathBuf::from("test_cairo_contracts");
+	let compiled_contract_dir = cache_dir().unwrap().join(CAIRO_FOUNDRY_COMPILED_CONTRACT_DIR);
+
+	let compiled_contract_path =
+		create_compiled_contract_path(&root_dir.join("a").join("test_foo.cairo"), &root_dir)
+			.unwrap();
+	assert_eq!(
+		compiled_contract_path,
+		compiled_contract_dir.join("a").join("test_foo.json")
+	);
+	assert!(compiled_contract_path.parent().unwrap().is_dir());
+
+	// Contracts with the same name in different directories do not collide
+	let other_compiled_contract_path =
+		create_compiled_contract_path(&root_dir.join("b").join("test_foo.cairo"), &root_dir)
+			.unwrap();
+	assert_eq!(
+		other_compiled_contract_path,
+		compiled_contract_dir.join("b").join("test_foo.json")
+	);
+	assert_ne!(compiled_contract_path, other_compiled_contract_path);
+}
+
+#[test]
+fn write_cache_then_read_it_back() {
+	let root_dir = std::env::temp_dir().join("cairo-foundry-write-cache");
+	let contract_path = root_dir.join("test_nested_dir").join("test_write_cache.cairo");
+
+	let cache = Cache {
+		contract_path: contract_path.clone(),
+		compiled_contract_path: PathBuf::from("compiled-cairo-files/test_write_cache.json"),
+		hash: "0x0000000000000000000000000000000000000000000000000000000000000002".to_string(),
+		compiler_version: COMPILER_VERSION.to_string(),
+		compiler_args: Vec::new(),
+		dependencies: BTreeMap::new(),
+	};
+	write_cache(&cache, &root_dir).unwrap();
+
+	let cache_path = get_cache_path(&contract_path, &root_dir).unwrap();
+	assert_eq!(read_cache_file(&cache_path).unwrap(), cache);
+}
+
+#[test]
+fn get_cache_is_invalidated_when_contract_changes() {
+	let root_dir = std::env::temp_dir().join("cairo-foundry-get-cache");
+	fs::create_dir_all(&root_dir).unwrap();
+	let contract_path = root_dir.join("test_get_cache.cairo");
+	let compiled_contract_path = root_dir.join("test_get_cache.json");
+	fs::write(
+		&contract_path,
+		"func test_get_cache() {\n    return ();\n}\n",
+	)
+	.unwrap();
+	fs::write(&compiled_contract_path, "{}").unwrap();
+
+	let hash = compute_hash(&contract_path).unwrap();
+	assert_eq!(
+		get_cache(&contract_path, &root_dir, COMPILER_VERSION, &[]).unwrap(),
+		CacheStatus::Uncached(hash.clone())
+	);
+
+	let cache = Cache {
+		contract_path: contract_path.clone(),
+		compiled_contract_path,
+		hash,
+		compiler_version: COMPILER_VERSION.to_string(),
+		compiler_args: Vec::new(),
+		dependencies: BTreeMap::new(),
+	};
+	write_cache(&cache, &root_dir).unwrap();
+	assert_eq!(
+		get_cache(&contract_path, &root_dir, COMPILER_VERSION, &[]).unwrap(),
+		CacheStatus::Cached(cache)
+	);
+
+	fs::write(&contract_path, "func test_get_cache() {\n    ret;\n}\n").unwrap();
+	assert_matches!(
+		get_cache(&contract_path, &root_dir, COMPILER_VERSION, &[]).unwrap(),
+		CacheStatus::Uncached(_)
+	);
+}
+
+#[test]
+fn get_cache_is_invalidated_when_dependency_changes() {
+	let root_dir = std::env::temp_dir().join("cairo-foundry-get-cache-dependency");
+	fs::create_dir_all(&root_dir).unwrap();
+	let contract_path = root_dir.join("test_get_cache_dependency.cairo");
+	let helper_path = root_dir.join("helper.cairo");
+	let compiled_contract_path = root_dir.join("test_get_cache_dependency.json");
+	fs::write(
+		&contract_path,
+		"from helper import one\n\nfunc test_one() {\n    assert one() = 1;\n    return ();\n}\n",
+	)
+	.unwrap();
+	fs::write(&helper_path, "func one() -> felt {\n    return 1;\n}\n").unwrap();
+	fs::write(&compiled_contract_path, "{}").unwrap();
+
+	let cache = Cache {
+		contract_path: contract_path.clone(),
+		compiled_contract_path,
+		hash: compute_hash(&contract_path).unwrap(),
+		compiler_version: COMPILER_VERSION.to_string(),
+		compiler_args: Vec::new(),
+		dependencies: BTreeMap::from([(helper_path.clone(), compute_hash(&helper_path).unwrap())]),
+	};
+	write_cache(&cache, &root_dir).unwrap();
+	assert_eq!(None, changed_dependency(&cache, &root_dir));
+	assert_matches!(
+		get_cache(&contract_path, &root_dir, COMPILER_VERSION, &[]).unwrap(),
+		CacheStatus::Cached(_)
+	);
+
+	fs::write(&helper_path, "func one() -> felt {\n    return 2;\n}\n").unwrap();
+	assert_eq!(Some(helper_path), changed_dependency(&cache, &root_dir));
+	assert_matches!(
+		get_cache(&contract_path, &root_dir, COMPILER_VERSION, &[]).unwrap(),
+		CacheStatus::Uncached(_)
+	);
+}
+
+#[test]
+fn dependencies_are_the_source_files_of_the_instructions() {
+	let root_dir = std::env::temp_dir().join("cairo-foundry-hash-dependencies");
+	fs::create_dir_all(&root_dir).unwrap();
+	let contract_path = root_dir.join("test_hash_dependencies.cairo");
+	let helper_path = root_dir.join("helper.cairo");
+	let compiled_contract_path = root_dir.join("test_hash_dependencies.json");
+	fs::write(&contract_path, "").unwrap();
+	fs::write(&helper_path, "func one() -> felt {\n    return 1;\n}\n").unwrap();
+	let location = |file: &str| serde_json::json!({ "inst": { "input_file": { "filename": file }, "start_line": 1 } });
+	let compiled = serde_json::json!({
+		"debug_info": {
+			"instruction_locations": {
+				"0": location(contract_path.to_str().unwrap()),
+				"1": location(helper_path.to_str().unwrap()),
+				"2": location("autogen/generated.cairo"),
+			}
+		}
+	});
+	fs::write(&compiled_contract_path, compiled.to_string()).unwrap();
+
+	// The modules of the project are recorded relatively to its root
+	assert_eq!(
+		BTreeMap::from([(
+			PathBuf::from("helper.cairo"),
+			compute_hash(&helper_path).unwrap()
+		)]),
+		hash_dependencies(&contract_path, &compiled_contract_path, &root_dir).unwrap()
+	);
+}
+
+#[test]
+fn dependencies_outside_of_the_root_are_tracked() {
+	let dir = std::env::temp_dir().join("cairo-foundry-outside-dependency");
+	let root_dir = dir.join("project");
+	fs::create_dir_all(&root_dir).unwrap();
+	fs::create_dir_all(dir.join("lib")).unwrap();
+	let contract_path = root_dir.join("test_outside_dependency.cairo");
+	let helper_path = dir.join("lib").join("helper.cairo");
+	let compiled_contract_path = root_dir.join("test_outside_dependency.json");
+	fs::write(&contract_path, "").unwrap();
+	fs::write(&helper_path, "func one() -> felt {\n    return 1;\n}\n").unwrap();
+	let location = |file: &PathBuf| serde_json::json!({ "inst": { "input_file": { "filename": file.to_str().unwrap() }, "start_line": 1 } });
+	let compiled = serde_json::json!({
+		"debug_info": {
+			"instruction_locations": {
+				"0": location(&contract_path),
+				"1": location(&helper_path),
+			}
+		}
+	});
+	fs::write(&compiled_contract_path, compiled.to_string()).unwrap();
+
+	// The modules outside of the project are recorded with their canonical path
+	let dependencies =
+		hash_dependencies(&contract_path, &compiled_contract_path, &root_dir).unwrap();
+	assert_eq!(
+		BTreeMap::from([(
+			helper_path.canonicalize().unwrap(),
+			compute_hash(&helper_path).unwrap()
+		)]),
+		dependencies
+	);
+
+	let cache = Cache {
+		contract_path: contract_path.clone(),
+		compiled_contract_path,
+		hash: compute_hash(&contract_path).unwrap(),
+		compiler_version: COMPILER_VERSION.to_string(),
+		compiler_args: Vec::new(),
+		dependencies,
+	};
+	assert_eq!(None, changed_dependency(&cache, &root_dir));
+	fs::write(&helper_path, "func one() -> felt {\n    return 2;\n}\n").unwrap();
+	assert_eq!(
+		Some(helper_path.canonicalize().unwrap()),
+		changed_dependency(&cache, &root_dir)
+	);
+	fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn cache_entries_do_not_depend_on_the_location_of_the_project() {
+	let checkout = |name: &str| {
+		let root_dir = std::env::temp_dir().join("cairo-foundry-portable-cache").join(name);
+		fs::create_dir_all(root_dir.join("lib")).unwrap();
+		fs::write(
+			root_dir.join("test_portable.cairo"),
+			"func test_portable() {\n    return ();\n}\n",
+		)
+		.unwrap();
+		fs::write(
+			root_dir.join("lib").join("helper.cairo"),
+			"func one() -> felt {\n    return 1;\n}\n",
+		)
+		.unwrap();
+		root_dir
+	};
+	let first_root = checkout("first");
+	let second_root = checkout("second");
+	let first_contract = first_root.join("test_portable.cairo");
+	let second_contract = second_root.join("test_portable.cairo");
+
+	assert_eq!(
+		get_cache_path(&first_contract, &first_root).unwrap(),
+		get_cache_path(&second_contract, &second_root).unwrap()
+	);
+	assert_eq!(
+		get_compiled_contract_path(&first_contract, &first_root).unwrap(),
+		get_compiled_contract_path(&second_contract, &second_root).unwrap()
+	);
+	assert_eq!(
+		compute_hash(&first_contract).unwrap(),
+		compute_hash(&second_contract).unwrap()
+	);
+
+	// An entry written from the first checkout is up to date for the second one
+	let compiled_contract_path =
+		create_compiled_contract_path(&first_contract, &first_root).unwrap();
+	fs::write(&compiled_contract_path, "{}").unwrap();
+	let helper_path = PathBuf::from("lib").join("helper.cairo");
+	let cache = Cache {
+		contract_path: first_contract.clone(),
+		compiled_contract_path,
+		hash: compute_hash(&first_contract).unwrap(),
+		compiler_version: COMPILER_VERSION.to_string(),
+		compiler_args: Vec::new(),
+		dependencies: BTreeMap::from([(
+			helper_path.clone(),
+			compute_hash(&first_root.join(&helper_path)).unwrap(),
+		)]),
+	};
+	write_cache(&cache, &first_root).unwrap();
+	assert_matches!(
+		get_cache(&second_contract, &second_root, COMPILER_VERSION, &[]).unwrap(),
+		CacheStatus::Cached(_)
+	);
+}
+
+#[test]
+fn cache_entries_are_written_under_the_overridden_cache_dir() {
+	let root_dir = std::env::temp_dir().join("cairo-foundry-cache-dir-override");
+	let custom_cache_dir = root_dir.join("cache");
+	fs::create_dir_all(&root_dir).unwrap();
+	let contract_path = root_dir.join("test_cache_dir_override.cairo");
+	fs::write(&contract_path, "").unwrap();
+	let default_cache_dir = cache_dir().unwrap();
+
+	set_cache_dir(Some(custom_cache_dir.clone()));
+	assert_eq!(custom_cache_dir, cache_dir().unwrap());
+	let cache = Cache {
+		contract_path: contract_path.clone(),
+		compiled_contract_path: get_compiled_contract_path(&contract_path, &root_dir).unwrap(),
+		hash: compute_hash(&contract_path).unwrap(),
+		compiler_version: COMPILER_VERSION.to_string(),
+		compiler_args: Vec::new(),
+		dependencies: BTreeMap::new(),
+	};
+	write_cache(&cache, &root_dir).unwrap();
+	assert!(custom_cache_dir
+		.join(CAIRO_FOUNDRY_CACHE_DIR)
+		.join("test_cache_dir_override.json")
+		.is_file());
+	assert!(cache
+		.compiled_contract_path
+		.starts_with(custom_cache_dir.join(CAIRO_FOUNDRY_COMPILED_CONTRACT_DIR)));
+
+	set_cache_dir(None);
+	assert_eq!(default_cache_dir, cache_dir().unwrap());
+	fs::remove_dir_all(root_dir).unwrap();
+}
+
+#[test]
+fn invalidate_cache_removes_cache_entry() {
+	let root_dir = std::env::temp_dir().join("cairo-foundry-invalidate-cache");
+	fs::create_dir_all(&root_dir).unwrap();
+	let contract_path = root_dir.join("test_invalidate_cache.cairo");
+	let compiled_contract_path = root_dir.join("test_invalidate_cache.json");
+	fs::write(
+		&contract_path,
+		"func test_invalidate_cache() {\n    return ();\n}\n",
+	)
+	.unwrap();
+	fs::write(&compiled_contract_path, "{}").unwrap();
+
+	let cache = Cache {
+		contract_path: contract_path.clone(),
+		compiled_contract_path,
+		hash: compute_hash(&contract_path).unwrap(),
+		compiler_version: COMPILER_VERSION.to_string(),
+		compiler_args: Vec::new(),
+		dependencies: BTreeMap::new(),
+	};
+	write_cache(&cache, &root_dir).unwrap();
+	assert_matches!(
+		get_cache(&contract_path, &root_dir, COMPILER_VERSION, &[]).unwrap(),
+		CacheStatus::Cached(_)
+	);
+
+	invalidate_cache(&contract_path, &root_dir).unwrap();
+	assert_matches!(
+		get_cache(&contract_path, &root_dir, COMPILER_VERSION, &[]).unwrap(),
+		CacheStatus::Uncached(_)
+	);
+	// Invalidating a contract which is not cached is a no-op
+	invalidate_cache(&contract_path, &root_dir).unwrap();
+}
+
+#[test]
+fn cache_is_thread_safe() {
+	let root_dir = std::env::temp_dir().join("cairo-foundry-concurrent-cache");
+	let nested_dir = root_dir.join("test_nested_dir");
+	fs::create_dir_all(&nested_dir).unwrap();
+	// Make sure the cache directories are created concurrently
+	let cache_parent_dir = get_cache_path(&nested_dir.join("test_0.cairo"), &root_dir)
+		.unwrap()
+		.parent()
+		.unwrap()
+		.to_path_buf();
+	let _ = fs::remove_dir_all(cache_parent_dir);
+
+	let handles: Vec<_> = (0..8)
+		.map(|i| {
+			let root_dir = root_dir.clone();
+			let contract_path = nested_dir.join(format!("test_{i}.cairo"));
+			let compiled_contract_path = nested_dir.join(format!("test_{i}.json"));
+			thread::spawn(move || {
+				fs::write(
+					&contract_path,
+					format!("func test_{i}() {{\n    return ();\n}}\n"),
+				)
+				.unwrap();
+				fs::write(&compiled_contract_path, "{}").unwrap();
+
+				let hash =
+					match get_cache(&contract_path, &root_dir, COMPILER_VERSION, &[]).unwrap() {
+						CacheStatus::Uncached(hash) => hash,
+						CacheStatus::Cached(cache) => cache.hash,
+					};
+				let cache = Cache {
+					contract_path: contract_path.clone(),
+					compiled_contract_path,
+					hash,
+					compiler_version: COMPILER_VERSION.to_string(),
+					compiler_args: Vec::new(),
+					dependencies: BTreeMap::new(),
+				};
+				write_cache(&cache, &root_dir).unwrap();
+				assert_eq!(
+					get_cache(&contract_path, &root_dir, COMPILER_VERSION, &[]).unwrap(),
+					CacheStatus::Cached(cache)
+				);
+			})
+		})
+		.collect();
+
+	for handle in handles {
+		handle.join().expect("Cache access should not panic");
+	}
+}
+
+#[test]
+fn get_cache_is_stale_when_compiler_version_changes() {
+	let root_dir = std::env::temp_dir().join("cairo-foundry-compiler-version");
+	fs::create_dir_all(&root_dir).unwrap();
+	let contract_path = root_dir.join("test_compiler_version.cairo");
+	let compiled_contract_path = root_dir.join("test_compiler_version.json");
+	fs::write(
+		&contract_path,
+		"func test_compiler_version() {\n    return ();\n}\n",
+	)
+	.unwrap();
+	fs::write(&compiled_contract_path, "{}").unwrap();
+
+	let cache = Cache {
+		contract_path: contract_path.clone(),
+		compiled_contract_path,
+		hash: compute_hash(&contract_path).unwrap(),
+		compiler_version: COMPILER_VERSION.to_string(),
+		compiler_args: Vec::new(),
+		dependencies: BTreeMap::new(),
+	};
+	write_cache(&cache, &root_dir).unwrap();
+	assert_matches!(
+		get_cache(&contract_path, &root_dir, COMPILER_VERSION, &[]).unwrap(),
+		CacheStatus::Cached(_)
+	);
+	assert_matches!(
+		get_cache(&contract_path, &root_dir, "cairo-compile 0.11.0", &[]).unwrap(),
+		CacheStatus::Uncached(_)
+	);
+}
+
+#[test]
+fn get_cache_is_invalidated_when_compiler_args_change() {
+	let root_dir = std::env::temp_dir().join("cairo-foundry-compiler-args");
+	fs::create_dir_all(&root_dir).unwrap();
+	let contract_path = root_dir.join("test_compiler_args.cairo");
+	let compiled_contract_path = root_dir.join("test_compiler_args.json");
+	fs::write(
+		&contract_path,
+		"func test_compiler_args() {\n    return ();\n}\n",
+	)
+	.unwrap();
+	fs::write(&compiled_contract_path, "{}").unwrap();
+
+	let compiler_args = vec![String::from("--proof_mode")];
+	let cache = Cache {
+		contract_path: contract_path.clone(),
+		compiled_contract_path,
+		hash: compute_hash(&contract_path).unwrap(),
+		compiler_version: COMPILER_VERSION.to_string(),
+		compiler_args: compiler_args.clone(),
+		dependencies: BTreeMap::new(),
+	};
+	write_cache(&cache, &root_dir).unwrap();
+	assert_matches!(
+		get_cache(&contract_path, &root_dir, COMPILER_VERSION, &compiler_args).unwrap(),
+		CacheStatus::Cached(_)
+	);
+	assert_matches!(
+		get_cache(&contract_path, &root_dir, COMPILER_VERSION, &[]).unwrap(),
+		CacheStatus::Uncached(_)
+	);
+}
+
+#[test]
+fn cache_files_without_compiler_version_are_stale() {
+	let root_dir = std::env::temp_dir().join("cairo-foundry-legacy-cache");
+	fs::create_dir_all(&root_dir).unwrap();
+	let contract_path = root_dir.join("test_legacy_cache.cairo");
+	let compiled_contract_path = root_dir.join("test_legacy_cache.json");
+	fs::write(
+		&contract_path,
+		"func test_legacy_cache() {\n    return ();\n}\n",
+	)
+	.unwrap();
+	fs::write(&compiled_contract_path, "{}").unwrap();
+
+	// Cache file written before the compiler version was recorded
+	let cache_path = get_cache_path(&contract_path, &root_dir).unwrap();
+	fs::create_dir_all(cache_path.parent().unwrap()).unwrap();
+	fs::write(
+		&cache_path,
+		serde_json::json!({
+			"contract_path": contract_path,
+			"compiled_contract_path": compiled_contract_path,
+			"hash": compute_hash(&contract_path).unwrap(),
+		})
+		.to_string(),
+	)
+	.unwrap();
+
+	assert_matches!(
+		get_cache(&contract_path, &root_dir, COMPILER_VERSION, &[]).unwrap(),
+		CacheStatus::Uncached(_)
+	);
+}
+
+#[test]
+fn verbose_cache_message_explains_the_decision() {
+	let root_dir = std::env::temp_dir().join("cairo-foundry-verbose-cache");
+	fs::create_dir_all(&root_dir).unwrap();
+	let contract_path = root_dir.join("test_verbose_cache.cairo");
+	fs::write(&contract_path, "").unwrap();
+	let cache = Cache {
+		contract_path: contract_path.clone(),
+		compiled_contract_path: contract_path.clone(),
+		hash: String::from("0x1"),
+		compiler_version: COMPILER_VERSION.to_string(),
+		compiler_args: Vec::new(),
+		dependencies: BTreeMap::new(),
+	};
+
+	let reason = stale_reason(&cache, "0x2", COMPILER_VERSION, &[], &root_dir).map(|(_, r)| r);
+	assert_eq!(Some("the contract changed"), reason.as_deref());
+	assert_eq!(
+		None,
+		stale_reason(&cache, "0x1", COMPILER_VERSION, &[], &root_dir)
+	);
+
+	let cache_path = PathBuf::from("cache/test_verbose_cache.json");
+	assert_eq!(
+		format!(
+			"cache miss for '{}': stale cache, the contract changed (cache file \
+			 'cache/test_verbose_cache.json', stored hash: 0x1, current hash: 0x2)",
+			contract_path.display()
+		),
+		verbose_cache_message(
+			&contract_path,
+			&cache_path,
+			Some("0x1"),
+			"0x2",
+			reason.as_deref()
+		)
+	);
+	assert!(
+		verbose_cache_message(&contract_path, &cache_path, None, "0x2", None)
+			.contains(": no cache (")
+	);
+	assert!(
+		verbose_cache_message(&contract_path, &cache_path, Some("0x2"), "0x2", None)
+			.starts_with("cache hit for ")
+	);
+	fs::remove_dir_all(root_dir).unwrap();
+}
@@ -1,43 +1,116 @@
-use dirs;
+use lazy_static::lazy_static;
+use log::debug;
 use std::{
+	collections::HashMap,
 	fmt::Debug,
-	fs::File,
+	fs::{self, File},
 	io::{self, Write},
 	path::PathBuf,
 	process::Command,
+	sync::Mutex,
 };
 use thiserror::Error;
 use which::{which, Error as WhichError};
 
 pub mod cache;
 
-const JSON_FILE_EXTENTION: &str = "json";
-const CAIRO_COMPILE_BINARY: &str = "cairo-compile";
+#[cfg(test)]
+mod tests;
+
+use cache::{Cache, CacheError, CacheStatus};
+
+pub const CAIRO_COMPILE_BINARY: &str = "cairo-compile";
 
 #[derive(Error, Debug)]
 pub enum Error {
-	#[error("binary '{CAIRO_COMPILE_BINARY}' not found: {0}")]
-	CairoCompileBinaryNotFound(#[from] WhichError),
-	#[error("failed to execute a process: {0}")]
-	RunProcess(io::Error),
-	#[error("binary '{0}' failed to compile '{1}'")]
-	Compilation(String, String),
-	#[error("file '{0}' has no stem")]
-	StemlessFile(String),
-	#[error("cache directory does not exist on this platform")]
-	CacheDirSupported,
+	#[error("binary '{0}' not found: {1}")]
+	CairoCompileBinaryNotFound(String, #[source] WhichError),
+	#[error("failed to execute '{0}': {1}")]
+	RunProcess(String, io::Error),
+	#[error("binary '{0}' failed to compile '{1}':\n{2}")]
+	Compilation(String, String, String),
+	#[error("binary '{0}' failed to report its version: {1}")]
+	CompilerVersion(String, String),
 	#[error("failed to create file '{0}': {1}")]
 	FileCreation(String, io::Error),
-	#[error("failed to create directory '{0}': {1}")]
-	DirCreation(String, io::Error),
 	#[error("failed to write to file '{0}': {1}")]
 	WriteToFile(String, io::Error),
+	#[error(transparent)]
+	Cache(#[from] CacheError),
+}
+
+/// The cairo compiler used to compile cairo files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Compiler {
+	/// Name (looked up in the PATH) or path of the compiler binary
+	pub binary: String,
+	/// Directories searched for the imported modules, passed as `--cairo_path`
+	pub cairo_path: Vec<PathBuf>,
+	/// Extra arguments appended to the compiler invocation (eg: `--proof_mode`)
+	pub args: Vec<String>,
+}
+
+impl Default for Compiler {
+	fn default() -> Self {
+		Self {
+			binary: CAIRO_COMPILE_BINARY.to_string(),
+			cairo_path: Vec::new(),
+			args: Vec::new(),
+		}
+	}
+}
+
+lazy_static! {
+	/// Versions reported by the compiler binaries, so that each binary is only queried once
+	static ref COMPILER_VERSIONS: Mutex<HashMap<PathBuf, String>> = Mutex::new(HashMap::new());
+}
+
+impl Compiler {
+	/// Arguments given to the compiler along with the cairo file, they change the compiled file
+	pub fn arguments(&self) -> Vec<String> {
+		let mut arguments = Vec::new();
+		if !self.cairo_path.is_empty() {
+			let cairo_path: Vec<String> =
+				self.cairo_path.iter().map(|dir| dir.display().to_string()).collect();
+			arguments.push(String::from("--cairo_path"));
+			arguments.push(cairo_path.join(":"));
+		}
+		arguments.extend(self.args.iter().cloned());
+		arguments
+	}
+
+	/// Version of the compiler, as reported by `<binary> --version`
+	pub fn version(&self) -> Result<String, Error> {
+		let path_to_cairo_compiler = which(&self.binary)
+			.map_err(|e| Error::CairoCompileBinaryNotFound(self.binary.clone(), e))?;
+
+		let mut versions = COMPILER_VERSIONS.lock().expect("Compiler versions lock poisoned");
+		if let Some(version) = versions.get(&path_to_cairo_compiler) {
+			return Ok(version.clone())
+		}
+
+		let output =
+			Command::new(&path_to_cairo_compiler).arg("--version").output().map_err(|e| {
+				Error::RunProcess(path_to_cairo_compiler.as_path().display().to_string(), e)
+			})?;
+		if !output.status.success() {
+			return Err(Error::CompilerVersion(
+				path_to_cairo_compiler.as_path().display().to_string(),
+				String::from_utf8_lossy(&output.stderr).to_string(),
+			))
+		}
+
+		let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+		versions.insert(path_to_cairo_compiler, version.clone());
+		Ok(version)
+	}
 }
 
 /// Compile a cairo file.
 ///
 /// The given `&PathBuf` will be compiled as a simple Cairo file which can then be executed.
-/// The generated file will be a JSON file saved in your cache directory.
+/// The generated file will be a JSON file saved in your cache directory (see `cache::cache_dir`),
+/// at the path of the cairo file relatively to `root_dir`.
 ///
 /// Returns the `PathBuf` to the generated file and print the displayable element from the given
 /// `PathBuf' or nothing, which means that everything is fine.
@@ -48,58 +121,107 @@ pub enum Error {
 ///
 /// ```no_run
 /// # use std::path::PathBuf;
-/// # use cairo_foundry::compile::{compile, Error};
+/// # use cairo_foundry::compile::{compile, Compiler, Error};
 /// # fn foo() -> Result<(), Error> {
-///     let mut program = PathBuf::from("path_to_your_program");
-///     let compiled_program_path = compile(&program)?;
+///     let root_dir = PathBuf::from("path_to_your_project");
+///     let program = root_dir.join("path_to_your_program");
+///     let compiled_program_path = compile(&program, &root_dir, &Compiler::default())?;
 /// # Ok(())
 /// # }
 /// ```
-pub fn compile(path_to_cairo_file: &PathBuf) -> Result<PathBuf, Error> {
-	let path_to_cairo_compiler = which(CAIRO_COMPILE_BINARY)?;
+pub fn compile(
+	path_to_cairo_file: &PathBuf,
+	root_dir: &PathBuf,
+	compiler: &Compiler,
+) -> Result<PathBuf, Error> {
+	let path_to_cairo_compiler = which(&compiler.binary)
+		.map_err(|e| Error::CairoCompileBinaryNotFound(compiler.binary.clone(), e))?;
 
+	debug!(
+		"Running {} {} {}",
+		path_to_cairo_compiler.display(),
+		path_to_cairo_file.display(),
+		compiler.arguments().join(" ")
+	);
 	// Use cairo-compile binary in order to compile the .cairo file
-	let compilation_output = Command::new(CAIRO_COMPILE_BINARY)
-		.args([&path_to_cairo_file])
+	let compilation_output = Command::new(&path_to_cairo_compiler)
+		.arg(path_to_cairo_file)
+		.args(compiler.arguments())
 		.output()
-		.map_err(Error::RunProcess)?;
+		.map_err(|e| {
+			Error::RunProcess(path_to_cairo_compiler.as_path().display().to_string(), e)
+		})?;
 
-	// Check if the compilation was successful
+	// Check if the compilation was successful, the error output of the compiler tells why not
+	// (eg: the location of a syntax error)
 	if !compilation_output.status.success() {
 		return Err(Error::Compilation(
 			path_to_cairo_compiler.as_path().display().to_string(),
-			String::from_utf8(compilation_output.stderr).unwrap_or_else(|e| {
-				format!(
-					"{} with non utf8 error message: {}",
-					path_to_cairo_file.as_path().display(),
-					e
-				)
-			}),
+			path_to_cairo_file.as_path().display().to_string(),
+			String::from_utf8_lossy(&compilation_output.stderr).trim_end().to_string(),
 		))
 	}
 
-	// Retrieve only the file name to create a clean compiled file name.
-	let filename = path_to_cairo_file
-		.file_stem()
-		.ok_or_else(|| Error::StemlessFile(path_to_cairo_file.as_path().display().to_string()))?;
+	let compiled_program_path = cache::create_compiled_contract_path(path_to_cairo_file, root_dir)?;
 
-	let path_to_cache_dir = dirs::cache_dir().ok_or(Error::CacheDirSupported)?;
-
-	// Build path to save the  compiled file
-	let mut compiled_program_path = PathBuf::new();
-	compiled_program_path.push(&path_to_cache_dir);
-	compiled_program_path.push("compiled-cairo-files");
-	std::fs::create_dir_all(&compiled_program_path).map_err(|e| {
-		Error::DirCreation(compiled_program_path.as_path().display().to_string(), e)
+	// Store the command output inside a json file, through a temporary file so that an
+	// interrupted compilation does not leave a truncated compiled file behind
+	let tmp_path = compiled_program_path.with_extension("json.tmp");
+	let mut file = File::create(&tmp_path).map_err(|e| {
+		Error::FileCreation(compiled_program_path.as_path().display().to_string(), e)
+	})?;
+	file.write_all(&compilation_output.stdout).map_err(|e| {
+		Error::WriteToFile(compiled_program_path.as_path().display().to_string(), e)
+	})?;
+	fs::rename(&tmp_path, &compiled_program_path).map_err(|e| {
+		Error::WriteToFile(compiled_program_path.as_path().display().to_string(), e)
 	})?;
-	compiled_program_path.push(filename);
-	compiled_program_path.set_extension(JSON_FILE_EXTENTION);
-
-	// Create a file to store command output inside a json file
-	let mut file = File::create(&compiled_program_path)
-		.map_err(|e| Error::FileCreation(path_to_cache_dir.as_path().display().to_string(), e))?;
-	file.write_all(&compilation_output.stdout)
-		.map_err(|e| Error::WriteToFile(path_to_cache_dir.as_path().display().to_string(), e))?;
 
 	Ok(compiled_program_path)
 }
+
+/// Compile a cairo file, unless an up to date compiled version of it is already in the cache.
+///
+/// The cache entry of the file is identified by its path relatively to `root_dir`, and updated
+/// each time the file is compiled. Entries written by another version of the compiler, with
+/// other compiler arguments, or before one of the modules imported by the file changed, are
+/// stale.
+///
+/// Returns the `PathBuf` to the compiled file, along with the `CacheStatus` of the file before the
+/// call.
+pub fn compile_with_cache(
+	path_to_cairo_file: &PathBuf,
+	root_dir: &PathBuf,
+	compiler: &Compiler,
+) -> Result<(PathBuf, CacheStatus), Error> {
+	let compiler_version = compiler.version()?;
+	let compiler_args = compiler.arguments();
+	match cache::get_cache(
+		path_to_cairo_file,
+		root_dir,
+		&compiler_version,
+		&compiler_args,
+	)? {
+		CacheStatus::Cached(cache) => Ok((
+			cache.compiled_contract_path.clone(),
+			CacheStatus::Cached(cache),
+		)),
+		CacheStatus::Uncached(hash) => {
+			let compiled_contract_path = compile(path_to_cairo_file, root_dir, compiler)?;
+			let dependencies =
+				cache::hash_dependencies(path_to_cairo_file, &compiled_contract_path, root_dir)?;
+			cache::write_cache(
+				&Cache {
+					contract_path: path_to_cairo_file.to_owned(),
+					compiled_contract_path: compiled_contract_path.clone(),
+					hash: hash.clone(),
+					compiler_version,
+					compiler_args,
+					dependencies,
+				},
+				root_dir,
+			)?;
+			Ok((compiled_contract_path, CacheStatus::Uncached(hash)))
+		},
+	}
+}
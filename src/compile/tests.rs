@@ -0,0 +1,80 @@
+use std::{fs, path::PathBuf};
+
+use assert_matches::assert_matches;
+use uuid::Uuid;
+
+use super::{cache::CacheStatus, compile, compile_with_cache, Compiler, Error};
+
+#[test]
+fn compile_with_unknown_compiler_binary() {
+	let compiler = Compiler {
+		binary: String::from("not-a-cairo-compiler"),
+		cairo_path: Vec::new(),
+		args: Vec::new(),
+	};
+
+	let result = compile(
+		&PathBuf::from("test_cairo_contracts/test_valid_program.cairo"),
+		&PathBuf::from("test_cairo_contracts"),
+		&compiler,
+	);
+
+	assert_matches!(result, Err(Error::CairoCompileBinaryNotFound(binary, _)) if binary == "not-a-cairo-compiler");
+}
+
+#[test]
+fn compilation_error_contains_the_compiler_message() {
+	let root_dir = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	fs::create_dir_all(&root_dir).unwrap();
+	let contract_path = root_dir.join("test_broken.cairo");
+	fs::write(&contract_path, "func test_broken() {\n    let x = ;\n}\n").unwrap();
+
+	let err = compile(&contract_path, &root_dir, &Compiler::default()).unwrap_err();
+
+	assert_matches!(&err, Error::Compilation(_, path, _) if *path == contract_path.display().to_string());
+	assert!(err.to_string().contains("Unexpected token"), "{err}");
+	fs::remove_dir_all(root_dir).unwrap();
+}
+
+#[test]
+fn cairo_path_is_passed_before_extra_arguments() {
+	let compiler = Compiler {
+		binary: String::from("cairo-compile"),
+		cairo_path: vec![PathBuf::from("lib"), PathBuf::from("vendor")],
+		args: vec![String::from("--proof_mode")],
+	};
+
+	assert_eq!(
+		vec!["--cairo_path", "lib:vendor", "--proof_mode"],
+		compiler.arguments()
+	);
+}
+
+#[test]
+fn changing_an_imported_module_triggers_recompilation() {
+	let root_dir = std::env::temp_dir().join(Uuid::new_v4().to_string());
+	fs::create_dir_all(&root_dir).unwrap();
+	let contract_path = root_dir.join("test_import.cairo");
+	let helper_path = root_dir.join("helper.cairo");
+	fs::write(
+		&contract_path,
+		"from helper import one\n\nfunc test_one() {\n    assert one() = 1;\n    return ();\n}\n",
+	)
+	.unwrap();
+	fs::write(&helper_path, "func one() -> felt {\n    return 1;\n}\n").unwrap();
+	let compiler = Compiler {
+		cairo_path: vec![root_dir.clone()],
+		..Default::default()
+	};
+
+	let (_, status) = compile_with_cache(&contract_path, &root_dir, &compiler).unwrap();
+	assert_matches!(status, CacheStatus::Uncached(_));
+	let (_, status) = compile_with_cache(&contract_path, &root_dir, &compiler).unwrap();
+	assert_matches!(status, CacheStatus::Cached(_));
+
+	fs::write(&helper_path, "func one() -> felt {\n    return 2;\n}\n").unwrap();
+	let (_, status) = compile_with_cache(&contract_path, &root_dir, &compiler).unwrap();
+	assert_matches!(status, CacheStatus::Uncached(_));
+
+	fs::remove_dir_all(root_dir).unwrap();
+}
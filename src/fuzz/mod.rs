@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod tests;
+
+use lazy_static::lazy_static;
+use num_bigint::{BigInt, Sign};
+use num_traits::One;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::Serialize;
+use std::fmt::Display;
+
+/// Prefix of the entrypoints executed with randomized arguments
+pub const FUZZ_ENTRYPOINT_PREFIX: &str = "fuzz_";
+pub const DEFAULT_FUZZ_RUNS: usize = 100;
+/// Maximum number of runs attempted while shrinking a counterexample
+pub const MAX_SHRINK_ATTEMPTS: usize = 1000;
+
+lazy_static! {
+	/// Prime of the cairo field: 2^251 + 17 * 2^192 + 1
+	pub static ref FIELD_PRIME: BigInt =
+		(BigInt::one() << 251) + BigInt::from(17) * (BigInt::one() << 192) + BigInt::one();
+}
+
+/// Returns true if the entrypoint should be fuzzed rather than executed once
+pub fn is_fuzz_entrypoint(entrypoint: &str) -> bool {
+	entrypoint.starts_with(FUZZ_ENTRYPOINT_PREFIX)
+}
+
+/// Inputs which made a fuzzed test fail
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct Counterexample {
+	/// Seed of the fuzzing session (ie: the `--fuzz-seed` replaying it)
+	pub fuzz_seed: u64,
+	/// Seed used to generate the inputs of the failing run, see `random_felts`
+	pub seed: u64,
+	/// Arguments given to the entrypoint, as decimal strings
+	pub inputs: Vec<String>,
+	/// Smallest arguments found to still make the test fail, see `shrink`
+	pub minimized_inputs: Vec<String>,
+}
+
+impl Counterexample {
+	pub fn new(fuzz_seed: u64, seed: u64, inputs: &[BigInt], minimized_inputs: &[BigInt]) -> Self {
+		Self {
+			fuzz_seed,
+			seed,
+			inputs: inputs.iter().map(BigInt::to_string).collect(),
+			minimized_inputs: minimized_inputs.iter().map(BigInt::to_string).collect(),
+		}
+	}
+}
+
+impl Display for Counterexample {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"[{}], minimized to [{}] (replay with --fuzz-seed {})",
+			self.inputs.join(", "),
+			self.minimized_inputs.join(", "),
+			self.fuzz_seed
+		)
+	}
+}
+
+/// Outcome of the fuzzing of a test entrypoint
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct FuzzReport {
+	/// Number of runs executed (the failing one included)
+	pub runs: usize,
+	/// Inputs of the failing run, if any
+	pub counterexample: Option<Counterexample>,
+}
+
+/// Generate a random field element.
+///
+/// Uniformly distributed felts are rarely interesting,
+/// so edge cases (0, 1, P - 1, 2^128, ...) and small values are generated more often.
+pub fn random_felt(rng: &mut impl Rng) -> BigInt {
+	match rng.gen_range(0..4) {
+		0 => {
+			let edge_cases = [
+				BigInt::from(0),
+				BigInt::from(1),
+				BigInt::from(2),
+				&*FIELD_PRIME - 1,
+				&*FIELD_PRIME - 2,
+				(BigInt::one() << 128) - 1,
+				BigInt::one() << 128,
+			];
+			edge_cases[rng.gen_range(0..edge_cases.len())].clone()
+		},
+		1 => BigInt::from(rng.gen::<u8>()),
+		2 => BigInt::from(rng.gen::<u128>()),
+		_ => {
+			let mut bytes = [0u8; 32];
+			rng.fill(&mut bytes);
+			BigInt::from_bytes_be(Sign::Plus, &bytes) % &*FIELD_PRIME
+		},
+	}
+}
+
+/// Generate `count` random field elements.
+/// The same seed always generates the same felts, making a failing run reproducible.
+pub fn random_felts(seed: u64, count: usize) -> Vec<BigInt> {
+	let mut rng = StdRng::seed_from_u64(seed);
+	(0..count).map(|_| random_felt(&mut rng)).collect()
+}
+
+/// Values smaller than `felt` worth trying when shrinking it, from the smallest
+fn shrink_candidates(felt: &BigInt) -> Vec<BigInt> {
+	let mut candidates = vec![BigInt::from(0), felt / 2, felt - 1];
+	candidates.retain(|candidate| candidate >= &BigInt::from(0) && candidate < felt);
+	candidates.dedup();
+	candidates
+}
+
+/// Minimize the inputs of a failing test.
+///
+/// Each component is repeatedly replaced by a smaller value (0, half of it, or itself minus 1),
+/// as long as `still_fails` returns true for the new inputs.
+/// At most `MAX_SHRINK_ATTEMPTS` inputs are tried, the smallest failing inputs found so far
+/// are returned once this limit is reached.
+pub fn shrink<E>(
+	inputs: &[BigInt],
+	mut still_fails: impl FnMut(&[BigInt]) -> Result<bool, E>,
+) -> Result<Vec<BigInt>, E> {
+	let mut current = inputs.to_vec();
+	let mut attempts = 0;
+	loop {
+		let mut improved = false;
+		for index in 0..current.len() {
+			for candidate in shrink_candidates(&current[index]) {
+				if attempts >= MAX_SHRINK_ATTEMPTS {
+					return Ok(current)
+				}
+				attempts += 1;
+
+				let mut tentative = current.clone();
+				tentative[index] = candidate;
+				if still_fails(&tentative)? {
+					current = tentative;
+					improved = true;
+					break
+				}
+			}
+		}
+		if !improved {
+			return Ok(current)
+		}
+	}
+}
@@ -0,0 +1,13 @@
+func fuzz_addition_is_commutative(a: felt, b: felt) {
+    assert a + b = b + a;
+    return ();
+}
+
+func fuzz_without_arguments() {
+    return ();
+}
+
+func fuzz_is_always_zero(a: felt) {
+    assert a = 0;
+    return ();
+}
@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use num_bigint::BigInt;
+use rstest::rstest;
+
+use super::{random_felts, shrink, FIELD_PRIME, MAX_SHRINK_ATTEMPTS};
+use crate::cli::commands::test::{tests::run_single_fuzz_test, TestStatus};
+
+#[test]
+fn random_felts_are_reproducible() {
+	assert_eq!(random_felts(42, 10), random_felts(42, 10));
+	assert_eq!(random_felts(42, 3).len(), 3);
+}
+
+#[test]
+fn random_felts_are_field_elements() {
+	for seed in 0..100 {
+		for felt in random_felts(seed, 5) {
+			assert!(felt >= BigInt::from(0));
+			assert!(felt < *FIELD_PRIME);
+		}
+	}
+}
+
+#[test]
+fn shrink_finds_minimal_failing_inputs() {
+	let inputs = vec![BigInt::from(1000), BigInt::from(5)];
+	let minimized = shrink(&inputs, |inputs| Ok::<_, ()>(inputs[0] >= BigInt::from(10)));
+	assert_eq!(Ok(vec![BigInt::from(10), BigInt::from(0)]), minimized);
+}
+
+#[test]
+fn shrink_is_bounded() {
+	let inputs = vec![&*FIELD_PRIME - 1];
+	let mut attempts = 0;
+	let minimized = shrink(&inputs, |_| {
+		attempts += 1;
+		Ok::<_, ()>(false)
+	});
+	assert_eq!(Ok(inputs), minimized);
+	assert!(attempts <= MAX_SHRINK_ATTEMPTS);
+
+	let minimized = shrink(&[BigInt::from(42)], |_| Err("failed to run"));
+	assert_eq!(Err("failed to run"), minimized);
+}
+
+#[rstest]
+#[case("fuzz_addition_is_commutative", TestStatus::SUCCESS)]
+#[case("fuzz_without_arguments", TestStatus::SUCCESS)]
+#[case("fuzz_is_always_zero", TestStatus::FAILURE)]
+fn test_fuzz(#[case] test_name: &str, #[case] expected_status: TestStatus) {
+	let path = PathBuf::from("src/fuzz/test_cairo_programs/fuzz.cairo");
+	let result = run_single_fuzz_test(test_name, &path, 50, 42).expect("Should be Ok");
+	assert_eq!(expected_status, result.success);
+
+	let report = result.fuzz.expect("Should contain a fuzz report");
+	match expected_status {
+		TestStatus::FAILURE => {
+			let counterexample = report.counterexample.expect("Should contain a counterexample");
+			assert_eq!(42, counterexample.fuzz_seed);
+			assert_eq!(1, counterexample.inputs.len());
+			assert_ne!("0", counterexample.inputs[0]);
+			assert_eq!(
+				counterexample.inputs,
+				random_felts(counterexample.seed, 1)
+					.iter()
+					.map(BigInt::to_string)
+					.collect::<Vec<_>>()
+			);
+			assert_eq!(vec![String::from("1")], counterexample.minimized_inputs);
+		},
+		_ => {
+			assert_eq!(50, report.runs);
+			assert_eq!(None, report.counterexample);
+		},
+	}
+}
+
+#[test]
+fn test_fuzz_seed_reproduces_session() {
+	let path = PathBuf::from("src/fuzz/test_cairo_programs/fuzz.cairo");
+	let run = |fuzz_seed| {
+		run_single_fuzz_test("fuzz_is_always_zero", &path, 50, fuzz_seed)
+			.expect("Should be Ok")
+			.fuzz
+			.expect("Should contain a fuzz report")
+	};
+
+	assert_eq!(run(7), run(7));
+}
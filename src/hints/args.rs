@@ -0,0 +1,72 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use cairo_rs::{
+	hint_processor::{
+		builtin_hint_processor::hint_utils::get_integer_from_var_name,
+		hint_processor_definition::HintReference,
+	},
+	serde::deserialize_program::ApTracking,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+
+use crate::fuzz::FIELD_PRIME;
+
+/// Felt value of an integer, in the range [0, P): negative integers wrap around P
+pub(crate) fn to_felt(value: &BigInt) -> BigInt {
+	((value % &*FIELD_PRIME) + &*FIELD_PRIME) % &*FIELD_PRIME
+}
+
+/// Value of a felt argument of a hint: either a felt variable or an integer literal
+/// (decimal, or hexadecimal with a `0x` prefix), the literals being reduced to a felt as the
+/// variables are (ie: `-1` is P - 1)
+pub(crate) fn get_felt_arg<'a>(
+	arg: &'a str,
+	vm: &'a VirtualMachine,
+	ids_data: &'a HashMap<String, HintReference>,
+	ap_tracking: &'a ApTracking,
+) -> Result<Cow<'a, BigInt>, VirtualMachineError> {
+	let literal = match arg.strip_prefix("0x") {
+		Some(hex) => BigInt::parse_bytes(hex.as_bytes(), 16),
+		None => arg.parse::<BigInt>().ok(),
+	};
+	match literal {
+		Some(literal) => Ok(Cow::Owned(to_felt(&literal))),
+		None => get_integer_from_var_name(arg, vm, ids_data, ap_tracking),
+	}
+}
+
+/// Returns an error if a hint is given arguments
+pub(crate) fn no_args(hint: &str, args: &[String]) -> Result<(), VirtualMachineError> {
+	match args.iter().filter(|arg| !arg.is_empty()).count() {
+		0 => Ok(()),
+		n => Err(VirtualMachineError::CustomHint(format!(
+			"{hint} expects no argument, got {n}"
+		))),
+	}
+}
+
+/// Returns the only argument of a hint, or an error naming the hint
+pub(crate) fn single_arg<'a>(
+	hint: &str,
+	args: &'a [String],
+) -> Result<&'a str, VirtualMachineError> {
+	let [arg] = exact_args(hint, args)?;
+	Ok(arg.as_str())
+}
+
+/// Returns the `N` arguments of a hint, or an error naming the hint.
+/// The empty arguments are not counted (ie: a hint called without argument gets an empty one).
+pub(crate) fn exact_args<'a, const N: usize>(
+	hint: &str,
+	args: &'a [String],
+) -> Result<&'a [String; N], VirtualMachineError> {
+	let count = args.iter().filter(|arg| !arg.is_empty()).count();
+	match <&[String; N]>::try_from(args) {
+		Ok(args) if count == N => Ok(args),
+		_ => Err(VirtualMachineError::CustomHint(format!(
+			"{hint} expects {N} argument{}, got {count}",
+			if N == 1 { "" } else { "s" }
+		))),
+	}
+}
@@ -0,0 +1,398 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+	collections::HashMap,
+	sync::{PoisonError, RwLock},
+};
+
+use cairo_rs::{
+	hint_processor::{
+		builtin_hint_processor::hint_utils::get_ptr_from_var_name,
+		hint_processor_definition::HintReference,
+	},
+	serde::deserialize_program::ApTracking,
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use lazy_static::lazy_static;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use similar::TextDiff;
+use uuid::Uuid;
+
+use super::{
+	args::{exact_args, get_felt_arg},
+	output_buffer::get_execution_uuid,
+};
+use crate::io::felt_format::format_felt;
+
+pub const ASSERTION_FAILED_PREFIX: &str = "assertion failed";
+
+/// Felts wider than a limb are shown as their two 128-bit limbs (as an `Uint256` would be) in the
+/// diffs, so that the differing half of a big felt stands out
+const LIMB_BITS: u64 = 128;
+
+lazy_static! {
+	/// the `ASSERTION_DIFFS` hashmap stores the diff of the operands of a failed assertion, keyed
+	/// by the Uuid of the execution (see `output_buffer`). Entries are removed by the test runner
+	/// once the execution is over (see `take_assertion_diff`).
+	static ref ASSERTION_DIFFS: RwLock<HashMap<Uuid, String>> = RwLock::new(HashMap::new());
+}
+
+/// Remove and return the diff of the operands of the assertion which failed during an execution,
+/// if any
+pub fn take_assertion_diff(execution_uuid: &Uuid) -> Option<String> {
+	ASSERTION_DIFFS
+		.write()
+		.unwrap_or_else(PoisonError::into_inner)
+		.remove(execution_uuid)
+}
+
+/// Record the diff of the operands of a failed assertion for the current execution.
+/// Nothing is recorded if the hint is not executed by the test runner.
+fn record_assertion_diff(exec_scopes: &ExecutionScopes, diff: String) {
+	if let Ok(execution_uuid) = get_execution_uuid(exec_scopes) {
+		ASSERTION_DIFFS
+			.write()
+			.unwrap_or_else(PoisonError::into_inner)
+			.insert(execution_uuid, diff);
+	}
+}
+
+/// Lines showing a felt in a diff, `label` being its position in an array (if any): the felt
+/// itself (in the selected `FeltFormat`), or its `low` and `high` limbs if it does not fit in a
+/// single limb
+fn felt_lines(label: &str, felt: &BigInt) -> Vec<String> {
+	let separator = if label.is_empty() { "" } else { ": " };
+	if felt.bits() <= LIMB_BITS {
+		return vec![format!("{label}{separator}{}", format_felt(felt))]
+	}
+	let limb_mask = (BigInt::from(1) << LIMB_BITS) - 1;
+	let limb_label = |limb: &str| match label {
+		"" => format!("{limb}: "),
+		_ => format!("{label}.{limb}: "),
+	};
+	vec![
+		format!("{}{:#x}", limb_label("low"), felt & limb_mask),
+		format!("{}{:#x}", limb_label("high"), felt >> LIMB_BITS),
+	]
+}
+
+/// Unified diff of two operands, which are either single felts or arrays of felts (whose elements
+/// are labelled by their index)
+fn operands_diff(
+	lhs: &str,
+	lhs_felts: &[BigInt],
+	rhs: &str,
+	rhs_felts: &[BigInt],
+	array: bool,
+) -> String {
+	let lines = |felts: &[BigInt]| -> String {
+		felts
+			.iter()
+			.enumerate()
+			.flat_map(|(index, felt)| {
+				let label = if array {
+					format!("[{index}]")
+				} else {
+					String::new()
+				};
+				felt_lines(&label, felt)
+			})
+			.map(|line| line + "\n")
+			.collect()
+	};
+	let (lhs_lines, rhs_lines) = (lines(lhs_felts), lines(rhs_felts));
+	TextDiff::from_lines(&lhs_lines, &rhs_lines)
+		.unified_diff()
+		.header(lhs, rhs)
+		.to_string()
+}
+
+/// Check the two operands of an assertion with `predicate`.
+///
+/// Returns a `CustomHint` error naming the assertion and its operands, along with their values,
+/// if the predicate does not hold. The diff of the operands is recorded along with it if
+/// `with_diff` is set.
+#[allow(clippy::too_many_arguments)]
+fn check_assertion(
+	assertion: &str,
+	predicate: impl Fn(&BigInt, &BigInt) -> bool,
+	with_diff: bool,
+	vm: &VirtualMachine,
+	exec_scopes: &ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let [lhs, rhs] = exact_args(assertion, args)?;
+	let lhs_value = get_felt_arg(lhs, vm, ids_data, ap_tracking)?;
+	let rhs_value = get_felt_arg(rhs, vm, ids_data, ap_tracking)?;
+
+	if predicate(&lhs_value, &rhs_value) {
+		Ok(())
+	} else {
+		if with_diff {
+			let diff = operands_diff(
+				lhs,
+				&[(*lhs_value).clone()],
+				rhs,
+				&[(*rhs_value).clone()],
+				false,
+			);
+			record_assertion_diff(exec_scopes, diff);
+		}
+		Err(VirtualMachineError::CustomHint(format!(
+			"{ASSERTION_FAILED_PREFIX}: {assertion}({lhs}, {rhs})\n  {lhs}: {}\n  {rhs}: {}",
+			format_felt(&lhs_value),
+			format_felt(&rhs_value)
+		)))
+	}
+}
+
+/// Assert that two felts are equal
+///
+/// Operands are names of felt variables, or integer literals.
+///
+/// Returns nothing if the assertion holds, an error showing both values otherwise, along with
+/// their diff (see `assert_array_eq`)
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_sum() {
+///     let sum = 2 + 2;
+///     %{ assert_eq(sum, 4) %}
+///     return ();
+/// }
+/// ```
+pub fn assert_eq(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	check_assertion(
+		"assert_eq",
+		|lhs, rhs| lhs == rhs,
+		true,
+		vm,
+		exec_scopes,
+		ids_data,
+		ap_tracking,
+		args,
+	)
+}
+
+/// Felts of an array argument of a hint, given the name of its `felt*` variable and its length
+/// (a felt variable or an integer literal)
+fn get_felt_array_arg(
+	array: &str,
+	len: &str,
+	vm: &VirtualMachine,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	let ptr = get_ptr_from_var_name(array, vm, ids_data, ap_tracking)?;
+	let len = get_felt_arg(len, vm, ids_data, ap_tracking)?;
+	let len = len.to_usize().ok_or_else(|| {
+		VirtualMachineError::CustomHint(format!("invalid length of array {array}: {len}"))
+	})?;
+	(0..len)
+		.map(|index| vm.get_integer(&(ptr + index)).map(|felt| felt.into_owned()))
+		.collect()
+}
+
+/// Assert that two arrays of felts are equal
+///
+/// The arguments are each array (a `felt*` variable) followed by its length (a felt variable or
+/// an integer literal).
+///
+/// Returns nothing if the assertion holds, an error otherwise, along with a unified diff of the
+/// two arrays: each element is shown on its own line, the felts which do not fit in 128 bits
+/// being split into their `low` and `high` limbs.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_arrays() {
+///     let (a: felt*) = alloc();
+///     assert a[0] = 1;
+///     assert a[1] = 2;
+///     let (b: felt*) = alloc();
+///     assert b[0] = 1;
+///     assert b[1] = 2;
+///     %{ assert_array_eq(a, 2, b, 2) %}
+///     return ();
+/// }
+/// ```
+pub fn assert_array_eq(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let [lhs, lhs_len, rhs, rhs_len] = exact_args("assert_array_eq", args)?;
+	let lhs_felts = get_felt_array_arg(lhs, lhs_len, vm, ids_data, ap_tracking)?;
+	let rhs_felts = get_felt_array_arg(rhs, rhs_len, vm, ids_data, ap_tracking)?;
+
+	if lhs_felts == rhs_felts {
+		return Ok(())
+	}
+	record_assertion_diff(
+		exec_scopes,
+		operands_diff(lhs, &lhs_felts, rhs, &rhs_felts, true),
+	);
+	Err(VirtualMachineError::CustomHint(format!(
+		"{ASSERTION_FAILED_PREFIX}: assert_array_eq({lhs}, {lhs_len}, {rhs}, {rhs_len})\n  {lhs}: \
+		 {} felts\n  {rhs}: {} felts",
+		lhs_felts.len(),
+		rhs_felts.len()
+	)))
+}
+
+/// Assert that a felt is strictly lower than another one
+///
+/// Felts are compared as integers in the range [0, P).
+/// Operands are names of felt variables, or integer literals.
+///
+/// Returns nothing if the assertion holds, an error showing both values otherwise
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_lower() {
+///     let x = 2;
+///     %{ assert_lt(x, 3) %}
+///     return ();
+/// }
+/// ```
+pub fn assert_lt(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	check_assertion(
+		"assert_lt",
+		|lhs, rhs| lhs < rhs,
+		false,
+		vm,
+		exec_scopes,
+		ids_data,
+		ap_tracking,
+		args,
+	)
+}
+
+/// Assert that a felt is lower than or equal to another one
+///
+/// Felts are compared as integers in the range [0, P).
+/// Operands are names of felt variables, or integer literals.
+///
+/// Returns nothing if the assertion holds, an error showing both values otherwise
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_lower_or_equal() {
+///     let x = 3;
+///     %{ assert_le(x, 3) %}
+///     return ();
+/// }
+/// ```
+pub fn assert_le(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	check_assertion(
+		"assert_le",
+		|lhs, rhs| lhs <= rhs,
+		false,
+		vm,
+		exec_scopes,
+		ids_data,
+		ap_tracking,
+		args,
+	)
+}
+
+/// Whether `x` is in the range [`lo`, `hi`) of felts, wrapping around P if `lo` is greater than
+/// `hi` (ie: [`lo`, P) followed by [0, `hi`))
+fn is_in_range(x: &BigInt, lo: &BigInt, hi: &BigInt) -> bool {
+	if lo <= hi {
+		lo <= x && x < hi
+	} else {
+		lo <= x || x < hi
+	}
+}
+
+/// Assert that a felt is in the range [lo, hi), lower bound included and upper bound excluded
+///
+/// Operands are names of felt variables, or integer literals. Felts being integers in the range
+/// [0, P), negative literals wrap around P (`-1` is `P - 1`, as it is in cairo), and a range whose
+/// lower bound is greater than its upper bound wraps around P as well: `assert_in_range(x, -5, 5)`
+/// holds for the felts between -5 and 4, and for no other. A range whose bounds are equal is
+/// empty.
+///
+/// Returns nothing if the assertion holds, an error showing the value and the range otherwise
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_digit() {
+///     let digit = 7;
+///     %{ assert_in_range(digit, 0, 10) %}
+///     return ();
+/// }
+/// ```
+pub fn assert_in_range(
+	vm: &mut VirtualMachine,
+	_exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let [x, lo, hi] = exact_args("assert_in_range", args)?;
+	let x_value = get_felt_arg(x, vm, ids_data, ap_tracking)?;
+	let lo_value = get_felt_arg(lo, vm, ids_data, ap_tracking)?;
+	let hi_value = get_felt_arg(hi, vm, ids_data, ap_tracking)?;
+
+	if is_in_range(&x_value, &lo_value, &hi_value) {
+		return Ok(())
+	}
+	let wrapping = if lo_value > hi_value {
+		" (wrapping around P)"
+	} else {
+		""
+	};
+	Err(VirtualMachineError::CustomHint(format!(
+		"{ASSERTION_FAILED_PREFIX}: assert_in_range({x}, {lo}, {hi})\n  {x}: {}\n  range: [{}, \
+		 {}){wrapping}",
+		format_felt(&x_value),
+		format_felt(&lo_value),
+		format_felt(&hi_value)
+	)))
+}
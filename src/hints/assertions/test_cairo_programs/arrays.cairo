@@ -0,0 +1,48 @@
+from starkware.cairo.common.alloc import alloc
+
+func test_assert_array_eq() {
+    alloc_locals;
+    let (local a: felt*) = alloc();
+    assert a[0] = 1;
+    assert a[1] = 2;
+    let (local b: felt*) = alloc();
+    assert b[0] = 1;
+    assert b[1] = 2;
+    %{ assert_array_eq(a, 2, b, 2) %}
+    return ();
+}
+
+func test_assert_array_eq_failing() {
+    alloc_locals;
+    let (local a: felt*) = alloc();
+    assert a[0] = 1;
+    assert a[1] = 2;
+    assert a[2] = 3;
+    let (local b: felt*) = alloc();
+    assert b[0] = 1;
+    assert b[1] = 5;
+    assert b[2] = 3;
+    %{ assert_array_eq(a, 3, b, 3) %}
+    return ();
+}
+
+func test_assert_array_eq_different_lengths() {
+    alloc_locals;
+    let (local a: felt*) = alloc();
+    assert a[0] = 1;
+    let (local b: felt*) = alloc();
+    assert b[0] = 1;
+    assert b[1] = 2;
+    %{ assert_array_eq(a, 1, b, 2) %}
+    return ();
+}
+
+func test_assert_array_eq_big_felts() {
+    alloc_locals;
+    let (local a: felt*) = alloc();
+    assert a[0] = 0x100000000000000000000000000000001;
+    let (local b: felt*) = alloc();
+    assert b[0] = 0x200000000000000000000000000000001;
+    %{ assert_array_eq(a, 1, b, 1) %}
+    return ();
+}
@@ -0,0 +1,85 @@
+func test_assert_eq() {
+    let a = 2 + 2;
+    let b = 4;
+    %{ assert_eq(a, b) %}
+    %{ assert_eq(a, 4) %}
+    return ();
+}
+
+func test_assert_eq_failing() {
+    let a = 2 + 2;
+    let b = 5;
+    %{ assert_eq(a, b) %}
+    return ();
+}
+
+func test_assert_eq_negative() {
+    let a = -1;
+    %{ assert_eq(a, -1) %}
+    %{ assert_eq(a, 0x800000000000011000000000000000000000000000000000000000000000000) %}
+    return ();
+}
+
+func test_assert_lt() {
+    let a = 2;
+    %{ assert_lt(a, 3) %}
+    return ();
+}
+
+func test_assert_lt_failing() {
+    let a = 3;
+    %{ assert_lt(a, 3) %}
+    return ();
+}
+
+func test_assert_le() {
+    let a = 3;
+    %{ assert_le(a, 3) %}
+    return ();
+}
+
+func test_assert_le_failing() {
+    let a = 3;
+    let b = 2;
+    %{ assert_le(a, b) %}
+    return ();
+}
+
+func test_assert_in_range() {
+    let a = 3;
+    %{ assert_in_range(a, 0, 10) %}
+    %{ assert_in_range(a, 3, 4) %}
+    return ();
+}
+
+func test_assert_in_range_upper_bound_failing() {
+    let a = 10;
+    %{ assert_in_range(a, 0, 10) %}
+    return ();
+}
+
+func test_assert_in_range_lower_bound_failing() {
+    let a = 2;
+    let lo = 3;
+    %{ assert_in_range(a, lo, 10) %}
+    return ();
+}
+
+func test_assert_in_range_wrapping() {
+    let a = -1;
+    %{ assert_in_range(a, -5, 5) %}
+    %{ assert_in_range(a, -1, 0) %}
+    return ();
+}
+
+func test_assert_in_range_wrapping_failing() {
+    let a = 5;
+    %{ assert_in_range(a, -5, 5) %}
+    return ();
+}
+
+func test_assert_in_range_empty_failing() {
+    let a = 3;
+    %{ assert_in_range(a, 3, 3) %}
+    return ();
+}
@@ -0,0 +1,170 @@
+use rstest::rstest;
+
+use num_bigint::BigInt;
+
+use super::{felt_lines, is_in_range, ASSERTION_FAILED_PREFIX};
+use crate::{
+	cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus},
+	fuzz::FIELD_PRIME,
+	hints::args::to_felt,
+};
+
+#[rstest]
+#[case("test_assert_eq", TestStatus::SUCCESS, None)]
+#[case(
+	"test_assert_eq_failing",
+	TestStatus::FAILURE,
+	Some("assert_eq(a, b)\n  a: 4\n  b: 5")
+)]
+#[case("test_assert_eq_negative", TestStatus::SUCCESS, None)]
+#[case("test_assert_lt", TestStatus::SUCCESS, None)]
+#[case(
+	"test_assert_lt_failing",
+	TestStatus::FAILURE,
+	Some("assert_lt(a, 3)\n  a: 3\n  3: 3")
+)]
+#[case("test_assert_le", TestStatus::SUCCESS, None)]
+#[case(
+	"test_assert_le_failing",
+	TestStatus::FAILURE,
+	Some("assert_le(a, b)\n  a: 3\n  b: 2")
+)]
+#[case("test_assert_in_range", TestStatus::SUCCESS, None)]
+#[case(
+	"test_assert_in_range_upper_bound_failing",
+	TestStatus::FAILURE,
+	Some("assert_in_range(a, 0, 10)\n  a: 10\n  range: [0, 10)")
+)]
+#[case(
+	"test_assert_in_range_lower_bound_failing",
+	TestStatus::FAILURE,
+	Some("assert_in_range(a, lo, 10)\n  a: 2\n  range: [3, 10)")
+)]
+#[case("test_assert_in_range_wrapping", TestStatus::SUCCESS, None)]
+#[case(
+	"test_assert_in_range_empty_failing",
+	TestStatus::FAILURE,
+	Some("assert_in_range(a, 3, 3)\n  a: 3\n  range: [3, 3)")
+)]
+fn assertions(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+	#[case] expected_message: Option<&str>,
+) -> Result<(), TestCommandError> {
+	let path =
+		std::path::PathBuf::from("src/hints/assertions/test_cairo_programs/assertions.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok");
+	assert_eq!(expected_success, result.success);
+	if let Some(expected_message) = expected_message {
+		assert_eq!(
+			Some(format!("{ASSERTION_FAILED_PREFIX}: {expected_message}")),
+			result.error
+		);
+	}
+	Ok(())
+}
+
+#[test]
+fn failed_assert_eq_reports_the_diff_of_its_operands() {
+	let path =
+		std::path::PathBuf::from("src/hints/assertions/test_cairo_programs/assertions.cairo");
+	let result = run_single_test("test_assert_eq_failing", &path, 1000000).unwrap();
+
+	assert_eq!(
+		Some("--- a\n+++ b\n@@ -1 +1 @@\n-4\n+5\n"),
+		result.diff.as_deref()
+	);
+
+	let result = run_single_test("test_assert_lt_failing", &path, 1000000).unwrap();
+	assert_eq!(None, result.diff);
+}
+
+#[rstest]
+#[case("test_assert_array_eq", TestStatus::SUCCESS, &[])]
+#[case(
+	"test_assert_array_eq_failing",
+	TestStatus::FAILURE,
+	&[" [0]: 1\n-[1]: 2\n+[1]: 5\n [2]: 3\n"]
+)]
+#[case(
+	"test_assert_array_eq_different_lengths",
+	TestStatus::FAILURE,
+	&[" [0]: 1\n+[1]: 2\n"]
+)]
+#[case(
+	"test_assert_array_eq_big_felts",
+	TestStatus::FAILURE,
+	&[" [0].low: 0x1\n-[0].high: 0x1\n+[0].high: 0x2\n"]
+)]
+fn array_assertions(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+	#[case] expected_diff_lines: &[&str],
+) {
+	let path = std::path::PathBuf::from("src/hints/assertions/test_cairo_programs/arrays.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok");
+
+	assert_eq!(expected_success, result.success);
+	if expected_success == TestStatus::SUCCESS {
+		assert_eq!(None, result.diff);
+		return
+	}
+	assert!(
+		result
+			.error
+			.as_deref()
+			.unwrap_or_default()
+			.starts_with(&format!("{ASSERTION_FAILED_PREFIX}: assert_array_eq(a, ")),
+		"{:?}",
+		result.error
+	);
+	let diff = result.diff.expect("a failed assertion should have a diff");
+	assert!(diff.starts_with("--- a\n+++ b\n"), "{diff}");
+	for lines in expected_diff_lines {
+		assert!(diff.contains(lines), "{diff}");
+	}
+}
+
+#[test]
+fn failed_wrapping_range_assertion_shows_the_felt_bounds() {
+	let path =
+		std::path::PathBuf::from("src/hints/assertions/test_cairo_programs/assertions.cairo");
+	let result = run_single_test("test_assert_in_range_wrapping_failing", &path, 1000000).unwrap();
+
+	assert_eq!(TestStatus::FAILURE, result.success);
+	assert_eq!(
+		Some(format!(
+			"{ASSERTION_FAILED_PREFIX}: assert_in_range(a, -5, 5)\n  a: 5\n  range: [{}, 5) \
+			 (wrapping around P)",
+			&*FIELD_PRIME - 5
+		)),
+		result.error
+	);
+}
+
+#[test]
+fn negative_integers_wrap_around_the_prime() {
+	assert_eq!(&*FIELD_PRIME - 1, to_felt(&BigInt::from(-1)));
+	assert_eq!(BigInt::from(0), to_felt(&FIELD_PRIME));
+	assert_eq!(BigInt::from(7), to_felt(&BigInt::from(7)));
+
+	let (lo, hi) = (to_felt(&BigInt::from(-5)), BigInt::from(5));
+	assert!(is_in_range(&to_felt(&BigInt::from(-5)), &lo, &hi));
+	assert!(is_in_range(&BigInt::from(0), &lo, &hi));
+	assert!(!is_in_range(&BigInt::from(5), &lo, &hi));
+	assert!(!is_in_range(&to_felt(&BigInt::from(-6)), &lo, &hi));
+}
+
+#[test]
+fn felts_wider_than_a_limb_are_split() {
+	let felt = (BigInt::from(3) << 128) + 7;
+
+	assert_eq!(
+		vec![String::from("[2]: 7")],
+		felt_lines("[2]", &BigInt::from(7))
+	);
+	assert_eq!(
+		vec![String::from("low: 0x7"), String::from("high: 0x3")],
+		felt_lines("", &felt)
+	);
+}
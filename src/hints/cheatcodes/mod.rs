@@ -0,0 +1,442 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+	collections::HashMap,
+	sync::{PoisonError, RwLock},
+};
+
+use cairo_rs::{
+	hint_processor::{
+		builtin_hint_processor::hint_utils::insert_value_from_var_name,
+		hint_processor_definition::HintReference,
+	},
+	serde::deserialize_program::ApTracking,
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use lazy_static::lazy_static;
+use num_bigint::BigInt;
+use uuid::Uuid;
+
+use super::{
+	args::{exact_args, get_felt_arg, no_args, single_arg},
+	events::Event,
+	output_buffer::get_execution_uuid,
+};
+
+/// Block context overridden by the cheatcodes of an execution.
+///
+/// There is no syscall handler in cairo-foundry: a program reads these values with the matching
+/// getter hints (eg: `get_block_timestamp`), which return the default value when not overridden.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Cheatcodes {
+	/// Set by `warp`
+	pub block_timestamp: Option<BigInt>,
+	/// Set by `roll`
+	pub block_number: Option<BigInt>,
+	/// Set by `prank`, until `stop_prank`
+	pub caller_address: Option<BigInt>,
+	/// Return values of the contract calls, by (contract address, selector).
+	/// Set by `mock_contract_call`, until `clear_mocks`
+	pub contract_mocks: HashMap<(BigInt, BigInt), BigInt>,
+	/// Recorded by `emit_event`
+	pub events: Vec<Event>,
+	/// Recorded by `expect_emit`, checked once the execution completes
+	pub expected_events: Vec<Event>,
+	/// Storage of the contracts, by (contract address, storage slot).
+	/// Preloaded with `--seed-storage`, written by `store` and read by `load`
+	pub storage: HashMap<(BigInt, BigInt), BigInt>,
+}
+
+lazy_static! {
+	/// the `CHEATCODES` hashmap stores the `Cheatcodes` of each execution, keyed by the Uuid of
+	/// the execution (see `output_buffer`). Entries are removed along with the output buffer.
+	static ref CHEATCODES: RwLock<HashMap<Uuid, Cheatcodes>> = RwLock::new(HashMap::new());
+}
+
+/// Returns the cheatcodes of an execution (the default ones if none has been used)
+pub fn get_cheatcodes(execution_uuid: &Uuid) -> Cheatcodes {
+	CHEATCODES
+		.read()
+		.unwrap_or_else(PoisonError::into_inner)
+		.get(execution_uuid)
+		.cloned()
+		.unwrap_or_default()
+}
+
+/// Apply `update` to the cheatcodes of an execution
+pub fn update_cheatcodes<T>(execution_uuid: Uuid, update: impl FnOnce(&mut Cheatcodes) -> T) -> T {
+	let mut cheatcodes = CHEATCODES.write().unwrap_or_else(PoisonError::into_inner);
+	update(cheatcodes.entry(execution_uuid).or_default())
+}
+
+/// Remove the cheatcodes of an execution
+pub fn clear_cheatcodes(execution_uuid: &Uuid) {
+	CHEATCODES
+		.write()
+		.unwrap_or_else(PoisonError::into_inner)
+		.remove(execution_uuid);
+}
+
+/// Set the block timestamp seen by the current test
+///
+/// The argument is a felt variable or an integer literal.
+/// The timestamp is read back with `get_block_timestamp`.
+///
+/// Returns nothing
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_warp() {
+///     alloc_locals;
+///     local timestamp;
+///     %{ warp(1000) %}
+///     %{ get_block_timestamp(timestamp) %}
+///     assert timestamp = 1000;
+///     return ();
+/// }
+/// ```
+pub fn warp(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let timestamp =
+		get_felt_arg(single_arg("warp", args)?, vm, ids_data, ap_tracking)?.into_owned();
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	update_cheatcodes(execution_uuid, |cheatcodes| {
+		cheatcodes.block_timestamp = Some(timestamp)
+	});
+	Ok(())
+}
+
+/// Write the block timestamp into a felt variable
+///
+/// The timestamp is the one set by the last `warp` of the test, 0 if none.
+///
+/// Returns nothing
+pub fn get_block_timestamp(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let var_name = single_arg("get_block_timestamp", args)?;
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	let timestamp = get_cheatcodes(&execution_uuid).block_timestamp.unwrap_or_default();
+	insert_value_from_var_name(var_name, timestamp, vm, ids_data, ap_tracking)
+}
+
+/// Set the block number seen by the current test
+///
+/// The argument is a felt variable or an integer literal, the last `roll` of a test wins.
+/// The block number is read back with `get_block_number`.
+///
+/// Returns nothing
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_roll() {
+///     alloc_locals;
+///     local block_number;
+///     %{ roll(42) %}
+///     %{ get_block_number(block_number) %}
+///     assert block_number = 42;
+///     return ();
+/// }
+/// ```
+pub fn roll(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let block_number =
+		get_felt_arg(single_arg("roll", args)?, vm, ids_data, ap_tracking)?.into_owned();
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	update_cheatcodes(execution_uuid, |cheatcodes| {
+		cheatcodes.block_number = Some(block_number)
+	});
+	Ok(())
+}
+
+/// Write the block number into a felt variable
+///
+/// The block number is the one set by the last `roll` of the test, 0 if none.
+///
+/// Returns nothing
+pub fn get_block_number(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let var_name = single_arg("get_block_number", args)?;
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	let block_number = get_cheatcodes(&execution_uuid).block_number.unwrap_or_default();
+	insert_value_from_var_name(var_name, block_number, vm, ids_data, ap_tracking)
+}
+
+/// Spoof the caller address seen by the current test, until `stop_prank` is called
+///
+/// The argument is a felt variable or an integer literal.
+/// The caller address is read back with `get_caller_address`.
+/// Pranks cannot be nested: `stop_prank` must be called before starting another one.
+///
+/// Returns nothing, or an error if a prank is already active
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_only_owner() {
+///     %{ prank(0x123) %}
+///     only_owner();
+///     %{ stop_prank() %}
+///     return ();
+/// }
+/// ```
+pub fn prank(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let caller_address =
+		get_felt_arg(single_arg("prank", args)?, vm, ids_data, ap_tracking)?.into_owned();
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	update_cheatcodes(execution_uuid, |cheatcodes| {
+		match &cheatcodes.caller_address {
+			Some(active) => Err(VirtualMachineError::CustomHint(format!(
+				"prank({caller_address}) called while prank({active}) is active, call stop_prank() \
+				 first"
+			))),
+			None => {
+				cheatcodes.caller_address = Some(caller_address);
+				Ok(())
+			},
+		}
+	})
+}
+
+/// Stop the active prank, the caller address goes back to its default value
+///
+/// Returns nothing, or an error if no prank is active
+pub fn stop_prank(
+	_vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	_ids_data: &HashMap<String, HintReference>,
+	_ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	no_args("stop_prank", args)?;
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	update_cheatcodes(execution_uuid, |cheatcodes| {
+		match cheatcodes.caller_address.take() {
+			Some(_) => Ok(()),
+			None => Err(VirtualMachineError::CustomHint(String::from(
+				"stop_prank() called while no prank is active",
+			))),
+		}
+	})
+}
+
+/// Write the caller address into a felt variable
+///
+/// The caller address is the one set by the active `prank`, 0 if none.
+///
+/// Returns nothing
+pub fn get_caller_address(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let var_name = single_arg("get_caller_address", args)?;
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	let caller_address = get_cheatcodes(&execution_uuid).caller_address.unwrap_or_default();
+	insert_value_from_var_name(var_name, caller_address, vm, ids_data, ap_tracking)
+}
+
+/// Mock the calls to a function of a contract, for the rest of the current test
+///
+/// The arguments are the contract address, the selector of the function and the returned value,
+/// each one a felt variable or an integer literal. Mocking the same call again overrides its
+/// returned value.
+/// A call is made with `call_contract`, there is no contract to dispatch it to: calls which are
+/// not mocked fail.
+///
+/// Returns nothing
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_balance() {
+///     alloc_locals;
+///     local balance;
+///     %{ mock_contract_call(0x456, 0x2e4263afad30923c891518314c3c95dbe830a16874e8abc5777a9a20b54c76e, 100) %}
+///     %{ call_contract(0x456, 0x2e4263afad30923c891518314c3c95dbe830a16874e8abc5777a9a20b54c76e, balance) %}
+///     assert balance = 100;
+///     return ();
+/// }
+/// ```
+pub fn mock_contract_call(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let [address, selector, return_value] = exact_args("mock_contract_call", args)?;
+	let address = get_felt_arg(address, vm, ids_data, ap_tracking)?.into_owned();
+	let selector = get_felt_arg(selector, vm, ids_data, ap_tracking)?.into_owned();
+	let return_value = get_felt_arg(return_value, vm, ids_data, ap_tracking)?.into_owned();
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	update_cheatcodes(execution_uuid, |cheatcodes| {
+		cheatcodes.contract_mocks.insert((address, selector), return_value)
+	});
+	Ok(())
+}
+
+/// Call a function of a contract, writing its returned value into a felt variable
+///
+/// The arguments are the contract address, the selector of the function (felt variables or
+/// integer literals) and the felt variable receiving the returned value.
+///
+/// Returns nothing, or an error if the call has not been mocked with `mock_contract_call`
+pub fn call_contract(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let [address, selector, var_name] = exact_args("call_contract", args)?;
+	let address = get_felt_arg(address, vm, ids_data, ap_tracking)?.into_owned();
+	let selector = get_felt_arg(selector, vm, ids_data, ap_tracking)?.into_owned();
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	let call = (address, selector);
+	match get_cheatcodes(&execution_uuid).contract_mocks.get(&call).cloned() {
+		Some(return_value) =>
+			insert_value_from_var_name(var_name, return_value, vm, ids_data, ap_tracking),
+		None => Err(VirtualMachineError::CustomHint(format!(
+			"call_contract({:#x}, {:#x}) is not mocked, use mock_contract_call",
+			call.0, call.1
+		))),
+	}
+}
+
+/// Write a value to the storage of a contract, for the rest of the current test
+///
+/// The arguments are the contract address, the storage slot and the value, each one a felt
+/// variable or an integer literal. The value is read back with `load`.
+/// There is no syscall handler in cairo-foundry: the storage reads and writes of the tested code
+/// go through `load` and `store`, which play the part of the `storage_read` and `storage_write`
+/// syscalls.
+///
+/// Returns nothing
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_store() {
+///     alloc_locals;
+///     local value;
+///     %{ store(0x123, 0x1, 42) %}
+///     %{ load(0x123, 0x1, value) %}
+///     assert value = 42;
+///     return ();
+/// }
+/// ```
+pub fn store(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let [address, slot, value] = exact_args("store", args)?;
+	let address = get_felt_arg(address, vm, ids_data, ap_tracking)?.into_owned();
+	let slot = get_felt_arg(slot, vm, ids_data, ap_tracking)?.into_owned();
+	let value = get_felt_arg(value, vm, ids_data, ap_tracking)?.into_owned();
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	update_cheatcodes(execution_uuid, |cheatcodes| {
+		cheatcodes.storage.insert((address, slot), value)
+	});
+	Ok(())
+}
+
+/// Read a storage slot of a contract into a felt variable
+///
+/// The arguments are the contract address, the storage slot (felt variables or integer literals)
+/// and the felt variable receiving the value: the last one written by `store` (or preloaded with
+/// `--seed-storage`), 0 if the slot has never been written, as in an empty contract storage.
+///
+/// Returns nothing
+pub fn load(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let [address, slot, var_name] = exact_args("load", args)?;
+	let address = get_felt_arg(address, vm, ids_data, ap_tracking)?.into_owned();
+	let slot = get_felt_arg(slot, vm, ids_data, ap_tracking)?.into_owned();
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	let value = get_cheatcodes(&execution_uuid)
+		.storage
+		.get(&(address, slot))
+		.cloned()
+		.unwrap_or_default();
+	insert_value_from_var_name(var_name, value, vm, ids_data, ap_tracking)
+}
+
+/// Remove every mock set by `mock_contract_call` in the current test
+///
+/// Returns nothing
+pub fn clear_mocks(
+	_vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	_ids_data: &HashMap<String, HintReference>,
+	_ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	no_args("clear_mocks", args)?;
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	update_cheatcodes(execution_uuid, |cheatcodes| {
+		cheatcodes.contract_mocks.clear()
+	});
+	Ok(())
+}
@@ -0,0 +1,176 @@
+const OWNER = 0x123;
+const TOKEN = 0x456;
+// Selector of `balanceOf`
+const BALANCE_OF = 0x2e4263afad30923c891518314c3c95dbe830a16874e8abc5777a9a20b54c76e;
+
+func only_owner() {
+    alloc_locals;
+    local caller;
+    %{ get_caller_address(caller) %}
+    assert caller = OWNER;
+    return ();
+}
+
+func token_balance() -> felt {
+    alloc_locals;
+    local balance;
+    let token = TOKEN;
+    let selector = BALANCE_OF;
+    %{ call_contract(token, selector, balance) %}
+    return balance;
+}
+
+func test_warp() {
+    alloc_locals;
+    local timestamp;
+    %{ warp(1000) %}
+    %{ get_block_timestamp(timestamp) %}
+    assert timestamp = 1000;
+    return ();
+}
+
+func test_warp_twice() {
+    alloc_locals;
+    local timestamp;
+    let new_timestamp = 2000;
+    %{ warp(1000) %}
+    %{ warp(new_timestamp) %}
+    %{ get_block_timestamp(timestamp) %}
+    assert timestamp = 2000;
+    return ();
+}
+
+func test_block_timestamp_defaults_to_zero() {
+    alloc_locals;
+    local timestamp;
+    %{ get_block_timestamp(timestamp) %}
+    assert timestamp = 0;
+    return ();
+}
+
+func test_roll() {
+    alloc_locals;
+    local block_number;
+    %{ roll(42) %}
+    %{ get_block_number(block_number) %}
+    assert block_number = 42;
+    return ();
+}
+
+func test_roll_twice() {
+    alloc_locals;
+    local block_number;
+    %{ roll(42) %}
+    %{ roll(43) %}
+    %{ get_block_number(block_number) %}
+    assert block_number = 43;
+    return ();
+}
+
+func test_block_number_defaults_to_zero() {
+    alloc_locals;
+    local block_number;
+    %{ get_block_number(block_number) %}
+    assert block_number = 0;
+    return ();
+}
+
+func test_pranked_call() {
+    %{ prank(0x123) %}
+    only_owner();
+    %{ stop_prank() %}
+    return ();
+}
+
+func test_unpranked_call() {
+    %{ expect_revert() %}
+    only_owner();
+    return ();
+}
+
+func test_stopped_prank() {
+    %{ prank(0x123) %}
+    %{ stop_prank() %}
+    %{ expect_revert() %}
+    only_owner();
+    return ();
+}
+
+func test_nested_prank() {
+    %{ prank(0x123) %}
+    %{ prank(0x456) %}
+    return ();
+}
+
+func test_stop_prank_without_prank() {
+    %{ stop_prank() %}
+    return ();
+}
+
+func test_store_without_value() {
+    %{ store(0x123, 0x1) %}
+    return ();
+}
+
+func test_mocked_contract_call() {
+    %{ mock_contract_call(0x456, 0x2e4263afad30923c891518314c3c95dbe830a16874e8abc5777a9a20b54c76e, 100) %}
+    let balance = token_balance();
+    assert balance = 100;
+    return ();
+}
+
+func test_remocked_contract_call() {
+    %{ mock_contract_call(0x456, 0x2e4263afad30923c891518314c3c95dbe830a16874e8abc5777a9a20b54c76e, 100) %}
+    %{ mock_contract_call(0x456, 0x2e4263afad30923c891518314c3c95dbe830a16874e8abc5777a9a20b54c76e, 200) %}
+    let balance = token_balance();
+    assert balance = 200;
+    return ();
+}
+
+func test_unmocked_contract_call() {
+    token_balance();
+    return ();
+}
+
+func test_cleared_mocks() {
+    %{ mock_contract_call(0x456, 0x2e4263afad30923c891518314c3c95dbe830a16874e8abc5777a9a20b54c76e, 100) %}
+    %{ clear_mocks() %}
+    token_balance();
+    return ();
+}
+
+func balance_of(account) -> felt {
+    alloc_locals;
+    local balance;
+    %{ load(0x789, account, balance) %}
+    return balance;
+}
+
+func test_store_then_load() {
+    %{ store(0x789, 1, 42) %}
+    let balance = balance_of(1);
+    assert balance = 42;
+    return ();
+}
+
+func test_store_twice() {
+    %{ store(0x789, 1, 42) %}
+    %{ store(0x789, 1, 7) %}
+    let balance = balance_of(1);
+    assert balance = 7;
+    return ();
+}
+
+func test_storage_defaults_to_zero() {
+    %{ store(0x789, 1, 42) %}
+    let balance = balance_of(2);
+    assert balance = 0;
+    return ();
+}
+
+// Only passes when run with the storage seeded by `--seed-storage`
+func test_load_seeded_storage() {
+    let balance = balance_of(1);
+    assert balance = 42;
+    return ();
+}
@@ -0,0 +1,84 @@
+use rstest::rstest;
+
+use crate::cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus};
+
+// Each test is a separate execution, the cheatcodes of a test do not leak into the next one
+#[rstest]
+#[case("test_warp")]
+#[case("test_warp_twice")]
+#[case("test_block_timestamp_defaults_to_zero")]
+#[case("test_roll")]
+#[case("test_roll_twice")]
+#[case("test_block_number_defaults_to_zero")]
+#[case("test_pranked_call")]
+#[case("test_unpranked_call")]
+#[case("test_stopped_prank")]
+#[case("test_mocked_contract_call")]
+#[case("test_remocked_contract_call")]
+#[case("test_store_then_load")]
+#[case("test_store_twice")]
+#[case("test_storage_defaults_to_zero")]
+fn cheatcodes(#[case] test_name: &str) -> Result<(), TestCommandError> {
+	let path =
+		std::path::PathBuf::from("src/hints/cheatcodes/test_cairo_programs/cheatcodes.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok");
+	assert_eq!(TestStatus::SUCCESS, result.success, "{:?}", result.error);
+	Ok(())
+}
+
+#[test]
+fn cheatcodes_are_reset_between_tests() -> Result<(), TestCommandError> {
+	let path =
+		std::path::PathBuf::from("src/hints/cheatcodes/test_cairo_programs/cheatcodes.cairo");
+	for test_name in ["test_roll", "test_block_number_defaults_to_zero"] {
+		let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok");
+		assert_eq!(TestStatus::SUCCESS, result.success, "{:?}", result.error);
+	}
+	Ok(())
+}
+
+#[rstest]
+#[case(
+	"test_nested_prank",
+	"prank(1110) called while prank(291) is active, call stop_prank() first"
+)]
+#[case(
+	"test_stop_prank_without_prank",
+	"stop_prank() called while no prank is active"
+)]
+fn misused_prank_fails(
+	#[case] test_name: &str,
+	#[case] expected_error: &str,
+) -> Result<(), TestCommandError> {
+	let path =
+		std::path::PathBuf::from("src/hints/cheatcodes/test_cairo_programs/cheatcodes.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok");
+	assert_eq!(TestStatus::FAILURE, result.success);
+	assert!(result.error.unwrap().contains(expected_error));
+	Ok(())
+}
+
+#[test]
+fn cheatcode_with_missing_argument_fails() -> Result<(), TestCommandError> {
+	let path =
+		std::path::PathBuf::from("src/hints/cheatcodes/test_cairo_programs/cheatcodes.cairo");
+	let result = run_single_test("test_store_without_value", &path, 1000000).expect("Should be Ok");
+	assert_eq!(TestStatus::FAILURE, result.success);
+	assert!(result.error.unwrap().contains("store expects 3 arguments, got 2"));
+	Ok(())
+}
+
+#[rstest]
+#[case("test_unmocked_contract_call")]
+#[case("test_cleared_mocks")]
+fn unmocked_contract_call_fails(#[case] test_name: &str) -> Result<(), TestCommandError> {
+	let path =
+		std::path::PathBuf::from("src/hints/cheatcodes/test_cairo_programs/cheatcodes.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok");
+	assert_eq!(TestStatus::FAILURE, result.success);
+	assert!(result.error.unwrap().contains(
+		"call_contract(0x456, \
+		 0x2e4263afad30923c891518314c3c95dbe830a16874e8abc5777a9a20b54c76e) is not mocked"
+	));
+	Ok(())
+}
@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+	collections::{BTreeMap, HashMap},
+	sync::{Arc, PoisonError, RwLock},
+};
+
+use cairo_rs::{
+	hint_processor::hint_processor_definition::HintReference,
+	serde::deserialize_program::ApTracking,
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use lazy_static::lazy_static;
+use num_bigint::BigInt;
+
+/// A hint provided by the application embedding cairo-foundry, called from cairo like the
+/// built-in hints: `%{ name(arg1, arg2) %}`.
+///
+/// `execute` is given the same values as the built-in hints (and as the hints of cairo-rs's
+/// `BuiltinHintProcessor`): the `ids_data` and `ap_tracking` of the hint to resolve the cairo
+/// variables (eg: with `cairo_rs::hint_processor::builtin_hint_processor::hint_utils`), the
+/// constants of the program, and the arguments written between the parentheses, trimmed but not
+/// parsed. Returning an error fails the test, with the error as reason.
+///
+/// Hints are shared by the threads running the tests, any state they keep has to be synchronized.
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use cairo_rs::{
+/// # 	hint_processor::{
+/// # 		builtin_hint_processor::hint_utils::get_integer_from_var_name,
+/// # 		hint_processor_definition::HintReference,
+/// # 	},
+/// # 	serde::deserialize_program::ApTracking,
+/// # 	types::exec_scope::ExecutionScopes,
+/// # 	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+/// # };
+/// # use num_bigint::BigInt;
+/// use cairo_foundry::hints::{register_hint, CustomHint};
+///
+/// /// `%{ assert_even(x) %}` fails if the variable `x` is odd
+/// struct AssertEven;
+///
+/// impl CustomHint for AssertEven {
+/// 	fn name(&self) -> &str {
+/// 		"assert_even"
+/// 	}
+///
+/// 	fn execute(
+/// 		&self,
+/// 		vm: &mut VirtualMachine,
+/// 		_exec_scopes: &mut ExecutionScopes,
+/// 		ids_data: &HashMap<String, HintReference>,
+/// 		ap_tracking: &ApTracking,
+/// 		_constants: &HashMap<String, BigInt>,
+/// 		args: &[String],
+/// 	) -> Result<(), VirtualMachineError> {
+/// 		let value = get_integer_from_var_name(&args[0], vm, ids_data, ap_tracking)?;
+/// 		if value.as_ref() % 2u8 != BigInt::from(0) {
+/// 			return Err(VirtualMachineError::CustomHint(format!("{value} is odd")))
+/// 		}
+/// 		Ok(())
+/// 	}
+/// }
+///
+/// register_hint(AssertEven);
+/// ```
+pub trait CustomHint: Send + Sync {
+	/// Name the hint is called by in the cairo code
+	fn name(&self) -> &str;
+
+	#[allow(clippy::too_many_arguments)]
+	fn execute(
+		&self,
+		vm: &mut VirtualMachine,
+		exec_scopes: &mut ExecutionScopes,
+		ids_data: &HashMap<String, HintReference>,
+		ap_tracking: &ApTracking,
+		constants: &HashMap<String, BigInt>,
+		args: &[String],
+	) -> Result<(), VirtualMachineError>;
+}
+
+lazy_static! {
+	/// the `CUSTOM_HINTS` map stores the hints registered with `register_hint`, keyed by name.
+	/// They are added to every hint processor created by `setup_hint_processor`.
+	static ref CUSTOM_HINTS: RwLock<BTreeMap<String, Arc<dyn CustomHint>>> =
+		RwLock::new(BTreeMap::new());
+}
+
+/// Register a hint, so that the tests run afterwards can call it.
+///
+/// A hint replaces the one previously registered with the same name, and takes precedence over a
+/// built-in hint of the same name.
+pub fn register_hint(hint: impl CustomHint + 'static) {
+	CUSTOM_HINTS
+		.write()
+		.unwrap_or_else(PoisonError::into_inner)
+		.insert(hint.name().to_string(), Arc::new(hint));
+}
+
+/// The hints registered with `register_hint`, sorted by name
+pub(crate) fn registered_hints() -> Vec<Arc<dyn CustomHint>> {
+	CUSTOM_HINTS
+		.read()
+		.unwrap_or_else(PoisonError::into_inner)
+		.values()
+		.cloned()
+		.collect()
+}
@@ -0,0 +1,11 @@
+func test_custom_hint_with_even_value() {
+    let x = 42;
+    %{ test_assert_even(x) %}
+    return ();
+}
+
+func test_custom_hint_with_odd_value() {
+    let x = 7;
+    %{ test_assert_even(x) %}
+    return ();
+}
@@ -0,0 +1,64 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use cairo_rs::{
+	hint_processor::{
+		builtin_hint_processor::hint_utils::get_integer_from_var_name,
+		hint_processor_definition::HintReference,
+	},
+	serde::deserialize_program::ApTracking,
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+use rstest::rstest;
+
+use crate::cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus};
+
+use super::{register_hint, CustomHint};
+
+struct AssertEven;
+
+impl CustomHint for AssertEven {
+	fn name(&self) -> &str {
+		// Prefixed so that it cannot clash with the hints of the other tests, registered globally
+		"test_assert_even"
+	}
+
+	fn execute(
+		&self,
+		vm: &mut VirtualMachine,
+		_exec_scopes: &mut ExecutionScopes,
+		ids_data: &HashMap<String, HintReference>,
+		ap_tracking: &ApTracking,
+		_constants: &HashMap<String, BigInt>,
+		args: &[String],
+	) -> Result<(), VirtualMachineError> {
+		let value = get_integer_from_var_name(&args[0], vm, ids_data, ap_tracking)?;
+		if value.as_ref() % 2u8 != BigInt::from(0) {
+			return Err(VirtualMachineError::CustomHint(format!("{value} is odd")))
+		}
+		Ok(())
+	}
+}
+
+#[rstest]
+#[case("test_custom_hint_with_even_value", TestStatus::SUCCESS, None)]
+#[case(
+	"test_custom_hint_with_odd_value",
+	TestStatus::FAILURE,
+	Some("7 is odd")
+)]
+fn registered_hint_is_called_from_cairo(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+	#[case] expected_error: Option<&str>,
+) -> Result<(), TestCommandError> {
+	register_hint(AssertEven);
+	let path = PathBuf::from("src/hints/custom/test_cairo_programs/custom_hint.cairo");
+	let result = run_single_test(test_name, &path, 1000000)?;
+	assert_eq!(expected_success, result.success, "{:?}", result.error);
+	if let Some(expected_error) = expected_error {
+		assert!(result.error.unwrap().contains(expected_error));
+	}
+	Ok(())
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests;
+
+use std::{collections::HashMap, env};
+
+use cairo_rs::{
+	hint_processor::{
+		builtin_hint_processor::hint_utils::insert_value_from_var_name,
+		hint_processor_definition::HintReference,
+	},
+	serde::deserialize_program::ApTracking,
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+
+use super::args::exact_args;
+
+/// Prefix of the environment variables readable by `env_var`, so that a test cannot read the
+/// secrets of the environment it runs in
+pub const ENV_VAR_PREFIX: &str = "CAIRO_FOUNDRY_";
+
+/// Maximum length of a short string, so that it fits in a felt
+const SHORT_STRING_MAX_LENGTH: usize = 31;
+
+/// Encode an ascii string as a cairo short string (ie: `'hello'`)
+fn encode_short_string(value: &str) -> Option<BigInt> {
+	if !value.is_ascii() || value.len() > SHORT_STRING_MAX_LENGTH {
+		return None
+	}
+	Some(BigInt::from_bytes_be(
+		num_bigint::Sign::Plus,
+		value.as_bytes(),
+	))
+}
+
+/// Write the value of an environment variable into a felt variable, as a short string
+///
+/// The first argument is the name of the environment variable (which must start with
+/// `CAIRO_FOUNDRY_`), the second one the felt variable.
+///
+/// Returns nothing, or an error if the environment variable is not set, or if its value is not
+/// an ascii string of at most 31 characters
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_network() {
+///     alloc_locals;
+///     local network;
+///     %{ env_var("CAIRO_FOUNDRY_NETWORK", network) %}
+///     assert network = 'testnet';
+///     return ();
+/// }
+/// ```
+pub fn env_var(
+	vm: &mut VirtualMachine,
+	_exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let [name, var_name] = exact_args("env_var", args)?;
+	let name = name.trim_matches('"');
+	if !name.starts_with(ENV_VAR_PREFIX) {
+		return Err(VirtualMachineError::CustomHint(format!(
+			"env_var can only read variables starting with {ENV_VAR_PREFIX}, got '{name}'"
+		)))
+	}
+	let value = env::var(name).map_err(|_| {
+		VirtualMachineError::CustomHint(format!("environment variable '{name}' is not set"))
+	})?;
+	let felt = encode_short_string(&value).ok_or_else(|| {
+		VirtualMachineError::CustomHint(format!(
+			"environment variable '{name}' is not an ascii string of at most \
+			 {SHORT_STRING_MAX_LENGTH} characters"
+		))
+	})?;
+	insert_value_from_var_name(var_name, felt, vm, ids_data, ap_tracking)
+}
@@ -0,0 +1,21 @@
+func test_read_env_var() {
+    alloc_locals;
+    local value;
+    %{ env_var("CAIRO_FOUNDRY_TEST_ENV_VAR", value) %}
+    assert value = 'hello';
+    return ();
+}
+
+func test_unset_env_var() {
+    alloc_locals;
+    local value;
+    %{ env_var("CAIRO_FOUNDRY_TEST_UNSET_ENV_VAR", value) %}
+    return ();
+}
+
+func test_env_var_without_prefix() {
+    alloc_locals;
+    local value;
+    %{ env_var("HOME", value) %}
+    return ();
+}
@@ -0,0 +1,40 @@
+use num_bigint::BigInt;
+use rstest::rstest;
+
+use crate::cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus};
+
+use super::encode_short_string;
+
+#[test]
+fn short_strings_are_encoded_big_endian() {
+	assert_eq!(Some(BigInt::from(0x6869)), encode_short_string("hi"));
+	assert_eq!(None, encode_short_string("é"));
+	assert_eq!(None, encode_short_string(&"a".repeat(32)));
+}
+
+#[rstest]
+#[case("test_read_env_var", TestStatus::SUCCESS, None)]
+#[case(
+	"test_unset_env_var",
+	TestStatus::FAILURE,
+	Some("environment variable 'CAIRO_FOUNDRY_TEST_UNSET_ENV_VAR' is not set")
+)]
+#[case(
+	"test_env_var_without_prefix",
+	TestStatus::FAILURE,
+	Some("env_var can only read variables starting with CAIRO_FOUNDRY_, got 'HOME'")
+)]
+fn env_var(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+	#[case] expected_error: Option<&str>,
+) -> Result<(), TestCommandError> {
+	std::env::set_var("CAIRO_FOUNDRY_TEST_ENV_VAR", "hello");
+	let path = std::path::PathBuf::from("src/hints/env_var/test_cairo_programs/env_var.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok");
+	assert_eq!(expected_success, result.success, "{:?}", result.error);
+	if let Some(expected_error) = expected_error {
+		assert!(result.error.unwrap().contains(expected_error));
+	}
+	Ok(())
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests;
+
+use std::{collections::HashMap, fmt::Display};
+
+use cairo_rs::{
+	hint_processor::hint_processor_definition::HintReference,
+	serde::deserialize_program::ApTracking,
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+use uuid::Uuid;
+
+use super::{
+	args::get_felt_arg,
+	cheatcodes::{get_cheatcodes, update_cheatcodes},
+	output_buffer::get_execution_uuid,
+};
+
+/// An event, emitted with `emit_event` or expected with `expect_emit`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+	pub key: BigInt,
+	pub data: Vec<BigInt>,
+}
+
+impl Display for Event {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let data: Vec<String> = self.data.iter().map(|felt| format!("{felt:#x}")).collect();
+		write!(f, "{:#x}({})", self.key, data.join(", "))
+	}
+}
+
+/// Parse the arguments of an event hint: the key, followed by the data
+fn event_arg(
+	hint: &str,
+	args: &[String],
+	vm: &VirtualMachine,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Event, VirtualMachineError> {
+	let mut felts = args
+		.iter()
+		.filter(|arg| !arg.is_empty())
+		.map(|arg| get_felt_arg(arg, vm, ids_data, ap_tracking).map(|felt| felt.into_owned()));
+	let key = felts.next().ok_or_else(|| {
+		VirtualMachineError::CustomHint(format!("{hint} expects at least 1 argument, got 0"))
+	})??;
+	let data = felts.collect::<Result<_, _>>()?;
+	Ok(Event { key, data })
+}
+
+/// Emit an event, recorded for the rest of the current test
+///
+/// The first argument is the key of the event, the next ones its data, each one a felt variable
+/// or an integer literal.
+///
+/// Returns nothing
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func transfer(to: felt, amount: felt) {
+///     %{ emit_event(0x99, to, amount) %}
+///     return ();
+/// }
+/// ```
+pub fn emit_event(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let event = event_arg("emit_event", args, vm, ids_data, ap_tracking)?;
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	update_cheatcodes(execution_uuid, |cheatcodes| cheatcodes.events.push(event));
+	Ok(())
+}
+
+/// Expects an event to be emitted by the current test
+///
+/// The arguments are the key and the data of the event, as in `emit_event`. The event can be
+/// emitted before or after the hint: if the test completes without having emitted it, it fails.
+///
+/// Returns nothing
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_transfer() {
+///     %{ expect_emit(0x99, 0x123, 100) %}
+///     transfer(0x123, 100);
+///     return ();
+/// }
+/// ```
+pub fn expect_emit(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let event = event_arg("expect_emit", args, vm, ids_data, ap_tracking)?;
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	update_cheatcodes(execution_uuid, |cheatcodes| {
+		cheatcodes.expected_events.push(event)
+	});
+	Ok(())
+}
+
+/// Check that every event expected by an execution has been emitted.
+///
+/// Returns a message describing the first event which has not been emitted (along with the
+/// emitted ones) otherwise.
+pub fn check_expected_events(execution_uuid: &Uuid) -> Result<(), String> {
+	let cheatcodes = get_cheatcodes(execution_uuid);
+	match cheatcodes
+		.expected_events
+		.iter()
+		.find(|event| !cheatcodes.events.contains(event))
+	{
+		Some(missing) => {
+			let emitted: Vec<String> = cheatcodes.events.iter().map(Event::to_string).collect();
+			Err(format!(
+				"expected event {missing} was not emitted, emitted events: [{}]",
+				emitted.join(", ")
+			))
+		},
+		None => Ok(()),
+	}
+}
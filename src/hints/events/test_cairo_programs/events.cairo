@@ -0,0 +1,30 @@
+const TRANSFER = 0x99;
+
+func transfer(to: felt, amount: felt) {
+    let key = TRANSFER;
+    %{ emit_event(key, to, amount) %}
+    return ();
+}
+
+func test_expected_event() {
+    %{ expect_emit(0x99, 0x123, 100) %}
+    transfer(0x123, 100);
+    return ();
+}
+
+func test_event_expected_after_emission() {
+    transfer(0x123, 100);
+    %{ expect_emit(0x99, 0x123, 100) %}
+    return ();
+}
+
+func test_unexpected_data() {
+    %{ expect_emit(0x99, 0x123, 200) %}
+    transfer(0x123, 100);
+    return ();
+}
+
+func test_missing_event() {
+    %{ expect_emit(0x99, 0x123, 100) %}
+    return ();
+}
@@ -0,0 +1,33 @@
+use rstest::rstest;
+
+use crate::cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus};
+
+#[rstest]
+#[case("test_expected_event")]
+#[case("test_event_expected_after_emission")]
+fn expected_event_is_emitted(#[case] test_name: &str) -> Result<(), TestCommandError> {
+	let path = std::path::PathBuf::from("src/hints/events/test_cairo_programs/events.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok");
+	assert_eq!(TestStatus::SUCCESS, result.success, "{:?}", result.error);
+	Ok(())
+}
+
+#[rstest]
+#[case(
+	"test_unexpected_data",
+	"expected event 0x99(0x123, 0xc8) was not emitted, emitted events: [0x99(0x123, 0x64)]"
+)]
+#[case(
+	"test_missing_event",
+	"expected event 0x99(0x123, 0x64) was not emitted, emitted events: []"
+)]
+fn missing_event_fails(
+	#[case] test_name: &str,
+	#[case] expected_error: &str,
+) -> Result<(), TestCommandError> {
+	let path = std::path::PathBuf::from("src/hints/events/test_cairo_programs/events.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok");
+	assert_eq!(TestStatus::FAILURE, result.success);
+	assert_eq!(Some(String::from(expected_error)), result.error);
+	Ok(())
+}
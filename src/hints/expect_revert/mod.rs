@@ -1,21 +1,97 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use cairo_rs::{
 	hint_processor::hint_processor_definition::HintReference,
 	serde::deserialize_program::ApTracking,
-	types::exec_scope::ExecutionScopes,
+	types::{exec_scope::ExecutionScopes, program::Program, relocatable::Relocatable},
 	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
 };
 use num_bigint::BigInt;
 
+use super::args::{get_felt_arg, no_args};
+
 #[cfg(test)]
 mod tests;
 
 pub const EXPECT_REVERT_FLAG: &str = "expect_revert";
+pub const EXPECT_REVERT_MISMATCH_PREFIX: &str = "execution reverted with an unexpected error";
+pub const EXPECT_REVERT_REGION_NOT_REVERTED: &str =
+	"execution did not revert between start_expect_revert() and stop_expect_revert()";
+pub const PANIC_PREFIX: &str = "panicked with";
+/// Name of the execution scope variable holding the data of the last `panic`
+pub const PANIC_DATA_VAR_NAME: &str = "panic_data";
+
+/// Revert expected by a test, stored in the execution scopes by the `expect_revert` hint
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExpectedRevert {
+	/// A substring the revert error message must contain, if any
+	pub message: Option<String>,
+	/// The data the execution must panic with (its selector first), if any
+	pub panic_data: Option<Vec<BigInt>>,
+	/// The region opened by `start_expect_revert` the revert must happen in, if the revert is not
+	/// expected anywhere in the test
+	pub region: Option<RevertRegion>,
+}
+
+/// Region opened by `start_expect_revert`, with the registers at its start so that the execution
+/// can resume at its end once it reverted
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RevertRegion {
+	/// Offset of the pc of the `start_expect_revert` hint
+	pub start_pc: usize,
+	/// Offset of ap at the start of the region
+	pub ap: usize,
+	/// Offset of fp at the start of the region
+	pub fp: usize,
+	/// Tracking of ap at the start of the region, to compute ap at its end
+	pub ap_tracking: ApTracking,
+	/// Whether the region reverted (as expected), the execution resuming at its end
+	pub reverted: bool,
+}
+
+/// Format panic data as a list of hexadecimal felts, eg: `[0x5f, 0x1]`
+fn format_panic_data(panic_data: &[BigInt]) -> String {
+	let felts: Vec<String> = panic_data.iter().map(|felt| format!("{felt:#x}")).collect();
+	format!("[{}]", felts.join(", "))
+}
+
+impl ExpectedRevert {
+	/// Check that the error the execution reverted with matches the expectation.
+	/// `panic_data` is the data the execution panicked with, if it reverted through `panic`.
+	///
+	/// Returns a `CustomHint` error describing both the expected and the actual errors
+	/// otherwise.
+	pub fn check(
+		&self,
+		error: &VirtualMachineError,
+		panic_data: Option<&[BigInt]>,
+	) -> Result<(), VirtualMachineError> {
+		match (&self.message, &self.panic_data) {
+			(Some(expected), _) if !error.to_string().contains(expected.as_str()) =>
+				Err(VirtualMachineError::CustomHint(format!(
+					"{EXPECT_REVERT_MISMATCH_PREFIX}\nExpected: {expected}\nActual: {error}"
+				))),
+			(_, Some(expected)) if panic_data != Some(expected.as_slice()) => {
+				let actual = match panic_data {
+					Some(panic_data) => format_panic_data(panic_data),
+					None => format!("no panic data ({error})"),
+				};
+				Err(VirtualMachineError::CustomHint(format!(
+					"{EXPECT_REVERT_MISMATCH_PREFIX}\nExpected: {}\nActual: {actual}",
+					format_panic_data(expected)
+				)))
+			},
+			_ => Ok(()),
+		}
+	}
+}
 
 /// Expects an exception will be raised
 /// If an exception is triggered the test will pass. If not, the test will fail.
 ///
+/// An optional message can be given, in which case the test will only pass if the error
+/// message of the exception contains it.
+///
 /// Returns Result<(), VirtualMachineError>
 ///
 /// # Examples
@@ -27,6 +103,11 @@ pub const EXPECT_REVERT_FLAG: &str = "expect_revert";
 ///     %{ expect_revert() %}
 ///     assert 2 = 3;
 /// }
+///
+/// func test_that_should_revert_with_message() {
+///     %{ expect_revert("ASSERT_EQ") %}
+///     assert 2 = 3;
+/// }
 /// ```
 pub fn expect_revert(
 	_vm: &mut VirtualMachine,
@@ -34,8 +115,285 @@ pub fn expect_revert(
 	_ids_data: &HashMap<String, HintReference>,
 	_ap_tracking: &ApTracking,
 	_constants: &HashMap<String, BigInt>,
-	_args: &[String],
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	exec_scopes.assign_or_update_variable(
+		EXPECT_REVERT_FLAG,
+		Box::new(ExpectedRevert {
+			message: expected_message(args),
+			panic_data: None,
+			region: None,
+		}),
+	);
+	Ok(())
+}
+
+/// Expects an exception to be raised before the next `stop_expect_revert`.
+/// Same as `expect_revert` (with the same optional message), but a revert happening after the
+/// region does not satisfy the expectation, and the test fails if its execution reaches
+/// `stop_expect_revert` without reverting.
+///
+/// Once the region reverted, the execution resumes at `stop_expect_revert` (see
+/// `resume_after_region`): the code following the region runs, and fails the test if it reverts.
+/// Both hints must be in the same function, and the values computed in the region cannot be used
+/// after it.
+///
+/// Returns Result<(), VirtualMachineError>
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_withdraw_reverts() {
+///     deposit(100);
+///     %{ start_expect_revert() %}
+///     withdraw(200);
+///     %{ stop_expect_revert() %}
+///     return ();
+/// }
+/// ```
+pub fn start_expect_revert(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	_ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
 ) -> Result<(), VirtualMachineError> {
-	exec_scopes.assign_or_update_variable(EXPECT_REVERT_FLAG, Box::new(true));
+	let region = RevertRegion {
+		start_pc: vm.get_pc().offset,
+		ap: vm.get_ap().offset,
+		fp: vm.get_fp().offset,
+		ap_tracking: ap_tracking.clone(),
+		reverted: false,
+	};
+	exec_scopes.assign_or_update_variable(
+		EXPECT_REVERT_FLAG,
+		Box::new(ExpectedRevert {
+			message: expected_message(args),
+			panic_data: None,
+			region: Some(region),
+		}),
+	);
 	Ok(())
 }
+
+/// End the region opened by `start_expect_revert`: the execution resumes here once the region
+/// reverted, reaching it otherwise means that the region did not revert, the test fails.
+///
+/// Returns an error if the region did not revert
+pub fn stop_expect_revert(
+	_vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	_ids_data: &HashMap<String, HintReference>,
+	_ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	no_args("stop_expect_revert", args)?;
+	let opt_region = exec_scopes
+		.get::<ExpectedRevert>(EXPECT_REVERT_FLAG)
+		.ok()
+		.and_then(|expected_revert| expected_revert.region);
+	let region = match opt_region {
+		Some(region) => region,
+		None =>
+			return Err(VirtualMachineError::CustomHint(String::from(
+				"stop_expect_revert() called without start_expect_revert()",
+			))),
+	};
+	// Either the expectation is met, or the error below is the failure of the test, not the
+	// revert it expected
+	exec_scopes.delete_variable(EXPECT_REVERT_FLAG);
+	if region.reverted {
+		return Ok(())
+	}
+	Err(VirtualMachineError::CustomHint(
+		EXPECT_REVERT_REGION_NOT_REVERTED.to_string(),
+	))
+}
+
+/// Offsets of the pc of the `stop_expect_revert` hints of a program, with the tracking of ap at
+/// each of them
+pub fn revert_region_ends(program: &Program) -> BTreeMap<usize, ApTracking> {
+	program
+		.hints
+		.iter()
+		.filter_map(|(pc, hints)| {
+			hints
+				.iter()
+				.find(|hint| hint.code.trim().starts_with("stop_expect_revert("))
+				.map(|hint| (*pc, hint.flow_tracking_data.ap_tracking.clone()))
+		})
+		.collect()
+}
+
+/// Resume an execution which reverted inside the region opened by `start_expect_revert`, at the
+/// `stop_expect_revert` hint ending it (the first one following the start of the region, among
+/// `region_ends`).
+///
+/// fp is restored to its value at the start of the region. ap is the one the region would have
+/// ended with if its ap change is known at compile time (ie: the tracking of ap at both hints is
+/// in the same group), or the one of the revert otherwise, as the references to ap have then been
+/// revoked by the compiler.
+///
+/// Returns whether the execution was resumed, or the error describing how the revert did not
+/// match the expectation. There is nothing to resume if the revert happened outside of a region,
+/// or if the region is never ended.
+pub fn resume_after_region(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	region_ends: &BTreeMap<usize, ApTracking>,
+	error: &VirtualMachineError,
+) -> Result<bool, VirtualMachineError> {
+	let mut expected_revert = match exec_scopes.get::<ExpectedRevert>(EXPECT_REVERT_FLAG) {
+		Ok(expected_revert) => expected_revert,
+		Err(_) => return Ok(false),
+	};
+	let region = match &expected_revert.region {
+		Some(region) if !region.reverted => region.clone(),
+		_ => return Ok(false),
+	};
+	let opt_panic_data = exec_scopes.get::<Vec<BigInt>>(PANIC_DATA_VAR_NAME).ok();
+	if let Err(mismatch) = expected_revert.check(error, opt_panic_data.as_deref()) {
+		// The mismatch is the failure of the test, not the revert it expected
+		exec_scopes.delete_variable(EXPECT_REVERT_FLAG);
+		return Err(mismatch)
+	}
+	let (end_pc, end_ap_tracking) = match region_ends.range(region.start_pc + 1..).next() {
+		Some(region_end) => region_end,
+		None => return Ok(false),
+	};
+
+	let ap = if end_ap_tracking.group == region.ap_tracking.group {
+		region.ap + end_ap_tracking.offset - region.ap_tracking.offset
+	} else {
+		vm.get_ap().offset
+	};
+	let pc = Relocatable {
+		segment_index: vm.get_pc().segment_index,
+		offset: *end_pc,
+	};
+	vm.set_pc(pc);
+	vm.set_ap(ap);
+	vm.set_fp(region.fp);
+
+	// The panic data of the revert must not be mistaken for the one of a later revert
+	exec_scopes.delete_variable(PANIC_DATA_VAR_NAME);
+	expected_revert.region = Some(RevertRegion {
+		reverted: true,
+		..region
+	});
+	exec_scopes.assign_or_update_variable(EXPECT_REVERT_FLAG, Box::new(expected_revert));
+	Ok(true)
+}
+
+/// Parse the arguments of a panic hint: the selector, followed by the data
+fn panic_data_arg(
+	hint: &str,
+	args: &[String],
+	vm: &VirtualMachine,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+) -> Result<Vec<BigInt>, VirtualMachineError> {
+	let panic_data: Vec<BigInt> = args
+		.iter()
+		.filter(|arg| !arg.is_empty())
+		.map(|arg| get_felt_arg(arg, vm, ids_data, ap_tracking).map(|felt| felt.into_owned()))
+		.collect::<Result<_, _>>()?;
+	if panic_data.is_empty() {
+		return Err(VirtualMachineError::CustomHint(format!(
+			"{hint} expects at least 1 argument, got 0"
+		)))
+	}
+	Ok(panic_data)
+}
+
+/// Revert the execution with panic data: an error selector, followed by its data.
+/// Cairo 0 has no panics, this hint stands for them so that the reverts carrying an error
+/// selector can be tested with `assert_revert_with_selector`.
+///
+/// Each argument is a felt variable or an integer literal.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// const INSUFFICIENT_BALANCE = 0x1f;
+///
+/// func withdraw(balance: felt, amount: felt) {
+///     if (is_le(amount, balance) == 0) {
+///         %{ panic(INSUFFICIENT_BALANCE, amount) %}
+///     }
+///     return ();
+/// }
+/// ```
+pub fn panic(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let panic_data = panic_data_arg("panic", args, vm, ids_data, ap_tracking)?;
+	let message = format!("{PANIC_PREFIX} {}", format_panic_data(&panic_data));
+	exec_scopes.assign_or_update_variable(PANIC_DATA_VAR_NAME, Box::new(panic_data));
+	Err(VirtualMachineError::CustomHint(message))
+}
+
+/// Expects the execution to panic with the given selector and data.
+/// The test will only pass if it reverts through a `panic` carrying exactly these felts.
+///
+/// The first argument is the selector, the next ones the data, each one a felt variable or an
+/// integer literal.
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_withdraw_more_than_balance() {
+///     %{ assert_revert_with_selector(0x1f, 200) %}
+///     withdraw(100, 200);
+///     return ();
+/// }
+/// ```
+pub fn assert_revert_with_selector(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let panic_data = panic_data_arg(
+		"assert_revert_with_selector",
+		args,
+		vm,
+		ids_data,
+		ap_tracking,
+	)?;
+	exec_scopes.assign_or_update_variable(
+		EXPECT_REVERT_FLAG,
+		Box::new(ExpectedRevert {
+			message: None,
+			panic_data: Some(panic_data),
+			region: None,
+		}),
+	);
+	Ok(())
+}
+
+/// Rebuild the message given to the hint, which has been split on commas by the hint processor
+fn expected_message(args: &[String]) -> Option<String> {
+	let message = args.join(", ");
+	let message = message.trim().trim_matches(|c| c == '"' || c == '\'');
+	if message.is_empty() {
+		None
+	} else {
+		Some(message.to_string())
+	}
+}
@@ -0,0 +1,11 @@
+func test_expect_revert_with_message() {
+    %{ expect_revert("ASSERT_EQ") %}
+    assert 2 = 3;
+    return ();
+}
+
+func test_expect_revert_with_wrong_message() {
+    %{ expect_revert("not the reason of the revert") %}
+    assert 2 = 3;
+    return ();
+}
@@ -0,0 +1,68 @@
+func test_revert_in_region() {
+    assert 2 = 2;
+    %{ start_expect_revert("ASSERT_EQ") %}
+    assert 2 = 3;
+    %{ stop_expect_revert() %}
+    return ();
+}
+
+func test_revert_in_region_with_wrong_message() {
+    %{ start_expect_revert("not the reason of the revert") %}
+    assert 2 = 3;
+    %{ stop_expect_revert() %}
+    return ();
+}
+
+func test_code_after_reverted_region_runs() {
+    %{ start_expect_revert() %}
+    assert 2 = 3;
+    %{ stop_expect_revert() %}
+    assert 4 = 5;
+    return ();
+}
+
+func always_reverts() {
+    assert 2 = 3;
+    return ();
+}
+
+func test_revert_in_function_called_in_region() {
+    alloc_locals;
+    local x = 42;
+    %{ start_expect_revert("ASSERT_EQ") %}
+    always_reverts();
+    %{ stop_expect_revert() %}
+    assert x = 42;
+    return ();
+}
+
+func test_two_reverted_regions() {
+    %{ start_expect_revert() %}
+    assert 2 = 3;
+    %{ stop_expect_revert() %}
+    assert 2 = 2;
+    %{ start_expect_revert() %}
+    always_reverts();
+    %{ stop_expect_revert() %}
+    return ();
+}
+
+func test_revert_before_region() {
+    assert 2 = 3;
+    %{ start_expect_revert() %}
+    %{ stop_expect_revert() %}
+    return ();
+}
+
+func test_revert_after_region() {
+    %{ start_expect_revert() %}
+    assert 2 = 2;
+    %{ stop_expect_revert() %}
+    assert 2 = 3;
+    return ();
+}
+
+func test_stop_without_start() {
+    %{ stop_expect_revert() %}
+    return ();
+}
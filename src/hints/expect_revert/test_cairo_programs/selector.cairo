@@ -0,0 +1,31 @@
+const INSUFFICIENT_BALANCE = 0x1f;
+
+func withdraw(amount: felt) {
+    let selector = INSUFFICIENT_BALANCE;
+    %{ panic(selector, amount) %}
+    return ();
+}
+
+func test_revert_with_selector() {
+    %{ assert_revert_with_selector(0x1f, 200) %}
+    withdraw(200);
+    return ();
+}
+
+func test_revert_with_wrong_selector() {
+    %{ assert_revert_with_selector(0x2a, 200) %}
+    withdraw(200);
+    return ();
+}
+
+func test_revert_with_wrong_data() {
+    %{ assert_revert_with_selector(0x1f, 100) %}
+    withdraw(200);
+    return ();
+}
+
+func test_revert_without_panic() {
+    %{ assert_revert_with_selector(0x1f, 200) %}
+    assert 2 = 3;
+    return ();
+}
@@ -21,3 +21,85 @@ fn expect_revert(
 	assert_eq!(expected_success, result);
 	Ok(())
 }
+
+#[rstest]
+#[case("test_expect_revert_with_message", TestStatus::SUCCESS)]
+#[case("test_expect_revert_with_wrong_message", TestStatus::FAILURE)]
+fn expect_revert_with_message(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path =
+		std::path::PathBuf::from("src/hints/expect_revert/test_cairo_programs/message.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok");
+	assert_eq!(expected_success, result.success);
+	if expected_success == TestStatus::FAILURE {
+		assert!(result.error.unwrap().contains("Expected: not the reason of the revert"));
+	}
+	Ok(())
+}
+
+#[rstest]
+#[case("test_revert_with_selector", None)]
+#[case(
+	"test_revert_with_wrong_selector",
+	Some("Expected: [0x2a, 0xc8]\nActual: [0x1f, 0xc8]")
+)]
+#[case(
+	"test_revert_with_wrong_data",
+	Some("Expected: [0x1f, 0x64]\nActual: [0x1f, 0xc8]")
+)]
+#[case("test_revert_without_panic", Some("Actual: no panic data"))]
+fn assert_revert_with_selector(
+	#[case] test_name: &str,
+	#[case] expected_error: Option<&str>,
+) -> Result<(), TestCommandError> {
+	let path =
+		std::path::PathBuf::from("src/hints/expect_revert/test_cairo_programs/selector.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok");
+	match expected_error {
+		None => assert_eq!(TestStatus::SUCCESS, result.success, "{:?}", result.error),
+		Some(expected_error) => {
+			assert_eq!(TestStatus::FAILURE, result.success);
+			let error = result.error.unwrap();
+			assert!(error.contains(expected_error), "{error}");
+		},
+	}
+	Ok(())
+}
+
+#[rstest]
+#[case("test_revert_in_region", TestStatus::SUCCESS, None)]
+#[case(
+	"test_revert_in_region_with_wrong_message",
+	TestStatus::FAILURE,
+	Some("Expected: not the reason of the revert")
+)]
+#[case("test_code_after_reverted_region_runs", TestStatus::FAILURE, Some("ASSERT_EQ"))]
+#[case("test_revert_in_function_called_in_region", TestStatus::SUCCESS, None)]
+#[case("test_two_reverted_regions", TestStatus::SUCCESS, None)]
+#[case("test_revert_before_region", TestStatus::FAILURE, None)]
+#[case(
+	"test_revert_after_region",
+	TestStatus::FAILURE,
+	Some("execution did not revert between start_expect_revert() and stop_expect_revert()")
+)]
+#[case(
+	"test_stop_without_start",
+	TestStatus::FAILURE,
+	Some("stop_expect_revert() called without start_expect_revert()")
+)]
+fn scoped_expect_revert(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+	#[case] expected_error: Option<&str>,
+) -> Result<(), TestCommandError> {
+	let path = std::path::PathBuf::from("src/hints/expect_revert/test_cairo_programs/scoped.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok");
+	assert_eq!(expected_success, result.success, "{:?}", result.error);
+	if let Some(expected_error) = expected_error {
+		let error = result.error.unwrap();
+		assert!(error.contains(expected_error), "{error}");
+	}
+	Ok(())
+}
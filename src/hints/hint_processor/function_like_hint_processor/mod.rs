@@ -11,7 +11,7 @@ use cairo_rs::{
 	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
 };
 use num_bigint::BigInt;
-use std::{any::Any, collections::HashMap, rc::Rc};
+use std::{any::Any, collections::HashMap, fmt, rc::Rc};
 
 #[cfg(test)]
 mod tests;
@@ -37,6 +37,28 @@ pub enum Code {
 	Function(String, Vec<String>), // (name, args)
 }
 
+impl fmt::Display for Code {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Code::RawCode(raw_code) => write!(f, "{}", raw_code.trim()),
+			Code::Function(name, args) => write!(f, "{name}({})", args.join(", ")),
+		}
+	}
+}
+
+/// A hint whose execution failed, and the pc of the instruction it is attached to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedHint {
+	pub code: String,
+	pub pc: usize,
+}
+
+impl fmt::Display for FailedHint {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "in hint `{}` at pc {}", self.code, self.pc)
+	}
+}
+
 #[derive(Debug)]
 pub struct FunctionLikeHintProcessorData {
 	pub code: Code,
@@ -47,6 +69,8 @@ pub struct FunctionLikeHintProcessorData {
 pub struct FunctionLikeHintProcessor {
 	hints: HashMap<String, Rc<HintFunc>>,
 	builtin_hint_processor: BuiltinHintProcessor,
+	/// Last hint whose execution failed, it is kept until it is taken
+	failed_hint: Option<FailedHint>,
 }
 
 impl FunctionLikeHintProcessor {
@@ -54,6 +78,7 @@ impl FunctionLikeHintProcessor {
 		FunctionLikeHintProcessor {
 			hints: HashMap::new(),
 			builtin_hint_processor: BuiltinHintProcessor::new_empty(),
+			failed_hint: None,
 		}
 	}
 
@@ -61,26 +86,27 @@ impl FunctionLikeHintProcessor {
 		FunctionLikeHintProcessor {
 			hints,
 			builtin_hint_processor: BuiltinHintProcessor::new_empty(),
+			failed_hint: None,
 		}
 	}
 
 	pub fn add_hint(&mut self, hint_code_function_like: String, func: Rc<HintFunc>) {
 		self.hints.insert(hint_code_function_like, func);
 	}
-}
 
-impl HintProcessor for FunctionLikeHintProcessor {
-	fn execute_hint(
+	/// Take the last hint whose execution failed, if any, so that an execution error can be
+	/// reported along with the hint which caused it
+	pub fn take_failed_hint(&mut self) -> Option<FailedHint> {
+		self.failed_hint.take()
+	}
+
+	fn execute_hint_code(
 		&mut self,
 		vm: &mut VirtualMachine,
 		exec_scopes: &mut ExecutionScopes,
-		hint_data: &Box<dyn std::any::Any>,
-		constants: &HashMap<String, num_bigint::BigInt>,
+		hint_data: &FunctionLikeHintProcessorData,
+		constants: &HashMap<String, BigInt>,
 	) -> Result<(), VirtualMachineError> {
-		let hint_data = hint_data
-			.downcast_ref::<FunctionLikeHintProcessorData>()
-			.ok_or(VirtualMachineError::WrongHintData)?;
-
 		match &hint_data.code {
 			Code::RawCode(raw_code) => self.builtin_hint_processor.execute_hint(
 				vm,
@@ -91,7 +117,7 @@ impl HintProcessor for FunctionLikeHintProcessor {
 					ids_data: hint_data.ids_data.clone(),
 				}),
 				constants,
-			)?,
+			),
 			Code::Function(name, args) => {
 				let ptr_hint_func =
 					self.hints.get(name).ok_or(VirtualMachineError::WrongHintData)?;
@@ -102,11 +128,32 @@ impl HintProcessor for FunctionLikeHintProcessor {
 					&hint_data.ap_tracking.clone(),
 					constants,
 					args,
-				)?
+				)
 			},
-		};
+		}
+	}
+}
+
+impl HintProcessor for FunctionLikeHintProcessor {
+	fn execute_hint(
+		&mut self,
+		vm: &mut VirtualMachine,
+		exec_scopes: &mut ExecutionScopes,
+		hint_data: &Box<dyn std::any::Any>,
+		constants: &HashMap<String, num_bigint::BigInt>,
+	) -> Result<(), VirtualMachineError> {
+		let hint_data = hint_data
+			.downcast_ref::<FunctionLikeHintProcessorData>()
+			.ok_or(VirtualMachineError::WrongHintData)?;
 
-		Ok(())
+		let result = self.execute_hint_code(vm, exec_scopes, hint_data, constants);
+		if result.is_err() {
+			self.failed_hint = Some(FailedHint {
+				code: hint_data.code.to_string(),
+				pc: vm.get_pc().offset,
+			});
+		}
+		result
 	}
 
 	fn compile_hint(
@@ -9,7 +9,7 @@ use std::{collections::HashMap, rc::Rc};
 use crate::{
 	hints,
 	hints::hint_processor::function_like_hint_processor::{
-		Code, FunctionLikeHintProcessor, FunctionLikeHintProcessorData, HintFunc,
+		Code, FailedHint, FunctionLikeHintProcessor, FunctionLikeHintProcessorData, HintFunc,
 	},
 };
 use rstest::rstest;
@@ -107,3 +107,37 @@ fn test_function_like_hint_should_return_function_name_and_args_hint(
 	);
 	Ok(())
 }
+
+#[test]
+fn failing_hint_is_recorded_with_its_code_and_pc() {
+	let mut hint_processor = FunctionLikeHintProcessor::default();
+	hint_processor.add_hint(
+		String::from("skip"),
+		Rc::new(HintFunc(Box::new(hints::skip))),
+	);
+	let hint_data = hint_processor
+		.compile_hint(
+			"skip()",
+			&Default::default(),
+			&Default::default(),
+			&Default::default(),
+		)
+		.unwrap();
+
+	let result = hint_processor.execute_hint(
+		&mut VirtualMachine::new(Default::default(), true, vec![]),
+		&mut ExecutionScopes::new(),
+		&hint_data,
+		&HashMap::new(),
+	);
+
+	assert!(result.is_err());
+	assert_eq!(
+		Some(FailedHint {
+			code: String::from("skip()"),
+			pc: 0
+		}),
+		hint_processor.take_failed_hint()
+	);
+	assert_eq!(None, hint_processor.take_failed_hint());
+}
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+
+use cairo_rs::{
+	hint_processor::hint_processor_definition::HintReference,
+	serde::deserialize_program::ApTracking,
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+
+use super::args::single_arg;
+use crate::hooks::MAX_STEPS_VAR_NAME;
+
+/// Override the maximum number of steps of the current test
+///
+/// The override takes precedence over the `--max-steps` command line option, for this test only.
+/// The limit applies to the whole execution of the test, steps executed before the hint included.
+///
+/// Returns nothing, or an error if the argument is not a number of steps
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_long_computation() {
+///     %{ set_max_steps(10000000) %}
+///     long_computation();
+///     return ();
+/// }
+/// ```
+pub fn set_max_steps(
+	_vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	_ids_data: &HashMap<String, HintReference>,
+	_ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let max_steps = single_arg("set_max_steps", args)?;
+	let max_steps = max_steps.parse::<u64>().map_err(|_| {
+		VirtualMachineError::CustomHint(format!(
+			"set_max_steps expects a number of steps, got '{max_steps}'"
+		))
+	})?;
+	exec_scopes.assign_or_update_variable(MAX_STEPS_VAR_NAME, Box::new(max_steps));
+	Ok(())
+}
@@ -0,0 +1,25 @@
+// cairo-foundry: max_steps=1000
+
+func count_down(n: felt) {
+    if (n == 0) {
+        return ();
+    }
+    count_down(n - 1);
+    return ();
+}
+
+func test_within_file_max_steps() {
+    count_down(10);
+    return ();
+}
+
+func test_exceeding_file_max_steps() {
+    count_down(1000);
+    return ();
+}
+
+func test_overriding_file_max_steps() {
+    %{ set_max_steps(100000) %}
+    count_down(1000);
+    return ();
+}
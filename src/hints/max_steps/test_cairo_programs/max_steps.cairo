@@ -0,0 +1,29 @@
+func count_down(n: felt) {
+    if (n == 0) {
+        return ();
+    }
+    count_down(n - 1);
+    return ();
+}
+
+func test_without_override() {
+    count_down(1000);
+    return ();
+}
+
+func test_with_override() {
+    %{ set_max_steps(100000) %}
+    count_down(1000);
+    return ();
+}
+
+func test_with_lower_override() {
+    %{ set_max_steps(10) %}
+    count_down(10);
+    return ();
+}
+
+func test_with_invalid_override() {
+    %{ set_max_steps(many) %}
+    return ();
+}
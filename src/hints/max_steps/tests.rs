@@ -0,0 +1,71 @@
+use rstest::rstest;
+
+use crate::cli::commands::test::{
+	tests::{run_single_test, run_test_file},
+	TestCommandError, TestOptions, TestStatus,
+};
+
+// The tests are run with a global limit of 1000 steps, which `count_down(1000)` exceeds
+#[rstest]
+#[case("test_without_override", TestStatus::FAILURE)]
+#[case("test_with_override", TestStatus::SUCCESS)]
+#[case("test_with_lower_override", TestStatus::FAILURE)]
+#[case("test_with_invalid_override", TestStatus::FAILURE)]
+fn set_max_steps(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path = std::path::PathBuf::from("src/hints/max_steps/test_cairo_programs/max_steps.cairo");
+	let result = run_single_test(test_name, &path, 1000).expect("Should be Ok");
+	assert_eq!(expected_success, result.success);
+	Ok(())
+}
+
+#[test]
+fn exceeding_max_steps_is_reported() {
+	let path = std::path::PathBuf::from("src/hints/max_steps/test_cairo_programs/max_steps.cairo");
+	let result = run_single_test("test_without_override", &path, 1000).expect("Should be Ok");
+	assert_eq!(
+		Some(String::from(
+			"exceeded max steps (1000); increase with --max-steps"
+		)),
+		result.error
+	);
+	assert_eq!(
+		Some(String::from("max_steps reached: 1000")),
+		result.raw_error
+	);
+}
+
+// The directive of the file limits its tests to 1000 steps, despite the global limit of 1000000
+// steps, and `set_max_steps` overrides it
+#[test]
+fn file_directive_overrides_global_max_steps() {
+	let path =
+		std::path::PathBuf::from("src/hints/max_steps/test_cairo_programs/file_directive.cairo");
+	let options = TestOptions {
+		max_steps: 1000000,
+		..Default::default()
+	};
+	let file_result = run_test_file(&path, &options).unwrap().expect("Should contain tests");
+
+	let status = |name: &str| {
+		file_result
+			.tests
+			.iter()
+			.find(|test| test.entrypoint == name)
+			.map(|test| test.success)
+	};
+	assert_eq!(
+		Some(TestStatus::SUCCESS),
+		status("test_within_file_max_steps")
+	);
+	assert_eq!(
+		Some(TestStatus::FAILURE),
+		status("test_exceeding_file_max_steps")
+	);
+	assert_eq!(
+		Some(TestStatus::SUCCESS),
+		status("test_overriding_file_max_steps")
+	);
+}
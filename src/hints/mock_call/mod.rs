@@ -16,6 +16,9 @@ use num_bigint::BigInt;
 
 pub const MOCK_CALL_KEY: &str = "mock_call";
 
+/// Mocked return values, by offset of the mocked function
+pub type MockCalls = HashMap<usize, BigInt>;
+
 pub fn mock_call(
 	vm: &mut VirtualMachine,
 	exec_scopes: &mut ExecutionScopes,
@@ -29,7 +32,7 @@ pub fn mock_call(
 
 	let mocks = exec_scopes
 		.get_any_boxed_mut(MOCK_CALL_KEY)?
-		.downcast_mut::<HashMap<usize, BigInt>>()
+		.downcast_mut::<MockCalls>()
 		.ok_or_else(|| VirtualMachineError::VariableNotInScopeError(MOCK_CALL_KEY.to_string()))?;
 	mocks.insert(func_to_mock.offset, (*mock_ret_value).clone());
 
@@ -2,11 +2,45 @@ mod mock_call;
 pub use mock_call::*;
 
 mod expect_revert;
-pub use expect_revert::{expect_revert, EXPECT_REVERT_FLAG};
+pub use expect_revert::{
+	assert_revert_with_selector, expect_revert, panic, resume_after_region, revert_region_ends,
+	start_expect_revert, stop_expect_revert, ExpectedRevert, RevertRegion, EXPECT_REVERT_FLAG,
+	EXPECT_REVERT_MISMATCH_PREFIX, EXPECT_REVERT_REGION_NOT_REVERTED, PANIC_DATA_VAR_NAME,
+	PANIC_PREFIX,
+};
 
 mod skip;
 pub use skip::*;
 
+mod print;
+pub use print::*;
+
+mod assertions;
+pub use assertions::*;
+
+mod max_steps;
+pub use max_steps::*;
+
+mod env_var;
+pub use env_var::*;
+
+mod events;
+pub use events::*;
+
+pub mod cheatcodes;
+pub use cheatcodes::{
+	call_contract, clear_mocks, get_block_number, get_block_timestamp, get_caller_address, load,
+	mock_contract_call, prank, roll, stop_prank, store, warp,
+};
+
+pub mod random;
+pub use random::rand;
+
+mod custom;
+pub use custom::{register_hint, CustomHint};
+
+mod args;
+
 pub(crate) mod hint_processor;
 pub mod output_buffer;
 pub mod processor;
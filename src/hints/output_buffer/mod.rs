@@ -0,0 +1,417 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+	collections::HashMap,
+	fmt,
+	io::{self, Write},
+	mem,
+	sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+use cairo_rs::{types::exec_scope::ExecutionScopes, vm::errors::vm_errors::VirtualMachineError};
+use lazy_static::lazy_static;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use uuid::Uuid;
+
+use super::{cheatcodes::clear_cheatcodes, random::clear_random};
+
+pub const EXECUTION_UUID_VAR_NAME: &str = "cairo-foundry-execution-uuid";
+/// Default maximum size of the captured stdout (and stderr) of an execution, in bytes
+pub const DEFAULT_MAX_OUTPUT: usize = 1024 * 1024;
+/// Marker ending a captured output which has been truncated at its maximum size
+pub const OUTPUT_TRUNCATED_MARKER: &str = "[output truncated]";
+
+lazy_static! {
+	/// the `HINT_OUTPUT_BUFFER` is a hasmap<Uuid,String> protected from concurrency
+	/// with a RwLock (so that tests can be executed on several threads) used by `fn test_single_entrypoint` to store the output of the `cairo_run`
+	/// command for each test entrypoint.
+	///
+	/// Before test execution, an Uuid is associated for the test, test is executed
+	/// and `cairo_run` output is captured in the `HINT_OUTPUT_BUFFER`
+	///
+	/// # Example:
+	/// ```
+	/// # use uuid::Uuid;
+	/// # use cairo_foundry::hints::output_buffer::{clear_buffer, get_buffer, init_buffer, write_to_output_buffer};
+	///
+	/// let execution_uuid = Uuid::new_v4();
+	///	init_buffer(execution_uuid);
+	///
+	/// write_to_output_buffer(&execution_uuid, "foo");
+	/// let buffer = get_buffer(&execution_uuid).unwrap();
+	/// assert_eq!(buffer, "foo");
+	///
+	/// clear_buffer(&execution_uuid);
+	/// let buffer = get_buffer(&execution_uuid);
+	/// assert_eq!(buffer, None);
+	/// ```
+	static ref HINT_OUTPUT_BUFFER: Buffers = RwLock::new(HashMap::new());
+
+	/// the `HINT_STDERR_BUFFER` is the counterpart of `HINT_OUTPUT_BUFFER` for the error
+	/// output of the hints (eg: the `eprint` hint), keyed by the same execution Uuid.
+	static ref HINT_STDERR_BUFFER: Buffers = RwLock::new(HashMap::new());
+
+	/// the `LIVE_OUTPUTS` are the executions whose output is not captured (with `--nocapture`),
+	/// but written to the terminal as it is produced, prefixed with the name of their entrypoint
+	/// (the value of their key).
+	static ref LIVE_OUTPUTS: RwLock<HashMap<Uuid, String>> = RwLock::new(HashMap::new());
+}
+
+/// Output of an execution a line belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputStream {
+	Stdout,
+	Stderr,
+}
+
+/// Function called with each line of the captured output of an execution as soon as it is
+/// complete, along with the name of the entrypoint and the output it has been written to.
+///
+/// The output is still captured: the observer only allows it to be followed while the execution
+/// runs (eg: to see where a test which never returns is stuck). It is called from the threads
+/// running the tests, and must not write to the output buffers.
+///
+/// ```
+/// # use uuid::Uuid;
+/// # use cairo_foundry::hints::output_buffer::{write_to_output_buffer, OutputBufferGuard, OutputObserver};
+/// let observer = OutputObserver::new(|entrypoint, _stream, line| println!("{entrypoint}: {line}"));
+///
+/// let execution_uuid = Uuid::new_v4();
+/// let guard = OutputBufferGuard::new(execution_uuid).with_observer(observer, "test_foo");
+/// write_to_output_buffer(&execution_uuid, "x is 42\ny is");
+/// // "test_foo: x is 42" has been printed, "y is" will be once the line is complete (or once
+/// // the guard is dropped)
+/// assert_eq!(Some(String::from("x is 42\ny is")), guard.content());
+/// ```
+#[derive(Clone)]
+#[allow(clippy::type_complexity)]
+pub struct OutputObserver(Arc<dyn Fn(&str, OutputStream, &str) + Send + Sync>);
+
+impl OutputObserver {
+	pub fn new(observer: impl Fn(&str, OutputStream, &str) + Send + Sync + 'static) -> Self {
+		Self(Arc::new(observer))
+	}
+
+	fn notify(&self, entrypoint: &str, stream: OutputStream, lines: &[String]) {
+		for line in lines {
+			(self.0)(entrypoint, stream, line);
+		}
+	}
+}
+
+impl fmt::Debug for OutputObserver {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("OutputObserver")
+	}
+}
+
+/// Observer of a buffer, and the line being written to it
+struct ObservedLines {
+	observer: OutputObserver,
+	entrypoint: String,
+	stream: OutputStream,
+	partial_line: String,
+}
+
+impl ObservedLines {
+	/// Append `data` to the line being written, and take the lines it completed
+	fn complete_lines(&mut self, data: &str) -> Vec<String> {
+		self.partial_line.push_str(data);
+		match self.partial_line.rfind('\n') {
+			Some(end) => {
+				let rest = self.partial_line.split_off(end + 1);
+				mem::replace(&mut self.partial_line, rest).lines().map(String::from).collect()
+			},
+			None => Vec::new(),
+		}
+	}
+}
+
+/// Output captured for an execution, which stops growing once it reaches `max_size` bytes
+struct Buffer {
+	content: String,
+	max_size: usize,
+	truncated: bool,
+	/// Observer of the lines written to the buffer, truncated or not
+	observed: Option<ObservedLines>,
+}
+
+impl Buffer {
+	fn new() -> Self {
+		Self {
+			content: String::new(),
+			max_size: DEFAULT_MAX_OUTPUT,
+			truncated: false,
+			observed: None,
+		}
+	}
+
+	/// Append `data`, or the part of it which fits in the buffer
+	fn push_str(&mut self, data: &str) {
+		if self.truncated {
+			return
+		}
+		let available = self.max_size.saturating_sub(self.content.len());
+		if data.len() <= available {
+			self.content.push_str(data);
+			return
+		}
+		let mut end = available;
+		while !data.is_char_boundary(end) {
+			end -= 1;
+		}
+		self.content.push_str(&data[..end]);
+		self.truncated = true;
+	}
+
+	/// The captured output, followed by `OUTPUT_TRUNCATED_MARKER` if it has been truncated
+	fn output(&self) -> String {
+		if !self.truncated {
+			return self.content.clone()
+		}
+		let separator = if self.content.is_empty() || self.content.ends_with('\n') {
+			""
+		} else {
+			"\n"
+		};
+		format!("{}{separator}{OUTPUT_TRUNCATED_MARKER}\n", self.content)
+	}
+}
+
+type Buffers = RwLock<HashMap<Uuid, Buffer>>;
+
+// The buffers are only accessed by short operations which cannot leave them in an inconsistent
+// state, a thread panicking while holding the lock must not prevent other executions from writing
+// their output.
+fn read_buffers<T>(buffers: &RwLock<HashMap<Uuid, T>>) -> RwLockReadGuard<HashMap<Uuid, T>> {
+	buffers.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn write_buffers<T>(buffers: &RwLock<HashMap<Uuid, T>>) -> RwLockWriteGuard<HashMap<Uuid, T>> {
+	buffers.write().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Insert a new key 'execution_uuid` in the HINT_OUTPUT_BUFFER HashMap with a default
+/// empty String value.
+/// Returns nothing.
+///
+/// The given `execution_uuid` is the one used to identify the cairo test entrypoint
+pub fn init_buffer(execution_uuid: Uuid) {
+	write_buffers(&HINT_OUTPUT_BUFFER).insert(execution_uuid, Buffer::new());
+}
+
+/// Remove the key `execution_uuid` in the HINT_OUTPUT_BUFFER HashMap.
+/// Returns nothing.
+///
+/// The given `execution_uuid` is the one used to identify the cairo test entrypoint
+pub fn clear_buffer(execution_uuid: &Uuid) {
+	write_buffers(&HINT_OUTPUT_BUFFER).remove(execution_uuid);
+}
+
+/// Returns the cloned value of key `execution_uuid` in the HINT_OUTPUT_BUFFER HashMap.
+///
+/// The given `execution_uuid` is the one used to identify the cairo test entrypoint
+pub fn get_buffer(execution_uuid: &Uuid) -> Option<String> {
+	read_buffers(&HINT_OUTPUT_BUFFER).get(execution_uuid).map(Buffer::output)
+}
+
+/// Write each line of `data` to `out`, prefixed with the name of the `entrypoint`.
+/// The lines are written at once, so that the ones of concurrent executions are not interleaved.
+fn write_live(mut out: impl Write, entrypoint: &str, data: &str) {
+	let lines: String = data.lines().map(|line| format!("[{entrypoint}] {line}\n")).collect();
+	// The output of the test is lost if the terminal cannot be written to, as it would be anyway
+	let _ = out.write_all(lines.as_bytes());
+	let _ = out.flush();
+}
+
+/// Name of the entrypoint of `execution_uuid`, if its output is written live (see `LIVE_OUTPUTS`)
+fn live_entrypoint(execution_uuid: &Uuid) -> Option<String> {
+	read_buffers(&LIVE_OUTPUTS).get(execution_uuid).cloned()
+}
+
+/// Append string `data` to the buffer of `execution_uuid`, and notify its observer, if any, of
+/// the lines it completed.
+///
+/// The observer is called once the lock is released, so that it does not hold up the other
+/// executions
+fn push_to_buffer(buffers: &Buffers, execution_uuid: &Uuid, data: &str) {
+	let mut hashmap_lock = write_buffers(buffers);
+	let opt_buffer = hashmap_lock.get_mut(execution_uuid);
+	let notification = opt_buffer.and_then(|buffer| {
+		buffer.push_str(data);
+		let observed = buffer.observed.as_mut()?;
+		let lines = observed.complete_lines(data);
+		Some((
+			observed.observer.clone(),
+			observed.entrypoint.clone(),
+			observed.stream,
+			lines,
+		))
+	});
+	drop(hashmap_lock);
+	if let Some((observer, entrypoint, stream, lines)) = notification {
+		observer.notify(&entrypoint, stream, &lines);
+	}
+}
+
+/// Append string `data` to the value of key `execution_uuid` in the HINT_OUTPUT_BUFFER HashMap.
+/// If the output of the execution is not captured, `data` is written to stdout instead.
+///
+/// The given `execution_uuid` is the one used to identify the cairo test entrypoint
+pub fn write_to_output_buffer(execution_uuid: &Uuid, data: &str) {
+	if let Some(entrypoint) = live_entrypoint(execution_uuid) {
+		return write_live(io::stdout().lock(), &entrypoint, data)
+	}
+	push_to_buffer(&HINT_OUTPUT_BUFFER, execution_uuid, data);
+}
+
+/// Insert a new key 'execution_uuid` in the HINT_STDERR_BUFFER HashMap with a default
+/// empty String value.
+/// Returns nothing.
+///
+/// The given `execution_uuid` is the one used to identify the cairo test entrypoint
+pub fn init_stderr_buffer(execution_uuid: Uuid) {
+	write_buffers(&HINT_STDERR_BUFFER).insert(execution_uuid, Buffer::new());
+}
+
+/// Remove the key `execution_uuid` in the HINT_STDERR_BUFFER HashMap.
+/// Returns nothing.
+///
+/// The given `execution_uuid` is the one used to identify the cairo test entrypoint
+pub fn clear_stderr_buffer(execution_uuid: &Uuid) {
+	write_buffers(&HINT_STDERR_BUFFER).remove(execution_uuid);
+}
+
+/// Returns the cloned value of key `execution_uuid` in the HINT_STDERR_BUFFER HashMap.
+///
+/// The given `execution_uuid` is the one used to identify the cairo test entrypoint
+pub fn get_stderr_buffer(execution_uuid: &Uuid) -> Option<String> {
+	read_buffers(&HINT_STDERR_BUFFER).get(execution_uuid).map(Buffer::output)
+}
+
+/// Append string `data` to the value of key `execution_uuid` in the HINT_STDERR_BUFFER HashMap.
+/// If the output of the execution is not captured, `data` is written to stderr instead.
+///
+/// The given `execution_uuid` is the one used to identify the cairo test entrypoint
+pub fn write_to_stderr_buffer(execution_uuid: &Uuid, data: &str) {
+	if let Some(entrypoint) = live_entrypoint(execution_uuid) {
+		return write_live(io::stderr().lock(), &entrypoint, data)
+	}
+	push_to_buffer(&HINT_STDERR_BUFFER, execution_uuid, data);
+}
+
+/// Set the maximum size, in bytes, of the stdout and stderr buffers of `execution_uuid`: the
+/// output written beyond it is dropped, and replaced by `OUTPUT_TRUNCATED_MARKER`
+pub fn set_max_output(execution_uuid: &Uuid, max_size: usize) {
+	for buffers in [&*HINT_OUTPUT_BUFFER, &*HINT_STDERR_BUFFER] {
+		if let Some(buffer) = write_buffers(buffers).get_mut(execution_uuid) {
+			buffer.max_size = max_size;
+		}
+	}
+}
+
+/// Notify the observer of the buffers of `execution_uuid` of their last lines, if they are not
+/// complete, and stop observing them
+fn stop_observing(execution_uuid: &Uuid) {
+	for buffers in [&*HINT_OUTPUT_BUFFER, &*HINT_STDERR_BUFFER] {
+		let opt_observed = write_buffers(buffers)
+			.get_mut(execution_uuid)
+			.and_then(|buffer| buffer.observed.take());
+		if let Some(mut observed) =
+			opt_observed.filter(|observed| !observed.partial_line.is_empty())
+		{
+			let line = mem::take(&mut observed.partial_line);
+			observed.observer.notify(&observed.entrypoint, observed.stream, &[line]);
+		}
+	}
+}
+
+/// Returns the Uuid of the current execution, stored in the execution scopes by `cairo_run`
+pub fn get_execution_uuid(exec_scopes: &ExecutionScopes) -> Result<Uuid, VirtualMachineError> {
+	let execution_uuid = exec_scopes.get::<BigInt>(EXECUTION_UUID_VAR_NAME)?;
+	execution_uuid.to_u128().map(Uuid::from_u128).ok_or_else(|| {
+		VirtualMachineError::VariableNotInScopeError(EXECUTION_UUID_VAR_NAME.to_string())
+	})
+}
+
+/// Owns the output buffers (stdout and stderr) of an execution.
+///
+/// The buffers are initialized when the guard is created, and cleared when it is dropped (along
+/// with the cheatcodes and the random generator of the execution), so that they do not outlive the
+/// execution even if it returns early.
+pub struct OutputBufferGuard {
+	execution_uuid: Uuid,
+}
+
+impl OutputBufferGuard {
+	pub fn new(execution_uuid: Uuid) -> Self {
+		init_buffer(execution_uuid);
+		init_stderr_buffer(execution_uuid);
+		Self { execution_uuid }
+	}
+
+	/// Limit the size of the buffers (see `set_max_output`)
+	pub fn with_max_output(self, max_size: usize) -> Self {
+		set_max_output(&self.execution_uuid, max_size);
+		self
+	}
+
+	/// Notify the `observer` of each line written to the buffers as soon as it is complete, the
+	/// lines being attributed to `entrypoint`
+	pub fn with_observer(self, observer: OutputObserver, entrypoint: &str) -> Self {
+		for (buffers, stream) in [
+			(&*HINT_OUTPUT_BUFFER, OutputStream::Stdout),
+			(&*HINT_STDERR_BUFFER, OutputStream::Stderr),
+		] {
+			if let Some(buffer) = write_buffers(buffers).get_mut(&self.execution_uuid) {
+				buffer.observed = Some(ObservedLines {
+					observer: observer.clone(),
+					entrypoint: entrypoint.to_string(),
+					stream,
+					partial_line: String::new(),
+				});
+			}
+		}
+		self
+	}
+
+	/// Same as `new`, but the output of the execution is not captured: it is written to the
+	/// terminal as it is produced, each line prefixed with the name of the `entrypoint`, and the
+	/// buffers stay empty
+	pub fn live(execution_uuid: Uuid, entrypoint: &str) -> Self {
+		write_buffers(&LIVE_OUTPUTS).insert(execution_uuid, entrypoint.to_string());
+		Self::new(execution_uuid)
+	}
+
+	/// Returns the content of the stdout buffer, if it is not empty
+	pub fn content(&self) -> Option<String> {
+		get_buffer(&self.execution_uuid).filter(|buffer| !buffer.is_empty())
+	}
+
+	/// Returns the content of the stderr buffer, if it is not empty
+	pub fn stderr_content(&self) -> Option<String> {
+		get_stderr_buffer(&self.execution_uuid).filter(|buffer| !buffer.is_empty())
+	}
+}
+
+impl Drop for OutputBufferGuard {
+	fn drop(&mut self) {
+		clear_execution(&self.execution_uuid);
+	}
+}
+
+/// Clear the buffers, the cheatcodes and the random generator of an execution (as dropping its
+/// `OutputBufferGuard` does).
+///
+/// The state an execution still running once its guard has been dropped writes afterwards (eg: a
+/// test which timed out) is only cleared by calling it again once the execution returns.
+pub fn clear_execution(execution_uuid: &Uuid) {
+	stop_observing(execution_uuid);
+	clear_buffer(execution_uuid);
+	clear_stderr_buffer(execution_uuid);
+	write_buffers(&LIVE_OUTPUTS).remove(execution_uuid);
+	clear_cheatcodes(execution_uuid);
+	clear_random(execution_uuid);
+}
@@ -0,0 +1,167 @@
+use std::{sync::Mutex, thread};
+
+use super::*;
+
+#[test]
+fn guard_clears_buffer_when_dropped() {
+	let execution_uuid = Uuid::new_v4();
+	{
+		let guard = OutputBufferGuard::new(execution_uuid);
+		assert_eq!(None, guard.content());
+
+		write_to_output_buffer(&execution_uuid, "foo");
+		assert_eq!(Some(String::from("foo")), guard.content());
+	}
+	assert_eq!(None, get_buffer(&execution_uuid));
+}
+
+#[test]
+fn stderr_is_captured_separately() {
+	let execution_uuid = Uuid::new_v4();
+	let guard = OutputBufferGuard::new(execution_uuid);
+
+	write_to_output_buffer(&execution_uuid, "out");
+	write_to_stderr_buffer(&execution_uuid, "err");
+	assert_eq!(Some(String::from("out")), guard.content());
+	assert_eq!(Some(String::from("err")), guard.stderr_content());
+
+	drop(guard);
+	assert_eq!(None, get_stderr_buffer(&execution_uuid));
+}
+
+#[test]
+fn output_is_truncated_at_its_maximum_size() {
+	let execution_uuid = Uuid::new_v4();
+	let guard = OutputBufferGuard::new(execution_uuid).with_max_output(8);
+
+	write_to_output_buffer(&execution_uuid, "line 1\n");
+	write_to_output_buffer(&execution_uuid, "line 2\n");
+	write_to_output_buffer(&execution_uuid, "line 3\n");
+	write_to_stderr_buffer(&execution_uuid, "short\n");
+
+	assert_eq!(
+		Some(format!("line 1\nl\n{OUTPUT_TRUNCATED_MARKER}\n")),
+		guard.content()
+	);
+	assert_eq!(Some(String::from("short\n")), guard.stderr_content());
+}
+
+#[test]
+fn truncation_keeps_whole_characters() {
+	let mut buffer = Buffer {
+		max_size: 2,
+		..Buffer::new()
+	};
+	buffer.push_str("aé");
+
+	assert_eq!(format!("a\n{OUTPUT_TRUNCATED_MARKER}\n"), buffer.output());
+}
+
+#[test]
+fn live_output_is_not_captured() {
+	let execution_uuid = Uuid::new_v4();
+	let guard = OutputBufferGuard::live(execution_uuid, "test_foo");
+
+	write_to_output_buffer(&execution_uuid, "out\n");
+	write_to_stderr_buffer(&execution_uuid, "err\n");
+	assert_eq!(None, guard.content());
+	assert_eq!(None, guard.stderr_content());
+
+	drop(guard);
+	assert_eq!(None, live_entrypoint(&execution_uuid));
+}
+
+#[test]
+fn live_lines_are_prefixed_with_the_entrypoint() {
+	let mut out = Vec::new();
+	write_live(&mut out, "test_foo", "x is 42\ny is 7\n");
+
+	assert_eq!(
+		"[test_foo] x is 42\n[test_foo] y is 7\n",
+		String::from_utf8(out).unwrap()
+	);
+}
+
+#[test]
+fn observer_is_notified_of_complete_lines() {
+	let lines = Arc::new(Mutex::new(Vec::new()));
+	let observed_lines = lines.clone();
+	let observer = OutputObserver::new(move |entrypoint, stream, line| {
+		observed_lines.lock().unwrap().push(format!("{entrypoint} {stream:?} {line}"))
+	});
+	let execution_uuid = Uuid::new_v4();
+	let guard = OutputBufferGuard::new(execution_uuid).with_observer(observer, "test_foo");
+
+	write_to_output_buffer(&execution_uuid, "x is");
+	assert!(lines.lock().unwrap().is_empty());
+	write_to_output_buffer(&execution_uuid, " 42\ny is 7\nz");
+	write_to_stderr_buffer(&execution_uuid, "err\n");
+	assert_eq!(
+		vec![
+			"test_foo Stdout x is 42",
+			"test_foo Stdout y is 7",
+			"test_foo Stderr err"
+		],
+		*lines.lock().unwrap()
+	);
+	// The output is still captured
+	assert_eq!(Some(String::from("x is 42\ny is 7\nz")), guard.content());
+
+	drop(guard);
+	assert_eq!(
+		Some(&String::from("test_foo Stdout z")),
+		lines.lock().unwrap().last()
+	);
+}
+
+#[test]
+fn execution_uuid_round_trips_through_exec_scopes() {
+	let execution_uuid = Uuid::new_v4();
+	let mut exec_scopes = ExecutionScopes::new();
+	exec_scopes.insert_value(
+		EXECUTION_UUID_VAR_NAME,
+		BigInt::from(execution_uuid.as_u128()),
+	);
+
+	assert_eq!(execution_uuid, get_execution_uuid(&exec_scopes).unwrap());
+}
+
+#[test]
+fn buffers_are_thread_safe() {
+	let handles: Vec<_> = (0..8)
+		.map(|i| {
+			thread::spawn(move || {
+				let execution_uuid = Uuid::new_v4();
+				let guard = OutputBufferGuard::new(execution_uuid);
+				for line in 0..100 {
+					write_to_output_buffer(&execution_uuid, &format!("{i}:{line}\n"));
+					write_to_stderr_buffer(&execution_uuid, &format!("{i}\n"));
+					thread::yield_now();
+				}
+
+				let expected: String = (0..100).map(|line| format!("{i}:{line}\n")).collect();
+				assert_eq!(Some(expected), guard.content());
+				assert_eq!(Some(format!("{i}\n").repeat(100)), guard.stderr_content());
+			})
+		})
+		.collect();
+
+	for handle in handles {
+		handle.join().expect("Buffer access should not panic");
+	}
+}
+
+#[test]
+fn buffers_survive_a_panicking_thread() {
+	let execution_uuid = Uuid::new_v4();
+	let guard = OutputBufferGuard::new(execution_uuid);
+
+	let _ = thread::spawn(|| {
+		let _lock = write_buffers(&HINT_OUTPUT_BUFFER);
+		panic!("poison the lock");
+	})
+	.join();
+
+	write_to_output_buffer(&execution_uuid, "foo");
+	assert_eq!(Some(String::from("foo")), guard.content());
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+
+use cairo_rs::{
+	hint_processor::{
+		builtin_hint_processor::hint_utils::get_integer_from_var_name,
+		hint_processor_definition::HintReference,
+	},
+	serde::deserialize_program::ApTracking,
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use num_bigint::BigInt;
+
+use super::output_buffer::{get_execution_uuid, write_to_output_buffer, write_to_stderr_buffer};
+use crate::io::felt_format::format_felt;
+
+/// Print values to the captured stdout of the test
+///
+/// Each argument is either a string literal, printed as is, or the name of a felt variable,
+/// whose value is printed (in the format selected with `--felt-format`).
+/// Arguments are separated by a space, and followed by a new line.
+///
+/// Returns nothing
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_print() {
+///     let x = 42;
+///     %{ print("x is", x) %}
+///     return ();
+/// }
+/// ```
+pub fn print(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let line = format_line(vm, ids_data, ap_tracking, args)?;
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	write_to_output_buffer(&execution_uuid, &line);
+	Ok(())
+}
+
+/// Print values to the captured stderr of the test
+///
+/// Same as `print`, but the values are written to the error output of the test, which is displayed
+/// in its own section.
+///
+/// Returns nothing
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_eprint() {
+///     let x = 42;
+///     %{ eprint("unexpected x:", x) %}
+///     return ();
+/// }
+/// ```
+pub fn eprint(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let line = format_line(vm, ids_data, ap_tracking, args)?;
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	write_to_stderr_buffer(&execution_uuid, &line);
+	Ok(())
+}
+
+/// Format the arguments of a print hint as a single line
+fn format_line(
+	vm: &VirtualMachine,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	args: &[String],
+) -> Result<String, VirtualMachineError> {
+	let mut values = Vec::new();
+	for arg in args.iter().filter(|arg| !arg.is_empty()) {
+		if arg.len() >= 2 && arg.starts_with('"') && arg.ends_with('"') {
+			values.push(arg[1..arg.len() - 1].to_string());
+		} else {
+			values.push(format_felt(&get_integer_from_var_name(
+				arg,
+				vm,
+				ids_data,
+				ap_tracking,
+			)?));
+		}
+	}
+	Ok(format!("{}\n", values.join(" ")))
+}
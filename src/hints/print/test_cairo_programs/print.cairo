@@ -0,0 +1,20 @@
+func test_print() {
+    let x = 42;
+    %{ print("x is", x) %}
+    return ();
+}
+
+func test_print_then_fail() {
+    let x = 42;
+    %{ print("x is", x) %}
+    assert x = 21;
+    return ();
+}
+
+func test_eprint() {
+    let x = 42;
+    let y = 21;
+    %{ print("x is", x) %}
+    %{ eprint("x should be", y) %}
+    return ();
+}
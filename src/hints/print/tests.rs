@@ -0,0 +1,30 @@
+use rstest::rstest;
+
+use crate::cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus};
+
+#[rstest]
+#[case("test_print", TestStatus::SUCCESS)]
+#[case("test_print_then_fail", TestStatus::FAILURE)]
+fn print(
+	#[case] test_name: &str,
+	#[case] expected_success: TestStatus,
+) -> Result<(), TestCommandError> {
+	let path = std::path::PathBuf::from("src/hints/print/test_cairo_programs/print.cairo");
+	let result = run_single_test(test_name, &path, 1000000).expect("Should be Ok");
+	assert_eq!(expected_success, result.success);
+	// The output is captured even if the test fails
+	assert_eq!(Some(String::from("x is 42\n")), result.captured_stdout);
+	Ok(())
+}
+
+#[test]
+fn eprint_is_captured_separately() {
+	let path = std::path::PathBuf::from("src/hints/print/test_cairo_programs/print.cairo");
+	let result = run_single_test("test_eprint", &path, 1000000).expect("Should be Ok");
+	assert_eq!(TestStatus::SUCCESS, result.success);
+	assert_eq!(Some(String::from("x is 42\n")), result.captured_stdout);
+	assert_eq!(
+		Some(String::from("x should be 21\n")),
+		result.captured_stderr
+	);
+}
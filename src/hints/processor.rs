@@ -1,18 +1,95 @@
 use std::rc::Rc;
 
-use crate::{
-	hints,
-	hints::hint_processor::function_like_hint_processor::{FunctionLikeHintProcessor, HintFunc},
+use crate::hints::{
+	self, custom,
+	hint_processor::function_like_hint_processor::{FunctionLikeHintProcessor, HintFunc},
 };
 
-/// Create, setup and return a HintProcessor supporting our custom hints
+/// Create, setup and return a HintProcessor supporting our custom hints, and the ones registered
+/// with `register_hint`
 pub fn setup_hint_processor() -> FunctionLikeHintProcessor {
 	let skip_hint = Rc::new(HintFunc(Box::new(hints::skip)));
 	let mock_call_hint = Rc::new(HintFunc(Box::new(hints::mock_call)));
 	let expect_revert_hint = Rc::new(HintFunc(Box::new(hints::expect_revert)));
+	let start_expect_revert_hint = Rc::new(HintFunc(Box::new(hints::start_expect_revert)));
+	let stop_expect_revert_hint = Rc::new(HintFunc(Box::new(hints::stop_expect_revert)));
+	let print_hint = Rc::new(HintFunc(Box::new(hints::print)));
+	let eprint_hint = Rc::new(HintFunc(Box::new(hints::eprint)));
+	let assert_eq_hint = Rc::new(HintFunc(Box::new(hints::assert_eq)));
+	let assert_lt_hint = Rc::new(HintFunc(Box::new(hints::assert_lt)));
+	let assert_le_hint = Rc::new(HintFunc(Box::new(hints::assert_le)));
+	let assert_in_range_hint = Rc::new(HintFunc(Box::new(hints::assert_in_range)));
+	let assert_array_eq_hint = Rc::new(HintFunc(Box::new(hints::assert_array_eq)));
+	let set_max_steps_hint = Rc::new(HintFunc(Box::new(hints::set_max_steps)));
+	let warp_hint = Rc::new(HintFunc(Box::new(hints::warp)));
+	let get_block_timestamp_hint = Rc::new(HintFunc(Box::new(hints::get_block_timestamp)));
+	let roll_hint = Rc::new(HintFunc(Box::new(hints::roll)));
+	let get_block_number_hint = Rc::new(HintFunc(Box::new(hints::get_block_number)));
+	let prank_hint = Rc::new(HintFunc(Box::new(hints::prank)));
+	let stop_prank_hint = Rc::new(HintFunc(Box::new(hints::stop_prank)));
+	let get_caller_address_hint = Rc::new(HintFunc(Box::new(hints::get_caller_address)));
+	let mock_contract_call_hint = Rc::new(HintFunc(Box::new(hints::mock_contract_call)));
+	let call_contract_hint = Rc::new(HintFunc(Box::new(hints::call_contract)));
+	let clear_mocks_hint = Rc::new(HintFunc(Box::new(hints::clear_mocks)));
+	let store_hint = Rc::new(HintFunc(Box::new(hints::store)));
+	let load_hint = Rc::new(HintFunc(Box::new(hints::load)));
+	let emit_event_hint = Rc::new(HintFunc(Box::new(hints::emit_event)));
+	let expect_emit_hint = Rc::new(HintFunc(Box::new(hints::expect_emit)));
+	let env_var_hint = Rc::new(HintFunc(Box::new(hints::env_var)));
+	let rand_hint = Rc::new(HintFunc(Box::new(hints::rand)));
+	let panic_hint = Rc::new(HintFunc(Box::new(hints::panic)));
+	let assert_revert_with_selector_hint =
+		Rc::new(HintFunc(Box::new(hints::assert_revert_with_selector)));
 	let mut hint_processor = FunctionLikeHintProcessor::new_empty();
 	hint_processor.add_hint(String::from("skip"), skip_hint);
 	hint_processor.add_hint(String::from("expect_revert"), expect_revert_hint);
+	hint_processor.add_hint(
+		String::from("start_expect_revert"),
+		start_expect_revert_hint,
+	);
+	hint_processor.add_hint(String::from("stop_expect_revert"), stop_expect_revert_hint);
 	hint_processor.add_hint(String::from("mock_call"), mock_call_hint);
+	hint_processor.add_hint(String::from("print"), print_hint);
+	hint_processor.add_hint(String::from("eprint"), eprint_hint);
+	hint_processor.add_hint(String::from("assert_eq"), assert_eq_hint);
+	hint_processor.add_hint(String::from("assert_lt"), assert_lt_hint);
+	hint_processor.add_hint(String::from("assert_le"), assert_le_hint);
+	hint_processor.add_hint(String::from("assert_in_range"), assert_in_range_hint);
+	hint_processor.add_hint(String::from("assert_array_eq"), assert_array_eq_hint);
+	hint_processor.add_hint(String::from("set_max_steps"), set_max_steps_hint);
+	hint_processor.add_hint(String::from("warp"), warp_hint);
+	hint_processor.add_hint(
+		String::from("get_block_timestamp"),
+		get_block_timestamp_hint,
+	);
+	hint_processor.add_hint(String::from("roll"), roll_hint);
+	hint_processor.add_hint(String::from("get_block_number"), get_block_number_hint);
+	hint_processor.add_hint(String::from("prank"), prank_hint);
+	hint_processor.add_hint(String::from("stop_prank"), stop_prank_hint);
+	hint_processor.add_hint(String::from("get_caller_address"), get_caller_address_hint);
+	hint_processor.add_hint(String::from("mock_contract_call"), mock_contract_call_hint);
+	hint_processor.add_hint(String::from("call_contract"), call_contract_hint);
+	hint_processor.add_hint(String::from("clear_mocks"), clear_mocks_hint);
+	hint_processor.add_hint(String::from("store"), store_hint);
+	hint_processor.add_hint(String::from("load"), load_hint);
+	hint_processor.add_hint(String::from("emit_event"), emit_event_hint);
+	hint_processor.add_hint(String::from("expect_emit"), expect_emit_hint);
+	hint_processor.add_hint(String::from("env_var"), env_var_hint);
+	hint_processor.add_hint(String::from("rand"), rand_hint);
+	hint_processor.add_hint(String::from("panic"), panic_hint);
+	hint_processor.add_hint(
+		String::from("assert_revert_with_selector"),
+		assert_revert_with_selector_hint,
+	);
+	// Added last, so that they take precedence over the built-in hints
+	for hint in custom::registered_hints() {
+		let name = hint.name().to_string();
+		let hint_func = Rc::new(HintFunc(Box::new(
+			move |vm, exec_scopes, ids_data, ap_tracking, constants, args| {
+				hint.execute(vm, exec_scopes, ids_data, ap_tracking, constants, args)
+			},
+		)));
+		hint_processor.add_hint(name, hint_func);
+	}
 	hint_processor
 }
@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod tests;
+
+use std::{
+	collections::HashMap,
+	sync::{PoisonError, RwLock},
+};
+
+use cairo_rs::{
+	hint_processor::{
+		builtin_hint_processor::hint_utils::insert_value_from_var_name,
+		hint_processor_definition::HintReference,
+	},
+	serde::deserialize_program::ApTracking,
+	types::exec_scope::ExecutionScopes,
+	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
+};
+use lazy_static::lazy_static;
+use num_bigint::{BigInt, Sign};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use uuid::Uuid;
+
+use super::{args::single_arg, output_buffer::get_execution_uuid};
+use crate::fuzz::FIELD_PRIME;
+
+/// Generator of the numbers drawn by the `rand` hint during an execution
+struct RandomState {
+	seed: u64,
+	rng: StdRng,
+	/// Whether `rand` has been called, so that the seed is only reported when it matters
+	used: bool,
+}
+
+lazy_static! {
+	/// the `RANDOM_STATES` hashmap stores the generator of each execution, keyed by the Uuid of
+	/// the execution (see `output_buffer`). Entries are removed along with the output buffer.
+	static ref RANDOM_STATES: RwLock<HashMap<Uuid, RandomState>> = RwLock::new(HashMap::new());
+}
+
+/// Seed of the numbers drawn by a test, derived from the seed of the run and the name of the test
+/// entrypoint, so that adding a test does not change the numbers drawn by the other ones.
+pub fn test_seed(run_seed: u64, entrypoint: &str) -> u64 {
+	// FNV-1a, which (unlike the hasher of the standard library) is stable across releases
+	entrypoint.bytes().fold(run_seed ^ 0xcbf2_9ce4_8422_2325, |hash, byte| {
+		(hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+	})
+}
+
+/// Seed the generator of an execution, before it starts
+pub fn seed_random(execution_uuid: Uuid, seed: u64) {
+	RANDOM_STATES.write().unwrap_or_else(PoisonError::into_inner).insert(
+		execution_uuid,
+		RandomState {
+			seed,
+			rng: StdRng::seed_from_u64(seed),
+			used: false,
+		},
+	);
+}
+
+/// Remove the generator of an execution.
+///
+/// Returns its seed if the execution drew a number with `rand`
+pub fn clear_random(execution_uuid: &Uuid) -> Option<u64> {
+	RANDOM_STATES
+		.write()
+		.unwrap_or_else(PoisonError::into_inner)
+		.remove(execution_uuid)
+		.filter(|state| state.used)
+		.map(|state| state.seed)
+}
+
+/// Draw a felt uniformly distributed in [0, P)
+fn uniform_felt(rng: &mut impl Rng) -> BigInt {
+	loop {
+		let mut bytes = [0u8; 32];
+		rng.fill(&mut bytes);
+		// Keep 252 bits, the values above the prime (about half of them) are drawn again
+		bytes[0] &= 0x0f;
+		let felt = BigInt::from_bytes_be(Sign::Plus, &bytes);
+		if felt < *FIELD_PRIME {
+			return felt
+		}
+	}
+}
+
+/// Write a pseudo-random felt into a felt variable
+///
+/// The numbers are drawn from a generator seeded for each test from the seed of the run (printed
+/// at the start of the run, and given with `--seed` to replay it), so that a test draws the same
+/// sequence of numbers every time it is run with the same seed.
+///
+/// Returns nothing
+///
+/// # Examples
+///
+/// Basic usage in a `.cairo` file:
+///
+/// ```cairo
+/// func test_rand() {
+///     alloc_locals;
+///     local x;
+///     %{ rand(x) %}
+///     %{ print("drew", x) %}
+///     return ();
+/// }
+/// ```
+pub fn rand(
+	vm: &mut VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+	ids_data: &HashMap<String, HintReference>,
+	ap_tracking: &ApTracking,
+	_constants: &HashMap<String, BigInt>,
+	args: &[String],
+) -> Result<(), VirtualMachineError> {
+	let var_name = single_arg("rand", args)?;
+	let execution_uuid = get_execution_uuid(exec_scopes)?;
+	let value = {
+		let mut states = RANDOM_STATES.write().unwrap_or_else(PoisonError::into_inner);
+		// An execution which has not been seeded by the test runner draws from the seed 0
+		let state = states.entry(execution_uuid).or_insert_with(|| RandomState {
+			seed: 0,
+			rng: StdRng::seed_from_u64(0),
+			used: false,
+		});
+		state.used = true;
+		uniform_felt(&mut state.rng)
+	};
+	insert_value_from_var_name(var_name, value, vm, ids_data, ap_tracking)
+}
@@ -0,0 +1,21 @@
+func test_rand() {
+    alloc_locals;
+    local a;
+    local b;
+    %{ rand(a) %}
+    %{ rand(b) %}
+    %{ print(a, b) %}
+    return ();
+}
+
+func test_rand_other_test() {
+    alloc_locals;
+    local a;
+    %{ rand(a) %}
+    %{ print(a) %}
+    return ();
+}
+
+func test_without_rand() {
+    return ();
+}
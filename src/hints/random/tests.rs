@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use crate::cli::commands::test::{tests::run_test_file, TestOptions, TestResult};
+
+use super::test_seed;
+
+fn run_rand_tests(random_seed: u64) -> Vec<TestResult> {
+	let path = PathBuf::from("src/hints/random/test_cairo_programs/rand.cairo");
+	let options = TestOptions {
+		random_seed,
+		..Default::default()
+	};
+	run_test_file(&path, &options).unwrap().expect("Should contain tests").tests
+}
+
+fn drawn_numbers(random_seed: u64) -> Vec<Option<String>> {
+	run_rand_tests(random_seed)
+		.into_iter()
+		.map(|test| test.captured_stdout)
+		.collect()
+}
+
+#[test]
+fn same_seed_draws_the_same_numbers() {
+	let numbers = drawn_numbers(42);
+	assert_eq!(numbers, drawn_numbers(42));
+	assert_ne!(numbers, drawn_numbers(43));
+
+	// Each test has its own sequence
+	assert_ne!(
+		numbers[0].as_deref().and_then(|line| line.split(' ').next()),
+		numbers[1].as_deref().map(str::trim_end)
+	);
+}
+
+#[test]
+fn seed_is_only_reported_for_the_tests_drawing_numbers() {
+	let results = run_rand_tests(42);
+
+	assert_eq!(
+		vec![
+			Some(test_seed(42, "test_rand")),
+			Some(test_seed(42, "test_rand_other_test")),
+			None
+		],
+		results.iter().map(|test| test.random_seed).collect::<Vec<_>>()
+	);
+}
+
+#[test]
+fn test_seed_depends_on_the_entrypoint() {
+	assert_eq!(test_seed(1, "test_a"), test_seed(1, "test_a"));
+	assert_ne!(test_seed(1, "test_a"), test_seed(1, "test_b"));
+	assert_ne!(test_seed(1, "test_a"), test_seed(2, "test_a"));
+}
@@ -0,0 +1,112 @@
+use std::{
+	collections::{BTreeMap, HashMap},
+	fmt::Write,
+	sync::{Arc, Mutex},
+};
+
+/// Number of executions of each instruction of a program, by pc
+#[derive(Debug, Default)]
+pub struct Coverage {
+	hits: HashMap<usize, u64>,
+}
+
+/// A coverage shared between the hooks recording it and the test runner reporting it
+pub type SharedCoverage = Arc<Mutex<Coverage>>;
+
+/// Number of executions of each line, by source file.
+/// Lines of instructions which have never been executed have a count of 0.
+pub type LineCoverage = BTreeMap<String, BTreeMap<usize, u64>>;
+
+impl Coverage {
+	/// Record an execution of the instruction at `pc`
+	pub fn record(&mut self, pc: usize) {
+		*self.hits.entry(pc).or_default() += 1;
+	}
+
+	/// Map the executed instructions to their source lines.
+	/// `instruction_locations` are the file and line of each instruction, by pc.
+	///
+	/// A line holding several instructions counts the executions of the most executed one.
+	pub fn line_coverage(
+		&self,
+		instruction_locations: &HashMap<usize, (String, usize)>,
+	) -> LineCoverage {
+		let mut line_coverage = LineCoverage::new();
+		for (pc, (file, line)) in instruction_locations {
+			let hits = self.hits.get(pc).copied().unwrap_or_default();
+			let count = line_coverage.entry(file.clone()).or_default().entry(*line).or_default();
+			*count = (*count).max(hits);
+		}
+		line_coverage
+	}
+}
+
+/// Add the counts of `other` to `coverage`
+pub fn merge_line_coverage(coverage: &mut LineCoverage, other: &LineCoverage) {
+	for (file, lines) in other {
+		let file_coverage = coverage.entry(file.clone()).or_default();
+		for (line, hits) in lines {
+			*file_coverage.entry(*line).or_default() += hits;
+		}
+	}
+}
+
+/// Format a coverage as an lcov tracefile, one record per source file
+pub fn to_lcov(coverage: &LineCoverage) -> String {
+	let mut lcov = String::new();
+	// Writing to a String cannot fail
+	for (file, lines) in coverage {
+		let _ = writeln!(lcov, "SF:{file}");
+		for (line, hits) in lines {
+			let _ = writeln!(lcov, "DA:{line},{hits}");
+		}
+		let _ = writeln!(lcov, "LF:{}", lines.len());
+		let _ = writeln!(
+			lcov,
+			"LH:{}",
+			lines.values().filter(|hits| **hits > 0).count()
+		);
+		let _ = writeln!(lcov, "end_of_record");
+	}
+	lcov
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn unexecuted_lines_have_a_zero_count() {
+		let mut coverage = Coverage::default();
+		coverage.record(0);
+		coverage.record(0);
+		coverage.record(1);
+		let locations = HashMap::from([
+			(0, (String::from("test_foo.cairo"), 2)),
+			(1, (String::from("test_foo.cairo"), 2)),
+			(2, (String::from("test_foo.cairo"), 5)),
+		]);
+
+		let line_coverage = coverage.line_coverage(&locations);
+
+		assert_eq!(
+			"SF:test_foo.cairo\nDA:2,2\nDA:5,0\nLF:2\nLH:1\nend_of_record\n",
+			to_lcov(&line_coverage)
+		);
+	}
+
+	#[test]
+	fn coverages_are_merged_by_file_and_line() {
+		let mut coverage =
+			LineCoverage::from([(String::from("lib.cairo"), BTreeMap::from([(1, 1), (2, 0)]))]);
+		let other = LineCoverage::from([
+			(String::from("lib.cairo"), BTreeMap::from([(2, 3)])),
+			(String::from("test_foo.cairo"), BTreeMap::from([(4, 1)])),
+		]);
+
+		merge_line_coverage(&mut coverage, &other);
+
+		assert_eq!(Some(&3), coverage["lib.cairo"].get(&2));
+		assert_eq!(Some(&1), coverage["test_foo.cairo"].get(&4));
+	}
+}
@@ -1,18 +1,96 @@
 #[cfg(test)]
 mod tests;
 
-use std::{collections::HashMap, ops::Add};
+pub mod coverage;
+pub mod profile;
+
+use std::{
+	collections::HashMap,
+	ops::Add,
+	time::{Duration, Instant},
+};
 
 use cairo_rs::{
 	types::{exec_scope::ExecutionScopes, instruction::Opcode},
 	vm::{errors::vm_errors::VirtualMachineError, vm_core::VirtualMachine},
 };
+use log::{debug, trace};
 use num_bigint::BigInt;
 
-use crate::hints::MOCK_CALL_KEY;
+use crate::hints::{MockCalls, MOCK_CALL_KEY};
 
 pub const HOOKS_VAR_NAME: &str = "hooks";
 pub const MAX_STEPS_VAR_NAME: &str = "max_steps";
+pub const DEADLINE_VAR_NAME: &str = "deadline";
+pub const STAGNATION_VAR_NAME: &str = "stagnation";
+pub const TIMEOUT_ERROR_PREFIX: &str = "timed out after";
+pub const MAX_STEPS_ERROR_PREFIX: &str = "max_steps reached";
+pub const STAGNATION_ERROR_PREFIX: &str = "possible infinite loop";
+
+/// Wall-clock time limit of an execution
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline {
+	/// When the execution started
+	pub start: Instant,
+	/// How long the execution is allowed to run
+	pub timeout: Duration,
+}
+
+impl Deadline {
+	pub fn is_reached(&self) -> bool {
+		self.start.elapsed() >= self.timeout
+	}
+
+	/// The error message of an execution which reached this deadline
+	pub fn error_message(&self) -> String {
+		format!("{TIMEOUT_ERROR_PREFIX} {}s", self.timeout.as_secs_f64())
+	}
+}
+
+/// Detection of an execution stuck in a loop which does not change its state.
+///
+/// The memory of the VM being write-once, an execution whose registers (pc, ap and fp) come back
+/// to the same values keeps on executing the same instructions, unless a hint intervenes. The
+/// execution is considered stuck when the registers come back to the same values `window` times
+/// in a row, each time at most `window` steps after the previous one.
+#[derive(Debug, Clone)]
+pub struct Stagnation {
+	window: usize,
+	/// Registers the next ones are compared against
+	checkpoint: Option<(usize, usize, usize)>,
+	steps_since_checkpoint: usize,
+	repeats: usize,
+}
+
+impl Stagnation {
+	pub fn new(window: usize) -> Self {
+		Self {
+			window,
+			checkpoint: None,
+			steps_since_checkpoint: 0,
+			repeats: 0,
+		}
+	}
+
+	/// Record the registers (pc, ap, fp) of the next step.
+	///
+	/// Returns true if the execution is considered stuck
+	pub fn record(&mut self, registers: (usize, usize, usize)) -> bool {
+		if self.checkpoint == Some(registers) {
+			self.repeats += 1;
+			self.steps_since_checkpoint = 0;
+			return self.repeats >= self.window
+		}
+		self.steps_since_checkpoint += 1;
+		// Either the execution left the loop, or its period is longer than the window
+		if self.checkpoint.is_none() || self.steps_since_checkpoint > self.window {
+			self.checkpoint = Some(registers);
+			self.steps_since_checkpoint = 0;
+			self.repeats = 0;
+		}
+		false
+	}
+}
 
 /// Called before an instruction is executed by the virtual machine (VM).
 ///
@@ -31,8 +109,16 @@ pub fn pre_step_instruction(
 	_constants: &HashMap<String, BigInt>,
 ) -> Result<(), VirtualMachineError> {
 	let instruction = vm.decode_current_instruction()?;
+	trace!(
+		"step {} at pc {}: {:?}",
+		vm.get_current_step(),
+		vm.get_pc().offset,
+		instruction.opcode
+	);
 
 	ensure_max_steps_not_reached(vm, exec_scopes)?;
+	ensure_deadline_not_reached(exec_scopes)?;
+	ensure_not_stagnating(vm, exec_scopes)?;
 
 	if instruction.opcode == Opcode::Call {
 		let (operands, _operands_mem_addresses, _deduced_operands) =
@@ -42,12 +128,16 @@ pub fn pre_step_instruction(
 
 		let mocks = exec_scopes
 			.get_any_boxed_mut(MOCK_CALL_KEY)?
-			.downcast_mut::<HashMap<usize, BigInt>>()
+			.downcast_mut::<MockCalls>()
 			.ok_or_else(|| {
 				VirtualMachineError::VariableNotInScopeError(MOCK_CALL_KEY.to_string())
 			})?;
 
 		if let Some(mocked_ret_value) = mocks.get(&new_pc.offset) {
+			debug!(
+				"mocked call to pc {}, returning {mocked_ret_value}",
+				new_pc.offset
+			);
 			let pc = *vm.get_pc();
 			let ap = vm.get_ap();
 			vm.insert_value(&ap, mocked_ret_value)?;
@@ -76,6 +166,10 @@ pub fn post_step_instruction(
 	Ok(())
 }
 
+/// Fails if the execution reached its maximum number of steps.
+///
+/// The error message is `MAX_STEPS_ERROR_PREFIX` followed by the number of executed steps (which
+/// is the limit).
 pub fn ensure_max_steps_not_reached(
 	vm: &mut VirtualMachine,
 	exec_scopes: &mut ExecutionScopes,
@@ -83,10 +177,48 @@ pub fn ensure_max_steps_not_reached(
 	if *vm.get_current_step() >= exec_scopes.get::<u64>(MAX_STEPS_VAR_NAME)? as usize {
 		// TODO: find a better way to express custom errors
 		Err(VirtualMachineError::CustomHint(format!(
-			"max_steps reached: {}",
+			"{MAX_STEPS_ERROR_PREFIX}: {}",
 			*vm.get_current_step()
 		)))
 	} else {
 		Ok(())
 	}
 }
+
+/// Fails if the execution has a `Deadline` and it has been reached.
+/// It is checked before each step, a hint which never returns is caught by the test runner
+/// instead (see `test_single_entrypoint`)
+pub fn ensure_deadline_not_reached(
+	exec_scopes: &mut ExecutionScopes,
+) -> Result<(), VirtualMachineError> {
+	match exec_scopes.get::<Deadline>(DEADLINE_VAR_NAME) {
+		Ok(deadline) if deadline.is_reached() =>
+			Err(VirtualMachineError::CustomHint(deadline.error_message())),
+		_ => Ok(()),
+	}
+}
+
+/// Fails if the execution has a `Stagnation` detection and it considers the execution stuck.
+///
+/// The error message is `STAGNATION_ERROR_PREFIX` followed by the pc of the loop.
+pub fn ensure_not_stagnating(
+	vm: &VirtualMachine,
+	exec_scopes: &mut ExecutionScopes,
+) -> Result<(), VirtualMachineError> {
+	let stagnation = match exec_scopes.get_any_boxed_mut(STAGNATION_VAR_NAME) {
+		Ok(stagnation) => stagnation.downcast_mut::<Stagnation>().ok_or_else(|| {
+			VirtualMachineError::VariableNotInScopeError(STAGNATION_VAR_NAME.to_string())
+		})?,
+		Err(_) => return Ok(()),
+	};
+	let pc = vm.get_pc().offset;
+	if stagnation.record((pc, vm.get_ap().offset, vm.get_fp().offset)) {
+		Err(VirtualMachineError::CustomHint(format!(
+			"{STAGNATION_ERROR_PREFIX} at pc {pc}: the registers came back to the same values {} \
+			 times in a row",
+			stagnation.window
+		)))
+	} else {
+		Ok(())
+	}
+}
@@ -0,0 +1,121 @@
+use std::{
+	collections::BTreeMap,
+	fmt::Write,
+	fs, io,
+	path::Path,
+	sync::{Arc, Mutex},
+};
+
+use cairo_rs::{
+	types::{program::Program, relocatable::Relocatable},
+	vm::vm_core::VirtualMachine,
+};
+
+/// Segment of the program, in which the program counter points
+const PROGRAM_SEGMENT_INDEX: isize = 0;
+/// Segment of the execution, in which the frames of the functions are allocated
+const EXECUTION_SEGMENT_INDEX: isize = 1;
+
+/// Profile of an execution: the number of steps executed under each call stack.
+///
+/// It is written in the folded stack format (`main;foo;bar 42`), which flamegraph tools (such as
+/// `inferno-flamegraph` or `flamegraph.pl`) consume.
+#[derive(Debug, Default)]
+pub struct Profile {
+	/// Name of the functions of the program, by pc of their first instruction
+	functions: BTreeMap<usize, String>,
+	/// Number of steps by call stack, each frame being the pc of a function (outermost first)
+	samples: BTreeMap<Vec<usize>, u64>,
+}
+
+/// A profile shared between the hooks recording it and the test runner writing it
+pub type SharedProfile = Arc<Mutex<Profile>>;
+
+impl Profile {
+	pub fn new(program: &Program) -> Self {
+		let functions = program
+			.identifiers
+			.iter()
+			.filter(|(_, identifier)| identifier.type_.as_deref() == Some("function"))
+			.filter_map(|(name, identifier)| {
+				let name = name.strip_prefix("__main__.").unwrap_or(name);
+				identifier.pc.map(|pc| (pc, name.to_string()))
+			})
+			.collect();
+		Self {
+			functions,
+			samples: BTreeMap::new(),
+		}
+	}
+
+	/// Pc of the function containing the instruction at `pc`
+	fn function_at(&self, pc: usize) -> usize {
+		self.functions.range(..=pc).next_back().map_or(pc, |(start, _)| *start)
+	}
+
+	/// Record a step under the current call stack of the `vm`.
+	///
+	/// The stack is rebuilt by walking the frames from the current one: `[fp - 1]` is the return
+	/// pc of a frame, and `[fp - 2]` the frame pointer of its caller.
+	pub fn sample(&mut self, vm: &VirtualMachine) {
+		let mut stack = vec![self.function_at(vm.get_pc().offset)];
+		let mut fp = *vm.get_fp();
+		while fp.segment_index == EXECUTION_SEGMENT_INDEX && fp.offset >= 2 {
+			let return_pc =
+				match vm.get_relocatable(&Relocatable::from((fp.segment_index, fp.offset - 1))) {
+					Ok(return_pc) if return_pc.segment_index == PROGRAM_SEGMENT_INDEX =>
+						return_pc.into_owned(),
+					_ => break,
+				};
+			// The return pc is the instruction following the call
+			stack.push(self.function_at(return_pc.offset.saturating_sub(1)));
+			fp = match vm.get_relocatable(&Relocatable::from((fp.segment_index, fp.offset - 2))) {
+				Ok(caller_fp) => caller_fp.into_owned(),
+				Err(_) => break,
+			};
+		}
+		stack.reverse();
+		*self.samples.entry(stack).or_default() += 1;
+	}
+
+	/// The profile in the folded stack format, one line per call stack
+	pub fn to_folded(&self) -> String {
+		let name =
+			|pc: &usize| self.functions.get(pc).cloned().unwrap_or_else(|| format!("pc_{pc}"));
+		let mut folded = String::new();
+		for (stack, steps) in &self.samples {
+			let frames: Vec<String> = stack.iter().map(name).collect();
+			// Writing to a String cannot fail
+			let _ = writeln!(folded, "{} {steps}", frames.join(";"));
+		}
+		folded
+	}
+
+	/// Write the profile to `path`, creating its parent directories
+	pub fn write(&self, path: &Path) -> Result<(), io::Error> {
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		fs::write(path, self.to_folded())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn samples_are_folded_by_function_name() {
+		let mut profile = Profile {
+			functions: BTreeMap::from([(0, String::from("helper")), (4, String::from("test_foo"))]),
+			samples: BTreeMap::new(),
+		};
+		assert_eq!(0, profile.function_at(3));
+		assert_eq!(4, profile.function_at(9));
+
+		profile.samples.insert(vec![4], 3);
+		profile.samples.insert(vec![4, 0], 2);
+
+		assert_eq!("test_foo 3\ntest_foo;helper 2\n", profile.to_folded());
+	}
+}
@@ -0,0 +1,5 @@
+// Infinite loop which does not change the registers
+func test_tight_loop() {
+    loop:
+    jmp loop;
+}
@@ -1,5 +1,12 @@
+use std::time::{Duration, Instant};
+
+use assert_matches::assert_matches;
+use cairo_rs::{types::exec_scope::ExecutionScopes, vm::errors::vm_errors::VirtualMachineError};
 use rstest::rstest;
 
+use super::{
+	ensure_deadline_not_reached, Deadline, Stagnation, DEADLINE_VAR_NAME, TIMEOUT_ERROR_PREFIX,
+};
 use crate::cli::commands::test::{tests::run_single_test, TestCommandError, TestStatus};
 
 #[rstest]
@@ -18,3 +25,54 @@ fn test_infinite_loop(
 	assert_eq!(expected_success, result);
 	Ok(())
 }
+
+#[rstest]
+#[case(Duration::ZERO, true)]
+#[case(Duration::from_secs(3600), false)]
+fn test_deadline(#[case] timeout: Duration, #[case] should_time_out: bool) {
+	let mut exec_scopes = ExecutionScopes::new();
+	exec_scopes.insert_value(
+		DEADLINE_VAR_NAME,
+		Deadline {
+			start: Instant::now(),
+			timeout,
+		},
+	);
+
+	let result = ensure_deadline_not_reached(&mut exec_scopes);
+
+	if should_time_out {
+		assert_matches!(result, Err(VirtualMachineError::CustomHint(message)) if message.starts_with(TIMEOUT_ERROR_PREFIX));
+	} else {
+		assert_matches!(result, Ok(()));
+	}
+}
+
+#[test]
+fn test_without_deadline() {
+	assert_matches!(
+		ensure_deadline_not_reached(&mut ExecutionScopes::new()),
+		Ok(())
+	);
+}
+
+#[test]
+fn repeated_registers_are_a_stagnation() {
+	let mut stagnation = Stagnation::new(3);
+
+	// A loop of two instructions which does not change ap nor fp
+	let stuck = (0..10)
+		.map(|step| stagnation.record((step % 2, 10, 10)))
+		.position(|stuck| stuck);
+
+	// The first state is the checkpoint, it comes back at steps 2, 4 and 6
+	assert_eq!(Some(6), stuck);
+}
+
+#[test]
+fn progressing_execution_is_not_a_stagnation() {
+	let mut stagnation = Stagnation::new(3);
+
+	// A recursion, ap grows at each iteration
+	assert!((0..100).all(|step| !stagnation.record((step % 2, 10 + step, 10))));
+}
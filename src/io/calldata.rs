@@ -0,0 +1,271 @@
+use num_bigint::BigInt;
+use serde_json::Value;
+use std::{
+	collections::HashMap,
+	fs, io,
+	path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// Arguments given to test entrypoints, by entrypoint name
+pub type Calldata = HashMap<String, Vec<BigInt>>;
+
+/// Arguments of a table-driven test, the entrypoint is executed once per row
+pub type DataRows = Vec<Vec<BigInt>>;
+
+/// Storage preloaded before each test, by (contract address, storage slot)
+pub type SeedStorage = HashMap<(BigInt, BigInt), BigInt>;
+
+#[derive(Error, Debug)]
+pub enum CalldataError {
+	#[error("Failed to read the calldata file '{0}': {1}")]
+	FailedToReadFile(PathBuf, io::Error),
+	#[error("Failed to parse the calldata file '{0}' as json: {1}")]
+	InvalidFormat(PathBuf, serde_json::Error),
+	#[error("The calldata file must map entrypoint names to lists of felts, got {0} for '{1}'")]
+	NotAList(Value, String),
+	#[error("Invalid felt {0} in the calldata of '{1}'")]
+	InvalidFelt(Value, String),
+	#[error("Row {0} of the data file '{1}' is not a list of felts")]
+	NotARow(usize, PathBuf),
+	#[error("Invalid felt {0} in row {1} of the data file '{2}'")]
+	InvalidRowFelt(String, usize, PathBuf),
+	#[error(
+		"The storage file must map contract addresses to maps of slots to felts, got {0} for '{1}'"
+	)]
+	NotAStorageMap(Value, String),
+	#[error("Invalid felt {0} in the storage of contract '{1}'")]
+	InvalidStorageFelt(Value, String),
+}
+
+/// Read a json file mapping entrypoint names to their arguments.
+/// A felt is either a json integer or a string (decimal, or hexadecimal with a `0x` prefix).
+///
+/// ```json
+/// { "test_transfer": [42, "0x123", "-1"] }
+/// ```
+pub fn read_calldata_file(path: &str) -> Result<Calldata, CalldataError> {
+	let path = PathBuf::from(path);
+	let data = fs::read_to_string(&path)
+		.map_err(|err| CalldataError::FailedToReadFile(path.clone(), err))?;
+	let json: HashMap<String, Value> =
+		serde_json::from_str(&data).map_err(|err| CalldataError::InvalidFormat(path, err))?;
+	json.into_iter()
+		.map(|(entrypoint, args)| {
+			let args = match args {
+				Value::Array(args) => args,
+				other => return Err(CalldataError::NotAList(other, entrypoint)),
+			};
+			let felts = args
+				.into_iter()
+				.map(|arg| {
+					parse_felt(&arg)
+						.ok_or_else(|| CalldataError::InvalidFelt(arg, entrypoint.clone()))
+				})
+				.collect::<Result<_, _>>()?;
+			Ok((entrypoint, felts))
+		})
+		.collect()
+}
+
+/// Read a json file mapping contract addresses to their storage, itself mapping storage slots to
+/// their value. Addresses and slots are felts written as strings, values are felts as in the
+/// calldata file.
+///
+/// ```json
+/// { "0x123": { "0x1": 42, "0x2": "0x10" } }
+/// ```
+pub fn read_storage_file(path: &str) -> Result<SeedStorage, CalldataError> {
+	let path = PathBuf::from(path);
+	let data = fs::read_to_string(&path)
+		.map_err(|err| CalldataError::FailedToReadFile(path.clone(), err))?;
+	let json: HashMap<String, Value> =
+		serde_json::from_str(&data).map_err(|err| CalldataError::InvalidFormat(path, err))?;
+	let mut storage = SeedStorage::new();
+	for (address, slots) in json {
+		let parsed_address = parse_felt(&Value::String(address.clone())).ok_or_else(|| {
+			CalldataError::InvalidStorageFelt(Value::String(address.clone()), address.clone())
+		})?;
+		let slots = match slots {
+			Value::Object(slots) => slots,
+			other => return Err(CalldataError::NotAStorageMap(other, address)),
+		};
+		for (slot, value) in slots {
+			let slot = parse_felt(&Value::String(slot.clone())).ok_or_else(|| {
+				CalldataError::InvalidStorageFelt(Value::String(slot), address.clone())
+			})?;
+			let value = parse_felt(&value)
+				.ok_or_else(|| CalldataError::InvalidStorageFelt(value, address.clone()))?;
+			storage.insert((parsed_address.clone(), slot), value);
+		}
+	}
+	Ok(storage)
+}
+
+/// Extensions of the data files of table-driven tests, by order of precedence
+const DATA_FILE_EXTENSIONS: [&str; 2] = ["json", "csv"];
+
+/// Path of the data file of a table-driven test, next to its cairo file, if it exists.
+/// eg: `tests/test_foo.test_bar.json` (or `.csv`) for the `test_bar` entrypoint of
+/// `tests/test_foo.cairo`
+pub fn data_file_path(path_to_original: &Path, entrypoint: &str) -> Option<PathBuf> {
+	let stem = path_to_original.file_stem().unwrap_or_default().to_string_lossy();
+	DATA_FILE_EXTENSIONS
+		.iter()
+		.map(|extension| {
+			path_to_original.with_file_name(format!("{stem}.{entrypoint}.{extension}"))
+		})
+		.find(|path| path.is_file())
+}
+
+/// Read the rows of arguments of a table-driven test, felts being parsed as in the calldata file.
+///
+/// A json data file is a list of rows, each one a list of felts:
+/// ```json
+/// [[1, 2, 3], ["0x10", "0x20", "0x30"]]
+/// ```
+///
+/// A csv data file has one row per line, its felts being separated by commas. Empty lines and
+/// lines starting with `#` are ignored:
+/// ```csv
+/// # a, b, sum
+/// 1, 2, 3
+/// 0x10, 0x20, 0x30
+/// ```
+pub fn read_data_file(path: &Path) -> Result<DataRows, CalldataError> {
+	let data = fs::read_to_string(path)
+		.map_err(|err| CalldataError::FailedToReadFile(path.to_path_buf(), err))?;
+	if path.extension().map_or(false, |extension| extension == "csv") {
+		return data
+			.lines()
+			.map(str::trim)
+			.filter(|line| !line.is_empty() && !line.starts_with('#'))
+			.enumerate()
+			.map(|(index, line)| {
+				line.split(',')
+					.map(|felt| {
+						let felt = felt.trim();
+						parse_felt(&Value::String(felt.to_string())).ok_or_else(|| {
+							CalldataError::InvalidRowFelt(
+								felt.to_string(),
+								index,
+								path.to_path_buf(),
+							)
+						})
+					})
+					.collect()
+			})
+			.collect()
+	}
+
+	let rows: Vec<Value> = serde_json::from_str(&data)
+		.map_err(|err| CalldataError::InvalidFormat(path.to_path_buf(), err))?;
+	rows.into_iter()
+		.enumerate()
+		.map(|(index, row)| match row {
+			Value::Array(felts) => felts
+				.iter()
+				.map(|felt| {
+					parse_felt(felt).ok_or_else(|| {
+						CalldataError::InvalidRowFelt(felt.to_string(), index, path.to_path_buf())
+					})
+				})
+				.collect(),
+			_ => Err(CalldataError::NotARow(index, path.to_path_buf())),
+		})
+		.collect()
+}
+
+fn parse_felt(value: &Value) -> Option<BigInt> {
+	match value {
+		Value::Number(number) => number.to_string().parse().ok(),
+		Value::String(string) => match string.strip_prefix("0x") {
+			Some(hex) => BigInt::parse_bytes(hex.as_bytes(), 16),
+			None => string.parse().ok(),
+		},
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+	use uuid::Uuid;
+
+	#[test]
+	fn felts_are_parsed_from_numbers_and_strings() {
+		assert_eq!(Some(BigInt::from(42)), parse_felt(&json!(42)));
+		assert_eq!(Some(BigInt::from(0x123)), parse_felt(&json!("0x123")));
+		assert_eq!(Some(BigInt::from(-1)), parse_felt(&json!("-1")));
+		assert_eq!(None, parse_felt(&json!("foo")));
+		assert_eq!(None, parse_felt(&json!(1.5)));
+	}
+
+	#[test]
+	fn calldata_file_maps_entrypoints_to_their_arguments() {
+		let path = std::env::temp_dir().join(format!("{}.json", Uuid::new_v4()));
+		fs::write(&path, r#"{ "test_sum": [2, "0x3"] }"#).unwrap();
+
+		let calldata = read_calldata_file(path.to_str().unwrap()).unwrap();
+
+		assert_eq!(
+			Some(&vec![BigInt::from(2), BigInt::from(3)]),
+			calldata.get("test_sum")
+		);
+		fs::remove_file(path).unwrap();
+	}
+
+	#[test]
+	fn storage_file_maps_contracts_to_their_slots() {
+		let path = std::env::temp_dir().join(format!("{}.json", Uuid::new_v4()));
+		fs::write(&path, r#"{ "0x123": { "1": 42, "0x2": "0x10" } }"#).unwrap();
+
+		let storage = read_storage_file(path.to_str().unwrap()).unwrap();
+
+		assert_eq!(
+			SeedStorage::from([
+				((BigInt::from(0x123), BigInt::from(1)), BigInt::from(42)),
+				((BigInt::from(0x123), BigInt::from(2)), BigInt::from(0x10)),
+			]),
+			storage
+		);
+
+		fs::write(&path, r#"{ "0x123": [42] }"#).unwrap();
+		assert!(matches!(
+			read_storage_file(path.to_str().unwrap()),
+			Err(CalldataError::NotAStorageMap(..))
+		));
+		fs::remove_file(path).unwrap();
+	}
+
+	#[test]
+	fn data_files_are_read_as_rows_of_felts() {
+		let dir = std::env::temp_dir().join(Uuid::new_v4().to_string());
+		fs::create_dir_all(&dir).unwrap();
+		let cairo_file = dir.join("test_sum.cairo");
+		let expected = vec![
+			vec![BigInt::from(1), BigInt::from(2), BigInt::from(3)],
+			vec![BigInt::from(0x10), BigInt::from(0x20), BigInt::from(0x30)],
+		];
+
+		assert_eq!(None, data_file_path(&cairo_file, "test_sum"));
+
+		let csv = dir.join("test_sum.test_sum.csv");
+		fs::write(&csv, "# a, b, sum\n1, 2, 3\n\n0x10,0x20,0x30\n").unwrap();
+		assert_eq!(Some(csv.clone()), data_file_path(&cairo_file, "test_sum"));
+		assert_eq!(expected, read_data_file(&csv).unwrap());
+
+		let json = dir.join("test_sum.test_sum.json");
+		fs::write(&json, r#"[[1, 2, 3], ["0x10", "0x20", 48]]"#).unwrap();
+		assert_eq!(Some(json.clone()), data_file_path(&cairo_file, "test_sum"));
+		assert_eq!(expected, read_data_file(&json).unwrap());
+
+		fs::write(&json, r#"[[1, 2, 3], 4]"#).unwrap();
+		assert!(matches!(
+			read_data_file(&json),
+			Err(CalldataError::NotARow(1, _))
+		));
+		fs::remove_dir_all(dir).unwrap();
+	}
+}
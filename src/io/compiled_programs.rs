@@ -1,6 +1,12 @@
-use std::{fs, io, path::PathBuf};
+use std::{
+	collections::HashMap,
+	fs, io,
+	path::{Path, PathBuf},
+};
 
+use log::{debug, warn};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use thiserror::Error;
 
@@ -10,17 +16,44 @@ pub enum ListTestEntrypointsError {
 	FailedToReadCompiledProgram(#[from] io::Error),
 	#[error("Failed to parse the content of the compiled cairo program as json")]
 	InvalidCompiledProgramFormat(#[from] serde_json::Error),
+	#[error("Argument '{1}' of entrypoint '{0}' is not a felt")]
+	NonFeltArgument(String, String),
+	#[error("Failed to read the cairo file '{0}': {1}")]
+	FailedToReadCairoFile(String, io::Error),
+	#[error("Invalid directive '{0}' in the cairo file '{1}', expected `max_steps=<steps>`")]
+	InvalidDirective(String, String),
+}
+
+/// A test entrypoint, along with the tags of its leading comments
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestEntrypoint {
+	pub name: String,
+	pub tags: Vec<String>,
+	/// Maximum number of steps set by the directives of its file, if any
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub max_steps: Option<u64>,
+}
+
+/// Options set for a whole cairo file by its `cairo-foundry:` directive comments
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FileDirectives {
+	pub max_steps: Option<u64>,
 }
 
 /// Get the list of test entrypoint from a compiled cairo file.
-/// test entrypoint are function starting with "test_".
-/// The function will return a list of test entrypoint as `String` (ie: "test_function");
+/// test entrypoint are function starting with "test_" (or "fuzz_" for fuzzed tests),
+/// the "setUp" and "tearDown" lifecycle functions are listed as well.
+/// The tags of each entrypoint are parsed from the comments preceding it in the original cairo
+/// file (see `parse_entrypoint_tags`), and its maximum number of steps from the directives of
+/// the file (see `parse_file_directives`).
 ///
-/// return a vector of entrypoints
+/// return a vector of entrypoints, sorted by name
 pub fn list_test_entrypoints(
 	path_to_compiled_cairo_program: &PathBuf,
-) -> Result<Vec<String>, ListTestEntrypointsError> {
-	let re = Regex::new(r"__main__.(test_\w+)$").expect("Should be a valid regex");
+	path_to_cairo_file: &PathBuf,
+) -> Result<Vec<TestEntrypoint>, ListTestEntrypointsError> {
+	let re = Regex::new(r"__main__.((?:test|fuzz)_\w+|setUp|tearDown)$")
+		.expect("Should be a valid regex");
 	let data = fs::read_to_string(path_to_compiled_cairo_program)?;
 	let json = serde_json::from_str::<Value>(&data)?;
 	let mut test_entrypoints = Vec::new();
@@ -34,7 +67,7 @@ pub fn list_test_entrypoints(
 					// capture n-1 refers to the next to last match
 					// captures are denoted with () in regex
 					for capture in re.captures_iter(key) {
-						// regex __main__.(test_\w+)$ has 2 captures
+						// regex __main__.((?:test|fuzz)_\w+|setUp|tearDown)$ has 2 captures
 						// capture 0 is the whole match
 						// capture 1 is the first (and last) capture in this regex
 						test_entrypoints.push(capture[1].to_string());
@@ -42,8 +75,315 @@ pub fn list_test_entrypoints(
 				}
 			}
 		},
-		None => eprintln!("Compilation output does not contain identifiers"),
+		None => warn!("Compilation output does not contain identifiers"),
 	}
 
-	Ok(test_entrypoints)
+	let source = fs::read_to_string(path_to_cairo_file).map_err(|e| {
+		ListTestEntrypointsError::FailedToReadCairoFile(path_to_cairo_file.display().to_string(), e)
+	})?;
+	let mut tags = parse_entrypoint_tags(&source);
+	let directives = parse_file_directives(&source).map_err(|directive| {
+		ListTestEntrypointsError::InvalidDirective(
+			directive,
+			path_to_cairo_file.display().to_string(),
+		)
+	})?;
+
+	test_entrypoints.sort();
+	Ok(test_entrypoints
+		.into_iter()
+		.map(|name| TestEntrypoint {
+			tags: tags.remove(&name).unwrap_or_default(),
+			name,
+			max_steps: directives.max_steps,
+		})
+		.collect())
+}
+
+/// Extension of the discovery cache of a compiled file, written next to it
+/// (eg: `test_foo.entrypoints.json` for `test_foo.json`)
+pub const DISCOVERY_CACHE_EXTENSION: &str = "entrypoints.json";
+
+/// Entrypoints listed from a compiled file, stored next to it so that the next runs do not parse
+/// it again (see `read_discovery_cache`)
+#[derive(Debug, Serialize, Deserialize)]
+struct DiscoveryCache {
+	/// Hash of the cairo file the entrypoints (and their tags) have been listed from, as in its
+	/// cache entry
+	hash: String,
+	/// Size of the compiled file the entrypoints have been listed from, so that a compiled file
+	/// which has been overwritten (or truncated) is parsed again
+	compiled_size: u64,
+	entrypoints: Vec<TestEntrypoint>,
+}
+
+/// Path of the discovery cache of a compiled file
+pub fn discovery_cache_path(path_to_compiled: &Path) -> PathBuf {
+	path_to_compiled.with_extension(DISCOVERY_CACHE_EXTENSION)
+}
+
+fn compiled_size(path_to_compiled: &Path) -> Option<u64> {
+	fs::metadata(path_to_compiled).ok().map(|metadata| metadata.len())
+}
+
+/// Entrypoints of a compiled file, as listed by `list_test_entrypoints` for the cairo file of
+/// content `hash`.
+///
+/// return None if the discovery cache of the compiled file does not exist, cannot be read, or
+/// has been written for another version of the cairo file or of the compiled file
+pub fn read_discovery_cache(path_to_compiled: &Path, hash: &str) -> Option<Vec<TestEntrypoint>> {
+	let path = discovery_cache_path(path_to_compiled);
+	let data = fs::read_to_string(&path).ok()?;
+	let cache: DiscoveryCache = match serde_json::from_str(&data) {
+		Ok(cache) => cache,
+		Err(err) => {
+			warn!("ignoring invalid discovery cache {}: {err}", path.display());
+			return None
+		},
+	};
+	if cache.hash != hash || Some(cache.compiled_size) != compiled_size(path_to_compiled) {
+		debug!("stale discovery cache {}", path.display());
+		return None
+	}
+	Some(cache.entrypoints)
+}
+
+/// Write the discovery cache of a compiled file, the entrypoints having been listed for the
+/// cairo file of content `hash`.
+///
+/// The cache only saves the parsing of the compiled file in the next runs: failing to write it
+/// is not an error, it is only logged.
+pub fn write_discovery_cache(path_to_compiled: &Path, hash: &str, entrypoints: &[TestEntrypoint]) {
+	let path = discovery_cache_path(path_to_compiled);
+	let compiled_size = match compiled_size(path_to_compiled) {
+		Some(compiled_size) => compiled_size,
+		None => return,
+	};
+	let cache = DiscoveryCache {
+		hash: hash.to_string(),
+		compiled_size,
+		entrypoints: entrypoints.to_vec(),
+	};
+	// Written to a temporary file first, as the cache entries, so that a concurrent run never
+	// reads a truncated file
+	let tmp_path = path.with_extension("json.tmp");
+	let result = serde_json::to_string(&cache)
+		.map_err(io::Error::from)
+		.and_then(|data| fs::write(&tmp_path, data))
+		.and_then(|_| fs::rename(&tmp_path, &path));
+	if let Err(err) = result {
+		warn!(
+			"failed to write the discovery cache {}: {err}",
+			path.display()
+		);
+	}
+}
+
+/// Parse the directives of a cairo file.
+///
+/// Directives are `key=value` pairs in comments starting with `cairo-foundry:`, anywhere in the
+/// file, eg: `// cairo-foundry: max_steps=5000000`. They apply to every entrypoint of the file.
+///
+/// return the directives, or the first one which is not valid
+pub fn parse_file_directives(source: &str) -> Result<FileDirectives, String> {
+	let mut directives = FileDirectives::default();
+	for line in source.lines().map(str::trim) {
+		let opt_comment = line.strip_prefix("//").or_else(|| line.strip_prefix('#'));
+		let opt_directive =
+			opt_comment.and_then(|comment| comment.trim_start().strip_prefix("cairo-foundry:"));
+		for directive in opt_directive.into_iter().flat_map(str::split_whitespace) {
+			match directive.split_once('=') {
+				Some(("max_steps", max_steps)) =>
+					directives.max_steps =
+						Some(max_steps.parse().map_err(|_| directive.to_string())?),
+				_ => return Err(directive.to_string()),
+			}
+		}
+	}
+	Ok(directives)
+}
+
+/// Parse the tags of the functions of a cairo file.
+///
+/// Tags are words prefixed with `@` in the comments (`//` or `#`) directly preceding a function,
+/// eg: `// @slow @integration`. Anything else in those comments is ignored.
+///
+/// return a map from function names to their tags (functions without tags are omitted)
+pub fn parse_entrypoint_tags(source: &str) -> HashMap<String, Vec<String>> {
+	let tag_re = Regex::new(r"(?:^|\s)@(\w+)").expect("Should be a valid regex");
+	let func_re = Regex::new(r"^func\s+(\w+)").expect("Should be a valid regex");
+	let mut tags_by_function = HashMap::new();
+	let mut leading_tags = Vec::new();
+
+	for line in source.lines().map(str::trim) {
+		if let Some(comment) = line.strip_prefix("//").or_else(|| line.strip_prefix('#')) {
+			leading_tags.extend(tag_re.captures_iter(comment).map(|tag| tag[1].to_string()));
+			continue
+		}
+		if let Some(function) = func_re.captures(line) {
+			if !leading_tags.is_empty() {
+				tags_by_function.insert(function[1].to_string(), leading_tags.clone());
+			}
+		}
+		// Decorators (eg: `@view`) may sit between the comments and the function
+		if !line.starts_with('@') {
+			leading_tags.clear();
+		}
+	}
+
+	tags_by_function
+}
+
+/// Get the names of the explicit arguments of an entrypoint from a compiled cairo file,
+/// in the order they are expected on the stack.
+/// Only felt arguments are supported.
+///
+/// return a vector of argument names (empty if the entrypoint takes no argument)
+pub fn list_entrypoint_arguments(
+	path_to_compiled_cairo_program: &PathBuf,
+	entrypoint: &str,
+) -> Result<Vec<String>, ListTestEntrypointsError> {
+	let data = fs::read_to_string(path_to_compiled_cairo_program)?;
+	let json = serde_json::from_str::<Value>(&data)?;
+
+	let members =
+		match json["identifiers"][format!("__main__.{entrypoint}.Args")]["members"].as_object() {
+			Some(members) => members,
+			None => return Ok(Vec::new()),
+		};
+
+	let mut arguments = Vec::new();
+	for (name, member) in members {
+		if member["cairo_type"] != "felt" {
+			return Err(ListTestEntrypointsError::NonFeltArgument(
+				entrypoint.to_string(),
+				name.clone(),
+			))
+		}
+		arguments.push((member["offset"].as_u64().unwrap_or_default(), name.clone()));
+	}
+	arguments.sort();
+
+	Ok(arguments.into_iter().map(|(_, name)| name).collect())
+}
+
+/// Get the source location of each instruction of a compiled cairo file, from its debug info.
+///
+/// return a map from the pc of each instruction to its source file and line (empty if the
+/// program has no debug info)
+pub fn list_instruction_locations(
+	path_to_compiled_cairo_program: &PathBuf,
+) -> Result<HashMap<usize, (String, usize)>, ListTestEntrypointsError> {
+	let data = fs::read_to_string(path_to_compiled_cairo_program)?;
+	let json = serde_json::from_str::<Value>(&data)?;
+
+	let locations = match json["debug_info"]["instruction_locations"].as_object() {
+		Some(locations) => locations,
+		None => return Ok(HashMap::new()),
+	};
+
+	Ok(locations
+		.iter()
+		.filter_map(|(pc, location)| {
+			let pc = pc.parse().ok()?;
+			let file = location["inst"]["input_file"]["filename"].as_str()?;
+			let line = location["inst"]["start_line"].as_u64()?;
+			Some((pc, (file.to_string(), line as usize)))
+		})
+		.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn discovery_cache_is_only_read_for_the_same_files() {
+		let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+		fs::create_dir_all(&dir).unwrap();
+		let path_to_compiled = dir.join("test_foo.json");
+		fs::write(&path_to_compiled, "{}").unwrap();
+		let entrypoints = vec![TestEntrypoint {
+			name: String::from("test_foo"),
+			tags: vec![String::from("slow")],
+			max_steps: None,
+		}];
+
+		assert_eq!(None, read_discovery_cache(&path_to_compiled, "0x1"));
+
+		write_discovery_cache(&path_to_compiled, "0x1", &entrypoints);
+		assert_eq!(
+			dir.join("test_foo.entrypoints.json"),
+			discovery_cache_path(&path_to_compiled)
+		);
+		assert_eq!(
+			Some(entrypoints),
+			read_discovery_cache(&path_to_compiled, "0x1")
+		);
+		// The cairo file changed
+		assert_eq!(None, read_discovery_cache(&path_to_compiled, "0x2"));
+		// The compiled file changed
+		fs::write(&path_to_compiled, "{ }").unwrap();
+		assert_eq!(None, read_discovery_cache(&path_to_compiled, "0x1"));
+		fs::remove_dir_all(dir).unwrap();
+	}
+
+	#[test]
+	fn tags_are_parsed_from_leading_comments() {
+		let source = "\
+// @slow @integration
+func test_slow() {
+    return ();
+}
+
+// Not a tag: user@example
+# @legacy
+@view
+func test_legacy() {
+    return ();
+}
+
+// @orphan
+
+func test_untagged() {
+    return ();
+}
+";
+		let tags = parse_entrypoint_tags(source);
+
+		assert_eq!(
+			Some(&vec![String::from("slow"), String::from("integration")]),
+			tags.get("test_slow")
+		);
+		assert_eq!(Some(&vec![String::from("legacy")]), tags.get("test_legacy"));
+		assert_eq!(None, tags.get("test_untagged"));
+	}
+
+	#[test]
+	fn directives_are_parsed_from_comments() {
+		let source = "\
+// cairo-foundry: max_steps=5000000
+func test_long() {
+    return ();
+}
+";
+		assert_eq!(
+			Ok(FileDirectives {
+				max_steps: Some(5000000)
+			}),
+			parse_file_directives(source)
+		);
+		assert_eq!(
+			Ok(FileDirectives::default()),
+			parse_file_directives("// max_steps=5\n")
+		);
+		assert_eq!(
+			Err(String::from("max_steps=many")),
+			parse_file_directives("# cairo-foundry: max_steps=many\n")
+		);
+		assert_eq!(
+			Err(String::from("timeout=5")),
+			parse_file_directives("// cairo-foundry: timeout=5\n")
+		);
+	}
 }
@@ -0,0 +1,128 @@
+use clap::ValueEnum;
+use num_bigint::{BigInt, Sign};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Maximum length of a cairo short string, so that it fits in a single felt
+const SHORT_STRING_MAX_LEN: usize = 31;
+
+/// Representation of the felts shown in the output of the tests and in the assertion messages
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FeltFormat {
+	/// As a decimal integer (eg: `1633837924`)
+	#[default]
+	Decimal,
+	/// As a hexadecimal integer (eg: `0x61626364`)
+	Hex,
+	/// As a short string if its bytes are printable ascii characters (eg: `'abcd'`), as a decimal
+	/// integer otherwise
+	ShortString,
+	/// Every representation at once (eg: `1633837924 (0x61626364, 'abcd')`)
+	All,
+}
+
+impl FeltFormat {
+	/// Render a felt in this format
+	pub fn format(self, felt: &BigInt) -> String {
+		match self {
+			Self::Decimal => felt.to_string(),
+			Self::Hex => format!("{felt:#x}"),
+			Self::ShortString => match short_string(felt) {
+				Some(string) => format!("'{string}'"),
+				None => felt.to_string(),
+			},
+			Self::All => match short_string(felt) {
+				Some(string) => format!("{felt} ({felt:#x}, '{string}')"),
+				None => format!("{felt} ({felt:#x})"),
+			},
+		}
+	}
+
+	/// Render the lines of an output holding one felt per line (such as the output of the
+	/// `output` builtin) in this format, the other lines being kept as is
+	pub fn format_lines(self, output: &str) -> String {
+		output
+			.split_inclusive('\n')
+			.map(|line| {
+				let value = line.trim_end_matches('\n');
+				match value.parse::<BigInt>() {
+					Ok(felt) => line.replacen(value, &self.format(&felt), 1),
+					Err(_) => line.to_string(),
+				}
+			})
+			.collect()
+	}
+}
+
+/// Short string encoded by a felt, if it is made of printable ascii characters (spaces included)
+pub fn short_string(felt: &BigInt) -> Option<String> {
+	let (sign, bytes) = felt.to_bytes_be();
+	if sign != Sign::Plus || bytes.len() > SHORT_STRING_MAX_LEN {
+		return None
+	}
+	if !bytes.iter().all(|byte| (b' '..=b'~').contains(byte)) {
+		return None
+	}
+	String::from_utf8(bytes).ok()
+}
+
+static FELT_FORMAT: AtomicU8 = AtomicU8::new(FeltFormat::Decimal as u8);
+
+/// Select the format of the felts rendered with `format_felt`
+pub fn set_felt_format(format: FeltFormat) {
+	FELT_FORMAT.store(format as u8, Ordering::Relaxed);
+}
+
+/// Format selected with `set_felt_format`
+pub fn felt_format() -> FeltFormat {
+	match FELT_FORMAT.load(Ordering::Relaxed) {
+		format if format == FeltFormat::Hex as u8 => FeltFormat::Hex,
+		format if format == FeltFormat::ShortString as u8 => FeltFormat::ShortString,
+		format if format == FeltFormat::All as u8 => FeltFormat::All,
+		_ => FeltFormat::Decimal,
+	}
+}
+
+/// Render a felt in the format selected with `set_felt_format`
+pub fn format_felt(felt: &BigInt) -> String {
+	felt_format().format(felt)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rstest::rstest;
+
+	#[rstest]
+	#[case(FeltFormat::Decimal, 1633837924, "1633837924")]
+	#[case(FeltFormat::Hex, 1633837924, "0x61626364")]
+	#[case(FeltFormat::ShortString, 1633837924, "'abcd'")]
+	#[case(FeltFormat::ShortString, 7, "7")]
+	#[case(FeltFormat::All, 1633837924, "1633837924 (0x61626364, 'abcd')")]
+	#[case(FeltFormat::All, 0, "0 (0x0)")]
+	fn felts_are_formatted(#[case] format: FeltFormat, #[case] felt: i64, #[case] expected: &str) {
+		assert_eq!(expected, format.format(&BigInt::from(felt)));
+	}
+
+	#[test]
+	fn only_printable_felts_are_short_strings() {
+		assert_eq!(
+			Some(String::from("a b")),
+			short_string(&BigInt::from(0x612062))
+		);
+		assert_eq!(None, short_string(&BigInt::from(0)));
+		assert_eq!(None, short_string(&BigInt::from(0x610a62)));
+		assert_eq!(None, short_string(&BigInt::from(-0x61)));
+		assert_eq!(
+			None,
+			short_string(&(BigInt::from(0x61) << (8 * SHORT_STRING_MAX_LEN)))
+		);
+	}
+
+	#[test]
+	fn only_the_felt_lines_of_an_output_are_formatted() {
+		assert_eq!(
+			"0x2a\n1:3\n0x0\n",
+			FeltFormat::Hex.format_lines("42\n1:3\n0\n")
+		);
+	}
+}
@@ -1,2 +1,5 @@
+pub mod calldata;
 pub mod compiled_programs;
+pub mod felt_format;
+pub mod pattern;
 pub mod test_files;
@@ -0,0 +1,101 @@
+use regex::Regex;
+
+/// A pattern used to select names (test entrypoints, file paths, ...) given on the command line.
+///
+/// A pattern containing a glob wildcard (`*` or `?`) must match the whole name,
+/// any other pattern matches every name containing it.
+///
+/// # Examples
+///
+/// ```
+/// # use cairo_foundry::io::pattern::Pattern;
+/// assert!(Pattern::new("array_sum").is_match("test_array_sum_positive"));
+/// assert!(Pattern::new("test_*_positive").is_match("test_array_sum_positive"));
+/// assert!(!Pattern::new("test_*_positive").is_match("test_array_sum_negative"));
+/// ```
+#[derive(Debug, Clone)]
+pub enum Pattern {
+	Substring(String),
+	Glob(Regex),
+}
+
+impl Pattern {
+	pub fn new(pattern: &str) -> Self {
+		if pattern.contains(['*', '?']) {
+			Self::Glob(glob_to_regex(pattern))
+		} else {
+			Self::Substring(pattern.to_string())
+		}
+	}
+
+	pub fn is_match(&self, name: &str) -> bool {
+		match self {
+			Self::Substring(substring) => name.contains(substring.as_str()),
+			Self::Glob(regex) => regex.is_match(name),
+		}
+	}
+}
+
+/// Translate a glob into an anchored regex.
+///
+/// - `?` matches any single character except `/`
+/// - `*` matches any sequence of characters except `/`
+/// - `**` matches any sequence of characters, `**/` also matches zero directories
+///
+/// Every other character is matched literally.
+pub fn glob_to_regex(glob: &str) -> Regex {
+	let mut regex = String::from("^");
+	let mut chars = glob.chars().peekable();
+	while let Some(c) = chars.next() {
+		match c {
+			'*' if chars.peek() == Some(&'*') => {
+				chars.next();
+				if chars.peek() == Some(&'/') {
+					chars.next();
+					regex.push_str("(?:.*/)?");
+				} else {
+					regex.push_str(".*");
+				}
+			},
+			'*' => regex.push_str("[^/]*"),
+			'?' => regex.push_str("[^/]"),
+			c => regex.push_str(&regex::escape(&c.to_string())),
+		}
+	}
+	regex.push('$');
+	// Every literal character has been escaped, the generated regex is always valid
+	Regex::new(&regex).expect("Should be a valid regex")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn substring_pattern_matches_names_containing_it() {
+		let pattern = Pattern::new("sum");
+
+		assert!(pattern.is_match("test_array_sum"));
+		assert!(pattern.is_match("sum"));
+		assert!(!pattern.is_match("test_array_product"));
+	}
+
+	#[test]
+	fn glob_pattern_matches_whole_name() {
+		let pattern = Pattern::new("test_?_*");
+
+		assert!(pattern.is_match("test_a_sum"));
+		assert!(!pattern.is_match("test_ab_sum"));
+		assert!(!pattern.is_match("my_test_a_sum"));
+	}
+
+	#[test]
+	fn glob_handles_path_separators() {
+		assert!(glob_to_regex("*.cairo").is_match("test_foo.cairo"));
+		assert!(!glob_to_regex("*.cairo").is_match("dir/test_foo.cairo"));
+		assert!(glob_to_regex("**/*.cairo").is_match("test_foo.cairo"));
+		assert!(glob_to_regex("**/*.cairo").is_match("dir/nested/test_foo.cairo"));
+		assert!(glob_to_regex("vendor/**").is_match("vendor/lib/foo.cairo"));
+		assert!(!glob_to_regex("test.cairo").is_match("test_cairo"));
+	}
+}
@@ -1,33 +1,175 @@
-use std::path::{Path, PathBuf};
+use std::{
+	fs, io,
+	path::{Path, PathBuf},
+};
 
 use lazy_static::lazy_static;
 use regex::Regex;
 use thiserror::Error;
 use walkdir::WalkDir;
 
+use super::pattern::glob_to_regex;
+use crate::compile::cache::cache_dir;
+
+/// Name of the file listing the globs of the files and directories skipped by `list_test_files`,
+/// at the root of the project
+pub const IGNORE_FILE_NAME: &str = ".cairofoundryignore";
+
 lazy_static! {
-	static ref TEST_FILE_REGEX: Regex = Regex::new(r"^test_.*\.cairo$").unwrap();
+	/// Default test file naming convention: `test_*.cairo` or `*_test.cairo`
+	static ref TEST_FILE_REGEX: Regex = Regex::new(r"^(test_.*|.*_test)\.cairo$").unwrap();
 }
 
 #[derive(Debug, Error)]
 pub enum ListTestsFilesError {
 	#[error("Failed to walk directory '{0}'")]
 	WalkDir(String, #[source] walkdir::Error),
+	#[error("Failed to read the ignore file '{0}': {1}")]
+	IgnoreFile(String, io::Error),
+}
+
+/// Whether a glob matches the name of a path, or its path relative to `root` if the glob
+/// contains a `/`
+fn is_glob_match(glob: &(Regex, bool), path: &Path, root: &Path) -> bool {
+	match glob {
+		(regex, false) => regex.is_match(&path.file_name().unwrap_or_default().to_string_lossy()),
+		(regex, true) => path.strip_prefix(root).map_or(false, |relative_path| {
+			regex.is_match(&relative_path.to_string_lossy())
+		}),
+	}
+}
+
+/// A line of an ignore file
+#[derive(Debug)]
+struct IgnorePattern {
+	glob: (Regex, bool),
+	/// The pattern starts with a `!`: the paths it matches are not ignored
+	negated: bool,
+	/// The pattern ends with a `/`: it only matches directories
+	dir_only: bool,
+}
+
+/// Patterns of the `IGNORE_FILE_NAME` file of a directory, which follow the syntax of a
+/// `.gitignore` file:
+/// - blank lines and lines starting with `#` are skipped
+/// - a pattern is matched against the name of the paths, or against their path relative to the
+///   directory if it contains a `/` (a leading `/` being dropped), `*`, `?` and `**` being globs
+///   (see `glob_to_regex`)
+/// - a pattern ending with a `/` only matches directories
+/// - a pattern starting with a `!` re-includes the paths matched by the previous patterns, the last
+///   matching pattern of a path deciding whether it is ignored. As the ignored directories are not
+///   walked, the files they contain cannot be re-included.
+#[derive(Debug, Default)]
+struct IgnoreFile {
+	patterns: Vec<IgnorePattern>,
 }
 
-pub fn list_test_files(root: &Path) -> Result<Vec<PathBuf>, ListTestsFilesError> {
+impl IgnoreFile {
+	/// Read the ignore file of `root`, a missing file ignoring nothing
+	fn load(root: &Path) -> Result<Self, ListTestsFilesError> {
+		let path = root.join(IGNORE_FILE_NAME);
+		match fs::read_to_string(&path) {
+			Ok(content) => Ok(Self::parse(&content)),
+			Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+			Err(err) => Err(ListTestsFilesError::IgnoreFile(
+				path.display().to_string(),
+				err,
+			)),
+		}
+	}
+
+	fn parse(content: &str) -> Self {
+		let patterns = content
+			.lines()
+			.map(str::trim)
+			.filter(|line| !line.is_empty() && !line.starts_with('#'))
+			.map(|line| {
+				let (negated, line) = match line.strip_prefix('!') {
+					Some(line) => (true, line),
+					None => (false, line),
+				};
+				let (dir_only, line) = match line.strip_suffix('/') {
+					Some(line) => (true, line),
+					None => (false, line),
+				};
+				let anchored = line.contains('/');
+				let glob = line.strip_prefix('/').unwrap_or(line);
+				IgnorePattern {
+					glob: (glob_to_regex(glob), anchored),
+					negated,
+					dir_only,
+				}
+			})
+			.collect();
+		Self { patterns }
+	}
+
+	/// Whether the `path` (a directory if `is_dir`) contained in `root` is ignored
+	fn is_ignored(&self, path: &Path, is_dir: bool, root: &Path) -> bool {
+		self.patterns
+			.iter()
+			.rev()
+			.find(|pattern| {
+				(is_dir || !pattern.dir_only) && is_glob_match(&pattern.glob, path, root)
+			})
+			.map_or(false, |pattern| !pattern.negated)
+	}
+}
+
+/// List the test files contained in `root` and its subdirectories, sorted.
+///
+/// By default, test files are the ones named `test_*.cairo` or `*_test.cairo`.
+/// A `test_glob` can be given instead: it is matched against the name of the files, or against
+/// their path relative to `root` if it contains a `/` (ie: `tests/**/*.cairo`).
+///
+/// The files and directories matching one of the `excludes` globs (matched the same way) are
+/// skipped, as well as the ones ignored by the `.cairofoundryignore` file of `root` (see
+/// `IgnoreFile`), the hidden directories and the cache directory.
+pub fn list_test_files(
+	root: &Path,
+	test_glob: Option<&str>,
+	excludes: &[String],
+) -> Result<Vec<PathBuf>, ListTestsFilesError> {
+	let to_glob = |glob: &str| (glob_to_regex(glob), glob.contains('/'));
+	let test_glob = test_glob.map(to_glob);
+	let excludes: Vec<(Regex, bool)> = excludes.iter().map(|glob| to_glob(glob)).collect();
+	let ignore_file = IgnoreFile::load(root)?;
+	let is_test_file = |path: &Path| -> bool {
+		match &test_glob {
+			None =>
+				TEST_FILE_REGEX.is_match(&path.file_name().unwrap_or_default().to_string_lossy()),
+			Some(glob) => is_glob_match(glob, path, root),
+		}
+	};
+	let cache_dir = cache_dir().ok().and_then(|dir| dir.canonicalize().ok());
+	let is_excluded = |entry: &walkdir::DirEntry| -> bool {
+		let path = entry.path();
+		// The root itself is never excluded, even if it is hidden (ie: `.`)
+		if entry.depth() == 0 {
+			return false
+		}
+		if excludes.iter().any(|glob| is_glob_match(glob, path, root))
+			|| ignore_file.is_ignored(path, entry.file_type().is_dir(), root)
+		{
+			return true
+		}
+		entry.file_type().is_dir()
+			&& (entry.file_name().to_string_lossy().starts_with('.')
+				|| cache_dir.as_ref().map_or(false, |cache_dir| {
+					path.canonicalize().map_or(false, |path| path.starts_with(cache_dir))
+				}))
+	};
+
 	let mut test_files = WalkDir::new(root)
 		.into_iter()
+		.filter_entry(|entry| !is_excluded(entry))
 		.filter_map(|entry_result| match entry_result {
-			Ok(entry) => {
-				if entry.path().is_file()
-					&& TEST_FILE_REGEX.is_match(&entry.file_name().to_string_lossy())
-				{
+			Ok(entry) =>
+				if entry.path().is_file() && is_test_file(entry.path()) {
 					Some(Ok(entry.path().to_path_buf()))
 				} else {
 					None
-				}
-			},
+				},
 			Err(err) => Some(Err(ListTestsFilesError::WalkDir(
 				root.display().to_string(),
 				err,
@@ -48,7 +190,7 @@ mod tests {
 	fn list_test_files_recursively() {
 		let root = PathBuf::from("./test_cairo_contracts");
 
-		let result = list_test_files(&root);
+		let result = list_test_files(&root, None, &[]);
 
 		assert!(result.is_ok(), "{}", result.unwrap_err());
 		assert_eq!(
@@ -64,8 +206,150 @@ mod tests {
 	fn returns_error_in_case_of_failure() {
 		let root = PathBuf::from("invalid");
 
-		let result = list_test_files(&root);
+		let result = list_test_files(&root, None, &[]);
 
 		assert_matches!(result, Err(ListTestsFilesError::WalkDir(r, _)) if &r == "invalid");
 	}
+
+	#[test]
+	fn list_test_files_in_nested_directories() {
+		let root = PathBuf::from("./test_nested_dirs");
+
+		let result = list_test_files(&root, None, &[]).unwrap();
+
+		assert_eq!(
+			vec![
+				PathBuf::from("./test_nested_dirs/sub/deeper/test_deep.cairo"),
+				PathBuf::from("./test_nested_dirs/sub/math_test.cairo"),
+				PathBuf::from("./test_nested_dirs/test_root.cairo"),
+			],
+			result
+		)
+	}
+
+	#[test]
+	fn list_test_files_matching_glob() {
+		let root = PathBuf::from("./test_nested_dirs");
+
+		assert_eq!(
+			vec![PathBuf::from("./test_nested_dirs/sub/spec_custom.cairo")],
+			list_test_files(&root, Some("spec_*.cairo"), &[]).unwrap()
+		);
+		assert_eq!(
+			vec![
+				PathBuf::from("./test_nested_dirs/sub/deeper/test_deep.cairo"),
+				PathBuf::from("./test_nested_dirs/sub/math_test.cairo"),
+				PathBuf::from("./test_nested_dirs/sub/spec_custom.cairo"),
+			],
+			list_test_files(&root, Some("sub/**/*.cairo"), &[]).unwrap()
+		);
+	}
+
+	#[test]
+	fn excluded_files_and_directories_are_skipped() {
+		let root = PathBuf::from("./test_nested_dirs");
+
+		assert_eq!(
+			vec![PathBuf::from("./test_nested_dirs/test_root.cairo")],
+			list_test_files(&root, None, &[String::from("sub")]).unwrap()
+		);
+		assert_eq!(
+			vec![
+				PathBuf::from("./test_nested_dirs/sub/math_test.cairo"),
+				PathBuf::from("./test_nested_dirs/test_root.cairo"),
+			],
+			list_test_files(&root, None, &[String::from("sub/deeper")]).unwrap()
+		);
+		assert_eq!(
+			vec![PathBuf::from(
+				"./test_nested_dirs/sub/deeper/test_deep.cairo"
+			)],
+			list_test_files(
+				&root,
+				None,
+				&[
+					String::from("*_test.cairo"),
+					String::from("test_root.cairo")
+				]
+			)
+			.unwrap()
+		);
+	}
+
+	#[test]
+	fn ignore_file_skips_the_matching_paths() {
+		let root = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+		for dir in ["vendor/lib", "tests/fixtures", "tests/unit"] {
+			std::fs::create_dir_all(root.join(dir)).unwrap();
+		}
+		for file in [
+			"vendor/lib/test_vendored.cairo",
+			"tests/fixtures/test_fixture.cairo",
+			"tests/unit/test_slow.cairo",
+			"tests/unit/test_fast.cairo",
+			"tests/unit/test_kept_slow.cairo",
+			"test_root.cairo",
+		] {
+			std::fs::write(root.join(file), "").unwrap();
+		}
+		std::fs::write(
+			root.join(IGNORE_FILE_NAME),
+			"# dependencies
+vendor/
+
+/tests/**/fixtures
+*_slow.cairo
+!test_kept_slow.cairo
+",
+		)
+		.unwrap();
+
+		assert_eq!(
+			vec![
+				root.join("test_root.cairo"),
+				root.join("tests/unit/test_fast.cairo"),
+				root.join("tests/unit/test_kept_slow.cairo"),
+			],
+			list_test_files(&root, None, &[]).unwrap()
+		);
+		std::fs::remove_dir_all(root).unwrap();
+	}
+
+	#[test]
+	fn ignore_patterns_follow_the_gitignore_syntax() {
+		let root = Path::new("root");
+		let ignore_file = IgnoreFile::parse(
+			"build/
+!build
+*.cairo
+!test_*.cairo
+",
+		);
+
+		assert!(ignore_file.is_ignored(&root.join("lib.cairo"), false, root));
+		assert!(!ignore_file.is_ignored(&root.join("test_lib.cairo"), false, root));
+		// The last matching pattern decides
+		assert!(!ignore_file.is_ignored(&root.join("build"), true, root));
+
+		let ignore_file = IgnoreFile::parse(
+			"build/
+",
+		);
+		assert!(ignore_file.is_ignored(&root.join("sub/build"), true, root));
+		assert!(!ignore_file.is_ignored(&root.join("build"), false, root));
+	}
+
+	#[test]
+	fn hidden_directories_are_skipped() {
+		let root = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+		std::fs::create_dir_all(root.join(".git")).unwrap();
+		std::fs::write(root.join(".git").join("test_hidden.cairo"), "").unwrap();
+		std::fs::write(root.join("test_visible.cairo"), "").unwrap();
+
+		assert_eq!(
+			vec![root.join("test_visible.cairo")],
+			list_test_files(&root, None, &[]).unwrap()
+		);
+		std::fs::remove_dir_all(root).unwrap();
+	}
 }
@@ -3,6 +3,7 @@
 pub mod cairo_run;
 pub mod cli;
 pub mod compile;
+pub mod fuzz;
 pub mod hints;
 pub mod hooks;
 pub mod io;
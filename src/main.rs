@@ -1,20 +1,64 @@
-use cairo_foundry::cli::{
-	self,
-	commands::CommandExecution,
-	formatter::{self, Formatter},
+use cairo_foundry::{
+	cli::{
+		self,
+		commands::{CommandExecution, Commands, EXIT_CODE_ERROR},
+		config::Config,
+		formatter::{self, Formatter},
+		interrupt,
+	},
+	compile::cache,
+	io::felt_format,
 };
-use clap::Parser;
-use log::error;
+use clap::{CommandFactory, FromArgMatches};
+use log::{error, warn, LevelFilter};
+use std::{env, process::ExitCode};
 
-fn main() {
-	env_logger::init();
+fn main() -> ExitCode {
+	let matches = cli::Args::command().get_matches();
+	let mut cli = cli::Args::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
 
-	let cli = cli::Args::parse();
+	let mut logger = env_logger::Builder::from_default_env();
+	if let Some(log_level) = cli.log_level {
+		logger.filter_level(log_level);
+	}
+	if cli.verbose_cache {
+		logger.filter_module("cairo_foundry::compile::cache", LevelFilter::Info);
+	}
+	logger.init();
 
+	if let Err(error) = interrupt::install_handler() {
+		warn!("failed to handle Ctrl-C: {error}");
+	}
+	let config_dir = env::current_dir().unwrap_or_default();
+	let config = Config::load(&config_dir).and_then(|config| match &cli.config_profile {
+		Some(profile) => config.select_profile(profile),
+		None => Ok(config),
+	});
+	match config {
+		Ok(config) => cli.apply_config(config, &matches),
+		Err(error) => {
+			error!("{}", error);
+			return ExitCode::from(EXIT_CODE_ERROR)
+		},
+	}
+
+	// The progress line would be mixed with a json output redirected along with stderr
+	if let (Commands::Test(args), true) = (&mut cli.command, cli.json) {
+		args.no_progress = true;
+	}
 	let formatter = formatter::make(&cli);
+	cache::set_cache_dir(cli.cache_dir.clone());
+	cache::set_verbose_cache(cli.verbose_cache);
+	felt_format::set_felt_format(cli.felt_format);
 
 	match cli.command.exec() {
-		Ok(output) => print!("{}", formatter.format(&output)),
-		Err(error) => error!("{}", error),
-	};
+		Ok(output) => {
+			print!("{}", formatter.format(&output));
+			output.exit_code()
+		},
+		Err(error) => {
+			error!("{}", error);
+			ExitCode::from(EXIT_CODE_ERROR)
+		},
+	}
 }
@@ -0,0 +1,4 @@
+// Not a test file: it is never compiled, so its syntax error does not make any test fail
+func helper( {
+    return ();
+}
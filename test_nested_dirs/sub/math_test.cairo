@@ -0,0 +1,4 @@
+func test_math() {
+    assert 4 = 2 + 2;
+    return ();
+}
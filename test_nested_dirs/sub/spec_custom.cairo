@@ -0,0 +1,3 @@
+func test_spec() {
+    return ();
+}